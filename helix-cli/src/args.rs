@@ -52,6 +52,12 @@ pub enum CommandType {
     /// Save an instances data.mdb file
     Save(SaveCommand),
 
+    /// Rebuild the vector index of a vector type on a running instance
+    Rebuild(RebuildCommand),
+
+    /// Measure the recall and latency of the vector index of a vector type on a running instance
+    Evaluate(EvaluateCommand),
+
     /// Delete an instance and all its data
     Delete(DeleteCommand),
 
@@ -152,6 +158,62 @@ pub struct SaveCommand {
     pub output: Option<String>,
 }
 
+#[derive(Debug, Args)]
+#[clap(
+    name = "rebuild",
+    about = "Rebuild the vector index of a vector type with its current config or new params"
+)]
+pub struct RebuildCommand {
+    #[clap(help = "Cluster ID of the running instance")]
+    pub cluster: String,
+
+    #[clap(short, long, help = "The vector type to rebuild the index of")]
+    pub label: String,
+
+    #[clap(long, help = "Max number of links per vector to rebuild the index with")]
+    pub m: Option<usize>,
+
+    #[clap(long, help = "Size of the candidate list to rebuild the index with")]
+    pub ef_construction: Option<usize>,
+
+    #[clap(long, help = "Size of the candidate list of searches of the rebuilt index")]
+    pub ef_search: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+#[clap(
+    name = "evaluate",
+    about = "Measure the recall@k, search latency and graph statistics of a vector index"
+)]
+pub struct EvaluateCommand {
+    #[clap(help = "Cluster ID of the running instance")]
+    pub cluster: String,
+
+    #[clap(short, long, help = "The vector type to evaluate the index of")]
+    pub label: String,
+
+    #[clap(short, long, default_value_t = 10, help = "Number of neighbors to search for")]
+    pub k: usize,
+
+    #[clap(
+        short,
+        long,
+        default_value_t = 100,
+        help = "Number of stored vectors to search with"
+    )]
+    pub sample: usize,
+
+    #[clap(long, help = "Size of the candidate list of the searches")]
+    pub ef: Option<usize>,
+
+    #[clap(
+        short,
+        long,
+        help = "JSON file with an array of query vectors to search with instead"
+    )]
+    pub queries: Option<String>,
+}
+
 #[derive(Debug, Args)]
 #[clap(name = "delete", about = "Delete an cluster and its saved data")]
 pub struct DeleteCommand {
@@ -820,6 +820,164 @@ async fn run() -> ExitCode {
             }
         }
 
+        CommandType::Rebuild(command) => {
+            let instance_manager = InstanceManager::new().unwrap();
+            let iid = &command.cluster;
+
+            let instance = match instance_manager.get_instance(iid) {
+                Ok(Some(instance)) if instance.running => instance,
+                Ok(Some(_)) => {
+                    println!("{} {}", "Instance is not running".red().bold(), iid.red().bold());
+                    return ExitCode::FAILURE;
+                }
+                Ok(None) => {
+                    println!(
+                        "{} {}",
+                        "No Helix instance found with id".red().bold(),
+                        iid.red().bold()
+                    );
+                    return ExitCode::FAILURE;
+                }
+                Err(e) => {
+                    println!("{} {}", "Error:".red().bold(), e);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let mut sp = Spinner::new(
+                Spinners::Dots9,
+                format!("Rebuilding the vector index of {}", command.label),
+            );
+            let res = reqwest::Client::new()
+                .post(format!(
+                    "http://localhost:{}/rebuild-vector-index",
+                    instance.port
+                ))
+                .query(&[("label", &command.label)])
+                .query(&[
+                    ("m", command.m),
+                    ("ef_construction", command.ef_construction),
+                    ("ef_search", command.ef_search),
+                ])
+                .send()
+                .await;
+
+            match res {
+                Ok(r) if r.status().is_success() => {
+                    sp.stop_with_message(format!(
+                        "{} {}",
+                        "Rebuilt the vector index of".green().bold(),
+                        command.label.green().bold()
+                    ));
+                    if let Ok(body) = r.text().await {
+                        println!("{body}");
+                    }
+                }
+                Ok(r) => {
+                    sp.stop_with_message(
+                        "Failed to rebuild the vector index".red().bold().to_string(),
+                    );
+                    println!("{}", r.text().await.unwrap_or_default());
+                    return ExitCode::FAILURE;
+                }
+                Err(e) => {
+                    sp.stop_with_message("Error sending request:".red().bold().to_string());
+                    println!("{e}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+
+        CommandType::Evaluate(command) => {
+            let instance_manager = InstanceManager::new().unwrap();
+            let iid = &command.cluster;
+
+            let instance = match instance_manager.get_instance(iid) {
+                Ok(Some(instance)) if instance.running => instance,
+                Ok(Some(_)) => {
+                    println!("{} {}", "Instance is not running".red().bold(), iid.red().bold());
+                    return ExitCode::FAILURE;
+                }
+                Ok(None) => {
+                    println!(
+                        "{} {}",
+                        "No Helix instance found with id".red().bold(),
+                        iid.red().bold()
+                    );
+                    return ExitCode::FAILURE;
+                }
+                Err(e) => {
+                    println!("{} {}", "Error:".red().bold(), e);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let queries = match &command.queries {
+                Some(path) => match fs::read_to_string(path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|contents| {
+                        sonic_rs::from_str::<Vec<Vec<f64>>>(&contents).map_err(|e| e.to_string())
+                    }) {
+                    Ok(queries) => Some(queries),
+                    Err(e) => {
+                        println!("{} {}", "Error reading query vectors:".red().bold(), e);
+                        return ExitCode::FAILURE;
+                    }
+                },
+                None => None,
+            };
+            let body = json!({
+                "label": command.label,
+                "k": command.k,
+                "sample": command.sample,
+                "ef": command.ef,
+                "queries": queries,
+            });
+
+            let mut sp = Spinner::new(
+                Spinners::Dots9,
+                format!("Evaluating the vector index of {}", command.label),
+            );
+            let res = reqwest::Client::new()
+                .post(format!(
+                    "http://localhost:{}/evaluate-vector-index",
+                    instance.port
+                ))
+                .header("Content-Type", "application/json")
+                .body(body.to_string())
+                .send()
+                .await;
+
+            match res {
+                Ok(r) if r.status().is_success() => {
+                    sp.stop_with_message(format!(
+                        "{} {}",
+                        "Evaluated the vector index of".green().bold(),
+                        command.label.green().bold()
+                    ));
+                    let body = r.text().await.unwrap_or_default();
+                    match sonic_rs::from_str::<sonic_rs::Value>(&body)
+                        .and_then(|value| sonic_rs::to_string_pretty(&value))
+                    {
+                        Ok(pretty) => println!("{pretty}"),
+                        Err(_) => println!("{body}"),
+                    }
+                }
+                Ok(r) => {
+                    sp.stop_with_message(
+                        "Failed to evaluate the vector index".red().bold().to_string(),
+                    );
+                    println!("{}", r.text().await.unwrap_or_default());
+                    return ExitCode::FAILURE;
+                }
+                Err(e) => {
+                    sp.stop_with_message("Error sending request:".red().bold().to_string());
+                    println!("{e}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+
         CommandType::Delete(command) => {
             let instance_manager = InstanceManager::new().unwrap();
             let iid = &command.cluster;
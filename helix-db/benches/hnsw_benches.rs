@@ -3,11 +3,17 @@
 mod tests {
     use heed3::{Env, EnvOpenOptions, RoTxn};
     use helix_db::{
-        helix_engine::vector_core::{
-            hnsw::HNSW,
-            vector::HVector,
-            vector_core::{HNSWConfig, VectorCore},
+        helix_engine::{
+            traversal_core::config::VectorIndex,
+            vector_core::{
+                hnsw::HNSW,
+                vector::HVector,
+                vector_core::{HNSWConfig, SearchParams, VectorCore},
+                vector_distance::DistanceMetric,
+                vector_encoding::VectorEncoding,
+            },
         },
+        protocol::value::Value,
         utils::tqdm::tqdm,
     };
     use polars::prelude::*;
@@ -84,7 +90,7 @@ mod tests {
                                 .iter()
                                 .filter_map(|base_vec| {
                                     query_hvector
-                                        .distance_to(base_vec)
+                                        .distance_to(base_vec, DistanceMetric::Cosine)
                                         .map(|dist| (base_vec.id.clone(), dist))
                                         .ok()
                                 })
@@ -249,7 +255,7 @@ mod tests {
         let mut total_search_time = std::time::Duration::from_secs(0);
         for ((_, query), gt) in query_vectors.iter().zip(ground_truths.iter()) {
             let start_time = Instant::now();
-            let results = index.search::<Filter>(&txn, query, k, None, false).unwrap();
+            let results = index.search::<Filter>(&txn, query, k, None, false, SearchParams::default()).unwrap();
             let search_duration = start_time.elapsed();
             total_search_time += search_duration;
 
@@ -354,7 +360,7 @@ mod tests {
         let mut total_search_time = std::time::Duration::from_secs(0);
         for (qid, query) in query_vectors.iter() {
             let start_time = Instant::now();
-            let results = index.search::<Filter>(&txn, query, k, "vector", None, false).unwrap();
+            let results = index.search::<Filter>(&txn, query, k, "vector", None, false, SearchParams::default()).unwrap();
             let search_duration = start_time.elapsed();
             total_search_time += search_duration;
 
@@ -397,6 +403,95 @@ mod tests {
         );
         assert!(total_recall >= 0.8, "recall not high enough!");
     }
+
+    /// Compares the recall and stored size of each vector encoding on the same vectors.
+    /// The size of binary vectors includes the int8 copies they are rescored with,
+    /// so they are expected to take a little more space than int8 ones.
+    #[test]
+    fn bench_hnsw_encodings() {
+        let n_base = 10_000;
+        let n_query = 1_000;
+        let k = 10;
+        let mut vectors = load_dbpedia_vectors(n_base).unwrap();
+
+        let mut rng = rand::rng();
+        vectors.shuffle(&mut rng);
+
+        let base_vectors = &vectors[..n_base - n_query];
+        let query_vectors = vectors[n_base - n_query..]
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (i + 1, x.clone()))
+            .collect::<Vec<(usize, Vec<f64>)>>();
+
+        for encoding in VectorEncoding::ALL {
+            let label = encoding.as_str();
+            let env = setup_temp_env();
+            let mut txn = env.write_txn().unwrap();
+            let mut index =
+                VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+            index
+                .set_indices(
+                    &mut txn,
+                    &[VectorIndex::new(label, DistanceMetric::Cosine).with_encoding(encoding)],
+                )
+                .unwrap();
+
+            let fields = Some(vec![("label".to_string(), Value::String(label.to_string()))]);
+            let start_time = Instant::now();
+            let base_all_vectors = base_vectors
+                .iter()
+                .map(|data| index.insert::<Filter>(&mut txn, data, fields.clone()).unwrap())
+                .collect::<Vec<HVector>>();
+            let insertion_time = start_time.elapsed();
+            txn.commit().unwrap();
+
+            let txn = env.read_txn().unwrap();
+            // vectors and the int8 copies binary vectors are rescored with
+            let stored_bytes: usize = [b"v:".as_slice(), b"r:".as_slice()]
+                .iter()
+                .flat_map(|prefix| index.vectors_db.prefix_iter(&txn, prefix).unwrap())
+                .map(|result| result.unwrap().1.len())
+                .sum();
+
+            let ground_truths = calc_ground_truths(base_all_vectors, &query_vectors, k);
+
+            let mut total_recall = 0.0;
+            let mut total_search_time = std::time::Duration::from_secs(0);
+            for (qid, query) in query_vectors.iter() {
+                let start_time = Instant::now();
+                let results = index.search::<Filter>(&txn, query, k, label, None, false, SearchParams::default()).unwrap();
+                total_search_time += start_time.elapsed();
+
+                let result_indices = results
+                    .into_iter()
+                    .map(|hvec| hvec.get_id())
+                    .collect::<HashSet<u128>>();
+                let gt_indices = ground_truths
+                    .get(qid)
+                    .unwrap()
+                    .iter()
+                    .copied()
+                    .collect::<HashSet<u128>>();
+
+                total_recall += result_indices.intersection(&gt_indices).count() as f64
+                    / gt_indices.len() as f64;
+            }
+
+            println!(
+                "{}: avg. recall: {:.4?}, stored: {:.2} MB{}, insertion: {:.2?}, avg. search: {:.2?} ms",
+                encoding,
+                total_recall / n_query as f64,
+                stored_bytes as f64 / (1024.0 * 1024.0),
+                match encoding {
+                    VectorEncoding::Binary => " (with int8 rescore copies)",
+                    _ => "",
+                },
+                insertion_time,
+                total_search_time.as_millis() as f64 / n_query as f64,
+            );
+        }
+    }
 }
 
 // TODO: memory benchmark (only the hnsw index ofc)
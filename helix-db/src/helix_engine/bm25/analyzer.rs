@@ -0,0 +1,385 @@
+use crate::helix_engine::types::GraphError;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Length of the character grams of the `ngram` analyzer
+pub const NGRAM_SIZE: usize = 3;
+
+/// Words the `english` analyzer leaves out, too common to tell documents apart
+const ENGLISH_STOP_WORDS: [&str; 33] = [
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is", "it",
+    "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there", "these",
+    "they", "this", "to", "was", "will", "with",
+];
+
+/// How the text of a field is split into the terms it is indexed and searched by,
+/// declared with `TEXT(analyzer)` in the schema.
+///
+/// The same analyzer runs over the text of a field when it is indexed and over the
+/// queries searching it, so the terms of both line up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TextAnalyzer {
+    /// Lowercased words, split on anything that is not a letter or digit
+    #[default]
+    Standard,
+    /// Standard words without English stop words, reduced to their Porter stems
+    English,
+    /// Words split on whitespace only, kept as written
+    Whitespace,
+    /// The overlapping `NGRAM_SIZE` character grams of each standard word,
+    /// matching parts of words
+    NGram,
+    /// Standard words, with runs of Chinese, Japanese and Korean characters,
+    /// which have no spaces between words, split into overlapping bigrams
+    CjkBigram,
+}
+
+impl TextAnalyzer {
+    pub const ALL: [TextAnalyzer; 5] = [
+        TextAnalyzer::Standard,
+        TextAnalyzer::English,
+        TextAnalyzer::Whitespace,
+        TextAnalyzer::NGram,
+        TextAnalyzer::CjkBigram,
+    ];
+
+    /// Name of the analyzer in the schema
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextAnalyzer::Standard => "standard",
+            TextAnalyzer::English => "english",
+            TextAnalyzer::Whitespace => "whitespace",
+            TextAnalyzer::NGram => "ngram",
+            TextAnalyzer::CjkBigram => "cjk",
+        }
+    }
+
+    /// Splits the text into its terms, in order and with repeats
+    pub fn analyze(&self, text: &str) -> Vec<String> {
+        match self {
+            TextAnalyzer::Standard => standard_words(text).collect(),
+            TextAnalyzer::English => standard_words(text)
+                .filter(|word| !ENGLISH_STOP_WORDS.contains(&word.as_str()))
+                .map(|word| porter_stem(&word))
+                .collect(),
+            TextAnalyzer::Whitespace => text.split_whitespace().map(str::to_string).collect(),
+            TextAnalyzer::NGram => standard_words(text)
+                .flat_map(|word| {
+                    let chars = word.chars().collect::<Vec<_>>();
+                    match chars.len() <= NGRAM_SIZE {
+                        true => vec![word],
+                        false => chars
+                            .windows(NGRAM_SIZE)
+                            .map(|gram| gram.iter().collect())
+                            .collect(),
+                    }
+                })
+                .collect(),
+            TextAnalyzer::CjkBigram => standard_words(text).flat_map(cjk_bigrams).collect(),
+        }
+    }
+}
+
+impl fmt::Display for TextAnalyzer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for TextAnalyzer {
+    type Err = GraphError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TextAnalyzer::ALL
+            .into_iter()
+            .find(|analyzer| analyzer.as_str() == s)
+            .ok_or_else(|| GraphError::New(format!("unknown text analyzer {s}")))
+    }
+}
+
+fn standard_words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{11FF}' // hangul jamo
+            | '\u{3040}'..='\u{30FF}' // hiragana and katakana
+            | '\u{3130}'..='\u{318F}' // hangul compatibility jamo
+            | '\u{3400}'..='\u{4DBF}' // cjk extension a
+            | '\u{4E00}'..='\u{9FFF}' // cjk unified ideographs
+            | '\u{AC00}'..='\u{D7AF}' // hangul syllables
+            | '\u{F900}'..='\u{FAFF}' // cjk compatibility ideographs
+            | '\u{20000}'..='\u{2A6DF}' // cjk extension b
+    )
+}
+
+/// Splits the runs of cjk characters in a word into overlapping bigrams,
+/// keeping a lone cjk character and the other runs whole
+fn cjk_bigrams(word: String) -> Vec<String> {
+    if !word.chars().any(is_cjk) {
+        return vec![word];
+    }
+    let chars = word.chars().collect::<Vec<_>>();
+    chars
+        .chunk_by(|a, b| is_cjk(*a) == is_cjk(*b))
+        .flat_map(|run| match is_cjk(run[0]) && run.len() > 1 {
+            true => run.windows(2).map(|pair| pair.iter().collect()).collect(),
+            false => vec![run.iter().collect()],
+        })
+        .collect()
+}
+
+/// Reduces an English word to its stem with the Porter algorithm,
+/// e.g. `running`, `runs` and `run` all to `run`.
+///
+/// Words of two letters or less, and words that are not lowercase ASCII, are left as they are.
+pub fn porter_stem(word: &str) -> String {
+    if word.len() <= 2 || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+        return word.to_string();
+    }
+    let mut stemmer = Stemmer {
+        b: word.as_bytes().to_vec(),
+        j: 0,
+    };
+    stemmer.step1ab();
+    if stemmer.b.len() > 1 {
+        stemmer.step1c();
+        stemmer.step2();
+        stemmer.step3();
+        stemmer.step4();
+        stemmer.step5();
+    }
+    String::from_utf8(stemmer.b).unwrap_or_else(|_| word.to_string())
+}
+
+/// The word being stemmed, always ending at its last letter, and the end of its stem
+/// before the suffix last matched by `ends` (`-1` when the suffix is the whole word)
+struct Stemmer {
+    b: Vec<u8>,
+    j: isize,
+}
+
+impl Stemmer {
+    fn k(&self) -> isize {
+        self.b.len() as isize - 1
+    }
+
+    fn at(&self, i: isize) -> u8 {
+        self.b[i as usize]
+    }
+
+    fn is_consonant(&self, i: isize) -> bool {
+        match self.at(i) {
+            b'a' | b'e' | b'i' | b'o' | b'u' => false,
+            b'y' => i == 0 || !self.is_consonant(i - 1),
+            _ => true,
+        }
+    }
+
+    /// The number of vowel-consonant sequences in the stem
+    fn measure(&self) -> usize {
+        let mut n = 0;
+        let mut i = 0;
+        loop {
+            if i > self.j {
+                return n;
+            }
+            if !self.is_consonant(i) {
+                break;
+            }
+            i += 1;
+        }
+        i += 1;
+        loop {
+            loop {
+                if i > self.j {
+                    return n;
+                }
+                if self.is_consonant(i) {
+                    break;
+                }
+                i += 1;
+            }
+            i += 1;
+            n += 1;
+            loop {
+                if i > self.j {
+                    return n;
+                }
+                if !self.is_consonant(i) {
+                    break;
+                }
+                i += 1;
+            }
+            i += 1;
+        }
+    }
+
+    fn vowel_in_stem(&self) -> bool {
+        (0..=self.j).any(|i| !self.is_consonant(i))
+    }
+
+    fn double_consonant(&self, i: isize) -> bool {
+        i >= 1 && self.at(i) == self.at(i - 1) && self.is_consonant(i)
+    }
+
+    /// Whether the letters ending at `i` are consonant, vowel, consonant,
+    /// the last not being `w`, `x` or `y`, as in `hop`
+    fn cvc(&self, i: isize) -> bool {
+        i >= 2
+            && self.is_consonant(i)
+            && !self.is_consonant(i - 1)
+            && self.is_consonant(i - 2)
+            && !matches!(self.at(i), b'w' | b'x' | b'y')
+    }
+
+    fn ends(&mut self, suffix: &str) -> bool {
+        if !self.b.ends_with(suffix.as_bytes()) {
+            return false;
+        }
+        self.j = self.k() - suffix.len() as isize;
+        true
+    }
+
+    fn set_to(&mut self, suffix: &str) {
+        self.b.truncate((self.j + 1) as usize);
+        self.b.extend_from_slice(suffix.as_bytes());
+    }
+
+    /// Replaces the suffix matched by `ends` when the stem has a vowel-consonant sequence
+    fn replace(&mut self, suffix: &str) {
+        if self.measure() > 0 {
+            self.set_to(suffix);
+        }
+    }
+
+    /// Plurals and `-ed` or `-ing`, e.g. `caresses` to `caress` and `hopping` to `hop`
+    fn step1ab(&mut self) {
+        if self.b.ends_with(b"s") {
+            if self.ends("sses") {
+                self.b.truncate(self.b.len() - 2);
+            } else if self.ends("ies") {
+                self.set_to("i");
+            } else if self.at(self.k() - 1) != b's' {
+                self.b.pop();
+            }
+        }
+        if self.ends("eed") {
+            if self.measure() > 0 {
+                self.b.pop();
+            }
+        } else if (self.ends("ed") || self.ends("ing")) && self.vowel_in_stem() {
+            self.b.truncate((self.j + 1) as usize);
+            if self.ends("at") {
+                self.set_to("ate");
+            } else if self.ends("bl") {
+                self.set_to("ble");
+            } else if self.ends("iz") {
+                self.set_to("ize");
+            } else if self.double_consonant(self.k()) {
+                if !matches!(self.at(self.k()), b'l' | b's' | b'z') {
+                    self.b.pop();
+                }
+            } else {
+                self.j = self.k();
+                if self.measure() == 1 && self.cvc(self.k()) {
+                    self.b.push(b'e');
+                }
+            }
+        }
+    }
+
+    /// A final `y` to `i` when there is another vowel, e.g. `happy` to `happi`
+    fn step1c(&mut self) {
+        if self.ends("y") && self.vowel_in_stem() {
+            self.b.pop();
+            self.b.push(b'i');
+        }
+    }
+
+    /// Double suffixes to single ones, e.g. `relational` to `relate`
+    fn step2(&mut self) {
+        const RULES: [(&str, &str); 21] = [
+            ("ational", "ate"),
+            ("tional", "tion"),
+            ("enci", "ence"),
+            ("anci", "ance"),
+            ("izer", "ize"),
+            ("bli", "ble"),
+            ("alli", "al"),
+            ("entli", "ent"),
+            ("eli", "e"),
+            ("ousli", "ous"),
+            ("ization", "ize"),
+            ("ation", "ate"),
+            ("ator", "ate"),
+            ("alism", "al"),
+            ("iveness", "ive"),
+            ("fulness", "ful"),
+            ("ousness", "ous"),
+            ("aliti", "al"),
+            ("iviti", "ive"),
+            ("biliti", "ble"),
+            ("logi", "log"),
+        ];
+        self.replace_first(&RULES);
+    }
+
+    /// `-ic-`, `-full`, `-ness` and the like, e.g. `hopeful` to `hope`
+    fn step3(&mut self) {
+        const RULES: [(&str, &str); 7] = [
+            ("icate", "ic"),
+            ("ative", ""),
+            ("alize", "al"),
+            ("iciti", "ic"),
+            ("ical", "ic"),
+            ("ful", ""),
+            ("ness", ""),
+        ];
+        self.replace_first(&RULES);
+    }
+
+    /// Replaces the first of the suffixes the word ends with, if any
+    fn replace_first(&mut self, rules: &[(&str, &str)]) {
+        if let Some((_, to)) = rules.iter().find(|(from, _)| self.ends(from)) {
+            self.replace(to);
+        }
+    }
+
+    /// `-ant`, `-ence` and the like when the stem is long enough, e.g. `adjustment` to `adjust`
+    fn step4(&mut self) {
+        const SUFFIXES: [&str; 19] = [
+            "al", "ance", "ence", "er", "ic", "able", "ible", "ant", "ement", "ment", "ent", "ion",
+            "ou", "ism", "ate", "iti", "ous", "ive", "ize",
+        ];
+        let Some(suffix) = SUFFIXES.iter().find(|suffix| self.ends(suffix)) else {
+            return;
+        };
+        // `-ion` only goes after an `s` or a `t`, as in `adoption`
+        if *suffix == "ion" && (self.j < 0 || !matches!(self.at(self.j), b's' | b't')) {
+            return;
+        }
+        if self.measure() > 1 {
+            self.b.truncate((self.j + 1) as usize);
+        }
+    }
+
+    /// A final `e` or double `l` when the stem is long enough, e.g. `probate` to `probat`
+    fn step5(&mut self) {
+        self.j = self.k();
+        if self.at(self.k()) == b'e' {
+            let m = self.measure();
+            if m > 1 || (m == 1 && !self.cvc(self.k() - 1)) {
+                self.b.pop();
+            }
+        }
+        self.j = self.k();
+        if self.at(self.k()) == b'l' && self.double_consonant(self.k()) && self.measure() > 1 {
+            self.b.pop();
+        }
+    }
+}
@@ -1,23 +1,48 @@
 use crate::{
     helix_engine::{
+        bm25::{
+            analyzer::TextAnalyzer,
+            query::{
+                FUZZY_PREFIX_LENGTH, MAX_EXPANSIONS, Occur, QueryClause, QueryNode, TermExpansion, edit_distance,
+                parse_query, phrase_slop,
+            },
+        },
         storage_core::HelixGraphStorage,
+        traversal_core::config::TextField,
         types::GraphError,
-        vector_core::{hnsw::HNSW, vector::HVector},
+        vector_core::{
+            hnsw::HNSW, sparse_vector::SparseVector, vector::HVector, vector_core::SearchParams,
+        },
     },
     protocol::value::Value,
+    utils::filterable::Filterable,
     debug_println,
 };
 
 use heed3::{types::*, Database, Env, RoTxn, RwTxn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 use tokio::task;
 
 const DB_BM25_INVERTED_INDEX: &str = "bm25_inverted_index"; // term -> list of (doc_id, tf)
 const DB_BM25_DOC_LENGTHS: &str = "bm25_doc_lengths"; // doc_id -> document length
 const DB_BM25_TERM_FREQUENCIES: &str = "bm25_term_frequencies"; // term -> document frequency
 const DB_BM25_METADATA: &str = "bm25_metadata"; // stores total docs, avgdl, etc.
+const DB_BM25_POSITIONS: &str = "bm25_positions"; // term + doc_id -> positions of the term
+const DB_BM25_FIELD_FREQUENCIES: &str = "bm25_field_frequencies"; // term + doc_id -> tf per field
 pub const METADATA_KEY: &[u8] = b"metadata";
+/// The text fields the index was last built from, to tell when they change
+pub const TEXT_FIELDS_KEY: &[u8] = b"text_fields";
+/// Whether the index was last built with the positions of its terms
+pub const POSITIONS_KEY: &[u8] = b"positions";
+/// Whether the index was built with the frequencies of its terms in each text field
+pub const FIELD_FREQUENCIES_KEY: &[u8] = b"field_frequencies";
+/// Distance between the positions of the first terms of consecutive text fields of a doc,
+/// so that a phrase never spans two fields
+pub const FIELD_POSITION_GAP: u32 = 1 << 20;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct BM25Metadata {
@@ -39,6 +64,15 @@ pub trait BM25 {
 
     fn insert_doc(&self, txn: &mut RwTxn, doc_id: u128, doc: &str) -> Result<(), GraphError>;
 
+    /// Inserts a doc already split into the terms of each of its text fields,
+    /// e.g. by the analyzers of the fields
+    fn insert_fields(
+        &self,
+        txn: &mut RwTxn,
+        doc_id: u128,
+        fields: Vec<Vec<String>>,
+    ) -> Result<(), GraphError>;
+
     fn delete_doc(&self, txn: &mut RwTxn, doc_id: u128) -> Result<(), GraphError>;
 
     fn update_doc(&self, txn: &mut RwTxn, doc_id: u128, doc: &str) -> Result<(), GraphError>;
@@ -53,12 +87,25 @@ pub trait BM25 {
         avgdl: f64,      // average document length
     ) -> f32;
 
+    /// Search for the top docs of any label with a query, as parsed by `parse_query`
     fn search(
         &self,
         txn: &RoTxn,
         query: &str,
         limit: usize,
     ) -> Result<Vec<(u128, f32)>, GraphError>;
+
+    /// Search for the top docs with the query terms among those `filter` keeps,
+    /// e.g. the items of one label
+    fn search_terms<F>(
+        &self,
+        txn: &RoTxn,
+        query_terms: &[String],
+        limit: usize,
+        filter: F,
+    ) -> Result<Vec<(u128, f32)>, GraphError>
+    where
+        F: Fn(u128) -> bool;
 }
 
 pub struct HBM25Config {
@@ -67,11 +114,32 @@ pub struct HBM25Config {
     pub doc_lengths_db: Database<U128<heed3::byteorder::BE>, U32<heed3::byteorder::BE>>,
     pub term_frequencies_db: Database<Bytes, U32<heed3::byteorder::BE>>,
     pub metadata_db: Database<Bytes, Bytes>,
+    /// Positions of each term in each doc, kept apart from the postings as lmdb caps the size
+    /// of a duplicate value, and only written when `positions` is set
+    pub positions_db: Database<Bytes, Bytes>,
+    /// Frequency of each term in each text field of each doc, for field queries,
+    /// only written when text fields are declared
+    pub field_frequencies_db: Database<Bytes, Bytes>,
+    /// The fields indexed for each label with their analyzers,
+    /// or `None` to index every field of every label
+    text_fields: Option<HashMap<String, Vec<(String, TextAnalyzer)>>>,
+    /// Whether the positions of terms are stored, for phrase and proximity queries
+    positions: bool,
     k1: f64,
     b: f64,
 }
 
 impl HBM25Config {
+    /// Names of the lmdb dbs (tables) the index opens
+    pub const DB_NAMES: [&str; 6] = [
+        DB_BM25_INVERTED_INDEX,
+        DB_BM25_DOC_LENGTHS,
+        DB_BM25_TERM_FREQUENCIES,
+        DB_BM25_METADATA,
+        DB_BM25_POSITIONS,
+        DB_BM25_FIELD_FREQUENCIES,
+    ];
+
     pub fn new(graph_env: &Env, wtxn: &mut RwTxn) -> Result<HBM25Config, GraphError> {
         let inverted_index_db: Database<Bytes, Bytes> = graph_env
             .database_options()
@@ -99,16 +167,648 @@ impl HBM25Config {
             .name(DB_BM25_METADATA)
             .create(wtxn)?;
 
+        let positions_db: Database<Bytes, Bytes> = graph_env
+            .database_options()
+            .types::<Bytes, Bytes>()
+            .name(DB_BM25_POSITIONS)
+            .create(wtxn)?;
+
+        let field_frequencies_db: Database<Bytes, Bytes> = graph_env
+            .database_options()
+            .types::<Bytes, Bytes>()
+            .name(DB_BM25_FIELD_FREQUENCIES)
+            .create(wtxn)?;
+
         Ok(HBM25Config {
             graph_env: graph_env.clone(),
             inverted_index_db,
             doc_lengths_db,
             term_frequencies_db,
             metadata_db,
+            positions_db,
+            field_frequencies_db,
+            text_fields: None,
+            positions: false,
             k1: 1.2,
             b: 0.75,
         })
     }
+
+    /// Only indexes the declared text fields, rather than every field, when they are given
+    pub fn with_text_fields(mut self, text_fields: Option<&[TextField]>) -> Self {
+        self.text_fields = text_fields.map(|text_fields| {
+            text_fields
+                .iter()
+                .fold(HashMap::new(), |mut fields, text_field| {
+                    fields
+                        .entry(text_field.label.clone())
+                        .or_insert_with(Vec::new)
+                        .push((text_field.field.clone(), text_field.analyzer));
+                    fields
+                })
+        });
+        self
+    }
+
+    /// Stores the positions of terms, for phrase and proximity queries, when set
+    pub fn with_positions(mut self, positions: bool) -> Self {
+        self.positions = positions;
+        self
+    }
+
+    /// The terms a node or vector of the label is indexed under, those of each text field
+    /// split by its analyzer, `None` if it has none
+    pub fn doc_fields<P: BM25Flatten + ?Sized>(
+        &self,
+        label: &str,
+        properties: &P,
+    ) -> Option<Vec<Vec<String>>> {
+        match &self.text_fields {
+            Some(text_fields) => text_fields
+                .get(label)
+                .map(|fields| {
+                    fields
+                        .iter()
+                        .map(|(field, analyzer)| match properties.bm25_field(field) {
+                            Some(value) => analyzer.analyze(&value.to_string()),
+                            None => Vec::new(),
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .filter(|fields| fields.iter().any(|terms| !terms.is_empty())),
+            None => {
+                let mut data = properties.flatten_bm25();
+                data.push_str(label);
+                Some(vec![self.tokenize::<true>(&data)])
+            }
+        }
+    }
+
+    /// The distinct analyzers of the text fields of the label,
+    /// `None` when every field is indexed by `tokenize`
+    fn label_analyzers(&self, label: &str) -> Option<Vec<TextAnalyzer>> {
+        let fields = self.text_fields.as_ref()?.get(label)?;
+        let mut analyzers = fields.iter().map(|(_, analyzer)| *analyzer).collect::<Vec<_>>();
+        analyzers.sort_unstable_by_key(|analyzer| analyzer.as_str());
+        analyzers.dedup();
+        Some(analyzers)
+    }
+
+    /// The terms a query searches the items of the label by, split by each of the analyzers
+    /// of its text fields so it matches the terms of any of them
+    pub fn query_terms(&self, label: &str, query: &str) -> Vec<String> {
+        let Some(analyzers) = self.label_analyzers(label) else {
+            return self.tokenize::<true>(query);
+        };
+        match analyzers.as_slice() {
+            [analyzer] => analyzer.analyze(query),
+            // a term of several analyzers is only searched once
+            _ => {
+                let mut seen = HashSet::new();
+                analyzers
+                    .iter()
+                    .flat_map(|analyzer| analyzer.analyze(query))
+                    .filter(|term| seen.insert(term.clone()))
+                    .collect()
+            }
+        }
+    }
+
+    /// The terms of a phrase as split by each of the analyzers of the label's text fields
+    fn phrase_terms(&self, label: &str, phrase: &str) -> Vec<Vec<String>> {
+        match self.label_analyzers(label) {
+            Some(analyzers) => analyzers
+                .iter()
+                .map(|analyzer| analyzer.analyze(phrase))
+                .collect(),
+            None => vec![self.tokenize::<true>(phrase)],
+        }
+    }
+
+    /// The place of a text field among those of the label, which its positions start from,
+    /// and its analyzer
+    fn text_field(&self, label: &str, field: &str) -> Result<(u32, TextAnalyzer), GraphError> {
+        let found = self
+            .text_fields
+            .as_ref()
+            .and_then(|fields| fields.get(label))
+            .and_then(|fields| {
+                fields
+                    .iter()
+                    .enumerate()
+                    .find(|(_, (name, _))| name == field)
+            });
+        match found {
+            None => Err(GraphError::New(format!(
+                "`{field}` is not a text field of `{label}`"
+            ))),
+            Some((index, (_, analyzer))) => Ok((index as u32, *analyzer)),
+        }
+    }
+
+    /// Searches the docs of the label with a query, e.g.
+    /// `+graph -sql title:"machine learning"~2 (rust OR zig)`, for the top docs among those
+    /// `filter` keeps, as parsed by `parse_query`.
+    ///
+    /// A doc matches when it has every required clause and no prohibited one, and at least one
+    /// optional clause when there are no required ones. It scores the sum of the scores of the
+    /// clauses it matches, the score of a phrase being that of its terms divided by one plus
+    /// its slop, and the score of an expanded word, e.g. `postg*` or `kuberntes~`, the best of
+    /// those of the terms it expands to weighted by how close they are to it.
+    pub fn search_query<F>(
+        &self,
+        txn: &RoTxn,
+        label: &str,
+        query: &str,
+        limit: usize,
+        filter: F,
+    ) -> Result<Vec<(u128, f32)>, GraphError>
+    where
+        F: Fn(u128) -> bool,
+    {
+        self.search_expanded_query(txn, label, query, None, limit, filter)
+    }
+
+    /// Searches like `search_query`, expanding every word of the query that doesn't set its own
+    /// expansion by `expansion`, e.g. matching typos of each word with `TermExpansion::Fuzzy`
+    pub fn search_expanded_query<F>(
+        &self,
+        txn: &RoTxn,
+        label: &str,
+        query: &str,
+        expansion: Option<TermExpansion>,
+        limit: usize,
+        filter: F,
+    ) -> Result<Vec<(u128, f32)>, GraphError>
+    where
+        F: Fn(u128) -> bool,
+    {
+        let clauses = parse_query(query, |field| self.text_field(label, field).is_ok());
+        let doc_scores = self.score_clauses(txn, label, &clauses, expansion)?;
+        Ok(top_docs(doc_scores, limit, filter))
+    }
+
+    /// The scores of the docs matching the clauses of a query or group
+    fn score_clauses(
+        &self,
+        txn: &RoTxn,
+        label: &str,
+        clauses: &[QueryClause],
+        expansion: Option<TermExpansion>,
+    ) -> Result<HashMap<u128, f32>, GraphError> {
+        let mut required: Option<HashMap<u128, f32>> = None;
+        let mut optional: HashMap<u128, f32> = HashMap::new();
+        let mut prohibited: HashSet<u128> = HashSet::new();
+
+        for clause in clauses {
+            let field = match (&clause.node, &clause.field) {
+                // the clauses of a group carry its field themselves
+                (QueryNode::Group(_), _) | (_, None) => None,
+                (_, Some(field)) => Some(self.text_field(label, field)?),
+            };
+            let word_terms = |text: &str| match field {
+                Some((_, analyzer)) => analyzer.analyze(text),
+                None => self.query_terms(label, text),
+            };
+            // analyzers drop short words and stem partial ones, so prefixes are only lowercased
+            let expanded_terms = |text: &str, expansion: TermExpansion| match expansion {
+                TermExpansion::Prefix => self.tokenize::<false>(text),
+                TermExpansion::Fuzzy(_) => word_terms(text),
+            };
+            let field_index = field.map(|(index, _)| index);
+            let clause_scores = match &clause.node {
+                QueryNode::Terms(text) => match expansion {
+                    Some(expansion) => self.score_expanded_terms(
+                        txn,
+                        &expanded_terms(text, expansion),
+                        expansion,
+                        field_index,
+                    )?,
+                    None => self.score_terms(txn, &word_terms(text), field_index)?,
+                },
+                QueryNode::Expanded { text, expansion } => self.score_expanded_terms(
+                    txn,
+                    &expanded_terms(text, *expansion),
+                    *expansion,
+                    field_index,
+                )?,
+                QueryNode::Phrase { text, slop } => match field {
+                    Some((index, analyzer)) => {
+                        self.score_phrase(txn, &[analyzer.analyze(text)], *slop, Some(index))?
+                    }
+                    None => self.score_phrase(txn, &self.phrase_terms(label, text), *slop, None)?,
+                },
+                QueryNode::Group(clauses) => self.score_clauses(txn, label, clauses, expansion)?,
+            };
+
+            match clause.occur {
+                Occur::Must => {
+                    required = Some(match required {
+                        None => clause_scores,
+                        Some(mut required) => {
+                            required.retain(|doc_id, _| clause_scores.contains_key(doc_id));
+                            for (doc_id, score) in required.iter_mut() {
+                                *score += clause_scores[doc_id];
+                            }
+                            required
+                        }
+                    })
+                }
+                Occur::Should => {
+                    for (doc_id, score) in clause_scores {
+                        *optional.entry(doc_id).or_insert(0.0) += score;
+                    }
+                }
+                Occur::MustNot => prohibited.extend(clause_scores.into_keys()),
+            }
+        }
+
+        // optional clauses only add to the scores of the docs with every required one
+        let mut doc_scores = match required {
+            Some(mut required) => {
+                for (doc_id, score) in required.iter_mut() {
+                    *score += optional.get(doc_id).copied().unwrap_or(0.0);
+                }
+                required
+            }
+            None => optional,
+        };
+        doc_scores.retain(|doc_id, _| !prohibited.contains(doc_id));
+        Ok(doc_scores)
+    }
+
+    fn get_metadata(&self, txn: &RoTxn) -> Result<BM25Metadata, GraphError> {
+        let metadata = self
+            .metadata_db
+            .get(txn, METADATA_KEY)?
+            .ok_or(GraphError::New("BM25 metadata not found".to_string()))?;
+        Ok(bincode::deserialize(metadata)?)
+    }
+
+    /// The summed BM25 scores of the terms in each doc with any of them,
+    /// counting only their occurrences in the text field at `field` among those of its label
+    /// when set
+    fn score_terms(
+        &self,
+        txn: &RoTxn,
+        query_terms: &[String],
+        field: Option<u32>,
+    ) -> Result<HashMap<u128, f32>, GraphError> {
+        // (node uuid, score)
+        let mut doc_scores: HashMap<u128, f32> = HashMap::new();
+
+        let metadata = self.get_metadata(txn)?;
+
+        // for each query term, calculate scores
+        for term in query_terms {
+            let term_bytes = term.as_bytes();
+
+            let doc_frequency = self.term_frequencies_db.get(txn, term_bytes)?.unwrap_or(0);
+            if doc_frequency == 0 {
+                continue;
+            }
+
+            // Get all documents containing this term
+            if let Some(duplicates) = self.inverted_index_db.get_duplicates(txn, term_bytes)? {
+                for result in duplicates {
+                    let (_, posting_bytes) = result?;
+                    let posting: PostingListEntry = bincode::deserialize(posting_bytes)?;
+
+                    let term_frequency = match field {
+                        Some(field) => self.field_term_frequency(txn, term, posting.doc_id, field)?,
+                        None => posting.term_frequency,
+                    };
+                    if term_frequency == 0 {
+                        continue;
+                    }
+
+                    // Get document length
+                    let doc_length = self.doc_lengths_db.get(txn, &posting.doc_id)?.unwrap_or(0);
+
+                    // Calculate BM25 score for this term in this document
+                    let score = self.calculate_bm25_score(
+                        term_frequency,
+                        doc_length,
+                        doc_frequency,
+                        metadata.total_docs,
+                        metadata.avgdl,
+                    );
+
+                    *doc_scores.entry(posting.doc_id).or_insert(0.0) += score;
+                }
+            }
+        }
+
+        Ok(doc_scores)
+    }
+
+    /// The scores of the docs with any of the terms the query terms expand to, each expanded
+    /// term weighted by how close it is to its query term, and a doc scoring the best of the
+    /// expansions of a query term it has
+    fn score_expanded_terms(
+        &self,
+        txn: &RoTxn,
+        query_terms: &[String],
+        expansion: TermExpansion,
+        field: Option<u32>,
+    ) -> Result<HashMap<u128, f32>, GraphError> {
+        let mut doc_scores: HashMap<u128, f32> = HashMap::new();
+        for term in query_terms {
+            let mut term_scores: HashMap<u128, f32> = HashMap::new();
+            for (expanded, weight) in self.expand_term(txn, term, expansion)? {
+                for (doc_id, score) in self.score_terms(txn, &[expanded], field)? {
+                    let best = term_scores.entry(doc_id).or_insert(0.0);
+                    *best = best.max(score * weight);
+                }
+            }
+            for (doc_id, score) in term_scores {
+                *doc_scores.entry(doc_id).or_insert(0.0) += score;
+            }
+        }
+        Ok(doc_scores)
+    }
+
+    /// The terms of the index a query term expands to with their weights, the term itself
+    /// weighing 1, a longer term it prefixes the share of its characters the query term covers,
+    /// and a term `n` edits away from it `1 / (1 + n)`.
+    ///
+    /// Prefixes are found by a range scan of the ordered term dictionary, and fuzzy terms by a
+    /// range scan of the terms sharing their first `FUZZY_PREFIX_LENGTH` characters, skipping
+    /// those whose length is further from theirs than the distance. Only the `MAX_EXPANSIONS`
+    /// best weighted terms are kept, the most frequent first among equal weights.
+    fn expand_term(
+        &self,
+        txn: &RoTxn,
+        term: &str,
+        expansion: TermExpansion,
+    ) -> Result<Vec<(String, f32)>, GraphError> {
+        let term_len = term.chars().count();
+        // (term, weight, document frequency)
+        let mut expanded: Vec<(String, f32, u32)> = Vec::new();
+        match expansion {
+            TermExpansion::Prefix => {
+                for result in self.term_frequencies_db.prefix_iter(txn, term.as_bytes())? {
+                    let (term_bytes, doc_frequency) = result?;
+                    if let Ok(candidate) = std::str::from_utf8(term_bytes)
+                        && doc_frequency > 0
+                    {
+                        let weight = term_len as f32 / candidate.chars().count() as f32;
+                        expanded.push((candidate.to_string(), weight, doc_frequency));
+                    }
+                }
+            }
+            // a term with no more characters than edits would match any short term
+            TermExpansion::Fuzzy(distance) if term_len <= distance as usize => {
+                return Ok(vec![(term.to_string(), 1.0)]);
+            }
+            TermExpansion::Fuzzy(distance) => {
+                let prefix_len = term
+                    .char_indices()
+                    .nth(FUZZY_PREFIX_LENGTH)
+                    .map_or(term.len(), |(index, _)| index);
+                let prefix = &term.as_bytes()[..prefix_len];
+                for result in self.term_frequencies_db.prefix_iter(txn, prefix)? {
+                    let (term_bytes, doc_frequency) = result?;
+                    if let Ok(candidate) = std::str::from_utf8(term_bytes)
+                        && doc_frequency > 0
+                        && candidate.chars().count().abs_diff(term_len) <= distance as usize
+                        && let Some(edits) = edit_distance(term, candidate, distance)
+                    {
+                        let weight = 1.0 / (1 + edits) as f32;
+                        expanded.push((candidate.to_string(), weight, doc_frequency));
+                    }
+                }
+            }
+        }
+
+        expanded.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.2.cmp(&a.2))
+        });
+        expanded.truncate(MAX_EXPANSIONS);
+        Ok(expanded
+            .into_iter()
+            .map(|(term, weight, _)| (term, weight))
+            .collect())
+    }
+
+    /// The scores of the docs with the terms of a phrase in order within `slop` moves,
+    /// trying the terms of each analyzer and keeping the best match of a doc,
+    /// only in the text field at `field` when set
+    fn score_phrase(
+        &self,
+        txn: &RoTxn,
+        phrase_terms: &[Vec<String>],
+        slop: u32,
+        field: Option<u32>,
+    ) -> Result<HashMap<u128, f32>, GraphError> {
+        let mut doc_scores: HashMap<u128, f32> = HashMap::new();
+        let metadata = self.get_metadata(txn)?;
+
+        for terms in phrase_terms {
+            if terms.len() <= 1 {
+                for (doc_id, score) in self.score_terms(txn, terms, field)? {
+                    let best = doc_scores.entry(doc_id).or_insert(0.0);
+                    *best = best.max(score);
+                }
+                continue;
+            }
+            if !self.positions {
+                return Err(GraphError::New(
+                    "phrase queries need the positions of terms, \
+                     enable `bm25_positions` in the config"
+                        .to_string(),
+                ));
+            }
+
+            // the document frequency and the term frequency in each doc of every term
+            let mut postings = Vec::with_capacity(terms.len());
+            for term in terms {
+                let term_bytes = term.as_bytes();
+                let doc_frequency = self.term_frequencies_db.get(txn, term_bytes)?.unwrap_or(0);
+                let mut term_frequencies = HashMap::new();
+                if let Some(duplicates) = self.inverted_index_db.get_duplicates(txn, term_bytes)? {
+                    for result in duplicates {
+                        let (_, posting_bytes) = result?;
+                        let posting: PostingListEntry = bincode::deserialize(posting_bytes)?;
+                        term_frequencies.insert(posting.doc_id, posting.term_frequency);
+                    }
+                }
+                postings.push((doc_frequency, term_frequencies));
+            }
+
+            let candidates = postings[0]
+                .1
+                .keys()
+                .filter(|doc_id| postings[1..].iter().all(|(_, tfs)| tfs.contains_key(doc_id)))
+                .copied()
+                .collect::<Vec<_>>();
+            for doc_id in candidates {
+                let mut positions = Vec::with_capacity(terms.len());
+                for term in terms {
+                    let mut term_positions = self.term_positions(txn, term, doc_id)?;
+                    if let Some(field) = field {
+                        term_positions.retain(|position| position / FIELD_POSITION_GAP == field);
+                    }
+                    positions.push(term_positions);
+                }
+                let Some(found) = phrase_slop(&positions, slop) else {
+                    continue;
+                };
+                let doc_length = self.doc_lengths_db.get(txn, &doc_id)?.unwrap_or(0);
+                let score = postings
+                    .iter()
+                    .map(|(doc_frequency, term_frequencies)| {
+                        self.calculate_bm25_score(
+                            term_frequencies[&doc_id],
+                            doc_length,
+                            *doc_frequency,
+                            metadata.total_docs,
+                            metadata.avgdl,
+                        )
+                    })
+                    .sum::<f32>()
+                    / (1 + found) as f32;
+                let best = doc_scores.entry(doc_id).or_insert(0.0);
+                *best = best.max(score);
+            }
+        }
+
+        Ok(doc_scores)
+    }
+
+    fn positions_key(term: &[u8], doc_id: u128) -> Vec<u8> {
+        [term, &doc_id.to_be_bytes()].concat()
+    }
+
+    /// The positions of the term in the doc, empty when they are not stored
+    fn term_positions(
+        &self,
+        txn: &RoTxn,
+        term: &str,
+        doc_id: u128,
+    ) -> Result<Vec<u32>, GraphError> {
+        match self
+            .positions_db
+            .get(txn, &Self::positions_key(term.as_bytes(), doc_id))?
+        {
+            Some(bytes) => Ok(bincode::deserialize(bytes)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// The occurrences of the term in the text field at `field` among those of the doc's label
+    fn field_term_frequency(
+        &self,
+        txn: &RoTxn,
+        term: &str,
+        doc_id: u128,
+        field: u32,
+    ) -> Result<u32, GraphError> {
+        match self
+            .field_frequencies_db
+            .get(txn, &Self::positions_key(term.as_bytes(), doc_id))?
+        {
+            Some(bytes) => Ok(bincode::deserialize::<Vec<u32>>(bytes)?
+                .get(field as usize)
+                .copied()
+                .unwrap_or(0)),
+            None => Ok(0),
+        }
+    }
+
+    /// Whether updating any of the fields changes the text an item of the label is indexed under
+    pub fn indexes_any<'a>(&self, label: &str, mut fields: impl Iterator<Item = &'a str>) -> bool {
+        match &self.text_fields {
+            Some(text_fields) => text_fields
+                .get(label)
+                .is_some_and(|text_fields| {
+                    fields.any(|field| text_fields.iter().any(|(f, _)| f == field))
+                }),
+            None => true,
+        }
+    }
+
+    /// The text fields and their analyzers in a stable order,
+    /// stored to tell when the declared ones change
+    fn text_fields_key(&self) -> Option<Vec<(&str, &str, &str)>> {
+        self.text_fields.as_ref().map(|text_fields| {
+            let mut fields = text_fields
+                .iter()
+                .flat_map(|(label, fields)| {
+                    fields.iter().map(move |(field, analyzer)| {
+                        (label.as_str(), field.as_str(), analyzer.as_str())
+                    })
+                })
+                .collect::<Vec<_>>();
+            fields.sort_unstable();
+            fields
+        })
+    }
+
+    /// Whether the index was built from other text fields than the declared ones
+    pub fn text_fields_changed(&self, txn: &RoTxn) -> Result<bool, GraphError> {
+        let stored = self.metadata_db.get(txn, TEXT_FIELDS_KEY)?;
+        Ok(match (self.text_fields_key(), stored) {
+            // indexes from before text fields were declared hold every field
+            (None, None) => false,
+            (fields, Some(stored)) => {
+                bincode::deserialize::<Option<Vec<(String, String, String)>>>(stored)
+                    .ok()
+                    .flatten()
+                    != fields.map(|fields| {
+                        fields
+                            .into_iter()
+                            .map(|(label, field, analyzer)| {
+                                (label.to_string(), field.to_string(), analyzer.to_string())
+                            })
+                            .collect()
+                    })
+            }
+            (Some(_), None) => true,
+        })
+    }
+
+    /// Whether the index was built with the positions of its terms when they are not
+    /// to be stored, or without them when they are, e.g. an index from before positions
+    pub fn positions_changed(&self, txn: &RoTxn) -> Result<bool, GraphError> {
+        let stored = match self.metadata_db.get(txn, POSITIONS_KEY)? {
+            Some(stored) => bincode::deserialize::<bool>(stored)?,
+            None => false,
+        };
+        Ok(stored != self.positions)
+    }
+
+    /// Whether the index holds text fields but was built without the frequencies of its terms
+    /// in each of them, e.g. an index from before field queries worked without positions
+    pub fn field_frequencies_missing(&self, txn: &RoTxn) -> Result<bool, GraphError> {
+        Ok(self.text_fields.is_some()
+            && self
+                .metadata_db
+                .get(txn, FIELD_FREQUENCIES_KEY)?
+                .is_none())
+    }
+
+    /// Empties the index, recording the text fields and whether positions are stored
+    /// for the index it is about to be rebuilt into, which holds the field frequencies
+    pub fn clear(&self, txn: &mut RwTxn) -> Result<(), GraphError> {
+        self.inverted_index_db.clear(txn)?;
+        self.doc_lengths_db.clear(txn)?;
+        self.term_frequencies_db.clear(txn)?;
+        self.positions_db.clear(txn)?;
+        self.field_frequencies_db.clear(txn)?;
+        self.metadata_db.clear(txn)?;
+        self.metadata_db.put(
+            txn,
+            TEXT_FIELDS_KEY,
+            &bincode::serialize(&self.text_fields_key())?,
+        )?;
+        self.metadata_db
+            .put(txn, POSITIONS_KEY, &bincode::serialize(&self.positions)?)?;
+        self.metadata_db.put(txn, FIELD_FREQUENCIES_KEY, &[])?;
+        Ok(())
+    }
 }
 
 impl BM25 for HBM25Config {
@@ -124,22 +824,35 @@ impl BM25 for HBM25Config {
     /// Inserts needed information into doc_lengths_db, inverted_index_db, term_frequencies_db, and
     /// metadata_db
     fn insert_doc(&self, txn: &mut RwTxn, doc_id: u128, doc: &str) -> Result<(), GraphError> {
-        let tokens = self.tokenize::<true>(doc);
-        let doc_length = tokens.len() as u32;
+        self.insert_fields(txn, doc_id, vec![self.tokenize::<true>(doc)])
+    }
 
-        let mut term_counts: HashMap<String, u32> = HashMap::new();
-        for token in tokens {
-            *term_counts.entry(token).or_insert(0) += 1;
+    fn insert_fields(
+        &self,
+        txn: &mut RwTxn,
+        doc_id: u128,
+        fields: Vec<Vec<String>>,
+    ) -> Result<(), GraphError> {
+        let doc_length = fields.iter().map(Vec::len).sum::<usize>() as u32;
+
+        // the positions of each term, the fields of a doc spaced apart
+        let mut term_positions: HashMap<String, Vec<u32>> = HashMap::new();
+        for (field, terms) in fields.into_iter().enumerate() {
+            let start = field as u32 * FIELD_POSITION_GAP;
+            for (offset, term) in terms.into_iter().enumerate() {
+                let position = start + (offset as u32).min(FIELD_POSITION_GAP - 1);
+                term_positions.entry(term).or_default().push(position);
+            }
         }
 
         self.doc_lengths_db.put(txn, &doc_id, &doc_length)?;
 
-        for (term, tf) in term_counts {
+        for (term, positions) in term_positions {
             let term_bytes = term.as_bytes();
 
             let posting_entry = PostingListEntry {
                 doc_id,
-                term_frequency: tf,
+                term_frequency: positions.len() as u32,
             };
 
             let posting_bytes = bincode::serialize(&posting_entry)?;
@@ -150,8 +863,31 @@ impl BM25 for HBM25Config {
             let current_df = self.term_frequencies_db.get(txn, term_bytes)?.unwrap_or(0);
             self.term_frequencies_db
                 .put(txn, term_bytes, &(current_df + 1))?;
+
+            // the positions are in field order, so the last one is in the last field of the term
+            if self.text_fields.is_some()
+                && let Some(last) = positions.last()
+            {
+                let mut frequencies = vec![0u32; (last / FIELD_POSITION_GAP) as usize + 1];
+                for position in &positions {
+                    frequencies[(position / FIELD_POSITION_GAP) as usize] += 1;
+                }
+                self.field_frequencies_db.put(
+                    txn,
+                    &Self::positions_key(term_bytes, doc_id),
+                    &bincode::serialize(&frequencies)?,
+                )?;
             }
 
+            if self.positions {
+                self.positions_db.put(
+                    txn,
+                    &Self::positions_key(term_bytes, doc_id),
+                    &bincode::serialize(&positions)?,
+                )?;
+            }
+        }
+
         let mut metadata = if let Some(data) = self.metadata_db.get(txn, METADATA_KEY)? {
             bincode::deserialize::<BM25Metadata>(data)?
         } else {
@@ -175,6 +911,11 @@ impl BM25 for HBM25Config {
     }
 
     fn delete_doc(&self, txn: &mut RwTxn, doc_id: u128) -> Result<(), GraphError> {
+        // items without text are never inserted, and must not be counted out of the metadata
+        if self.doc_lengths_db.get(txn, &doc_id)?.is_none() {
+            return Ok(());
+        }
+
         let terms_to_update = {
             let mut terms = Vec::new();
             let mut iter = self.inverted_index_db.iter(txn)?;
@@ -218,6 +959,11 @@ impl BM25 for HBM25Config {
                 self.term_frequencies_db
                     .put(txn, &term_bytes, &(current_df - 1))?;
             }
+
+            self.positions_db
+                .delete(txn, &Self::positions_key(&term_bytes, doc_id))?;
+            self.field_frequencies_db
+                .delete(txn, &Self::positions_key(&term_bytes, doc_id))?;
         }
 
         let doc_length = self.doc_lengths_db.get(txn, &doc_id)?.unwrap_or(0);
@@ -289,66 +1035,177 @@ impl BM25 for HBM25Config {
         query: &str,
         limit: usize,
     ) -> Result<Vec<(u128, f32)>, GraphError> {
-        let query_terms = self.tokenize::<true>(query);
-        // (node uuid, score)
-        let mut doc_scores: HashMap<u128, f32> = HashMap::with_capacity(limit);
+        // no label has text fields, so words are split by `tokenize` and can't be field scoped
+        self.search_query(txn, "", query, limit, |_| true)
+    }
 
-        let metadata = self
-            .metadata_db
-            .get(txn, METADATA_KEY)?
-            .ok_or(GraphError::New("BM25 metadata not found".to_string()))?;
-        let metadata: BM25Metadata = bincode::deserialize(metadata)?;
+    fn search_terms<F>(
+        &self,
+        txn: &RoTxn,
+        query_terms: &[String],
+        limit: usize,
+        filter: F,
+    ) -> Result<Vec<(u128, f32)>, GraphError>
+    where
+        F: Fn(u128) -> bool,
+    {
+        Ok(top_docs(self.score_terms(txn, query_terms, None)?, limit, filter))
+    }
+}
 
-        // for each query term, calculate scores
-        for term in query_terms {
-            let term_bytes = term.as_bytes();
+/// The `limit` best scored docs among those `filter` keeps
+fn top_docs<F>(doc_scores: HashMap<u128, f32>, limit: usize, filter: F) -> Vec<(u128, f32)>
+where
+    F: Fn(u128) -> bool,
+{
+    // Sort by score and return top results
+    let mut results: Vec<(u128, f32)> = doc_scores
+        .into_iter()
+        .filter(|(doc_id, _)| filter(*doc_id))
+        .collect();
+    results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    results.truncate(limit);
+
+    debug_println!("found {} results in bm25 search", results.len());
+
+    results
+}
 
-            let doc_frequency = self.term_frequencies_db.get(txn, term_bytes)?.unwrap_or(0);
-            if doc_frequency == 0 {
-                continue;
-            }
+/// The k of reciprocal rank fusion when a query doesn't set one
+pub const DEFAULT_RRF_K: f32 = 60.0;
 
-            // Get all documents containing this term
-            if let Some(duplicates) = self.inverted_index_db.get_duplicates(txn, term_bytes)? {
-                for result in duplicates {
-                    let (_, posting_bytes) = result?;
-                    let posting: PostingListEntry = bincode::deserialize(posting_bytes)?;
+/// How the bm25 and vector rankings of a hybrid search are combined
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HybridFusion {
+    /// `alpha * bm25_score + (1 - alpha) * vector_similarity`
+    Weighted { alpha: f32 },
+    /// The sum of `1 / (k + rank)` over both rankings, ignoring the raw scores
+    ReciprocalRank { k: f32 },
+}
 
-                    // Get document length
-                    let doc_length = self.doc_lengths_db.get(txn, &posting.doc_id)?.unwrap_or(0);
+impl Default for HybridFusion {
+    fn default() -> Self {
+        HybridFusion::Weighted { alpha: 0.5 }
+    }
+}
 
-                    // Calculate BM25 score for this term in this document
-                    let score = self.calculate_bm25_score(
-                        posting.term_frequency,
-                        doc_length,
-                        doc_frequency,
-                        metadata.total_docs,
-                        metadata.avgdl,
-                    );
+impl HybridFusion {
+    /// Fuses the bm25 scores and the vector similarities (both ranked best first)
+    /// into the top `limit` ids
+    pub fn fuse(
+        self,
+        bm25_results: Vec<(u128, f32)>,
+        vector_results: Vec<(u128, f32)>,
+        limit: usize,
+    ) -> Vec<(u128, f32)> {
+        let mut combined_scores: HashMap<u128, f32> = HashMap::new();
 
-                    *doc_scores.entry(posting.doc_id).or_insert(0.0) += score;
+        match self {
+            HybridFusion::Weighted { alpha } => {
+                for (doc_id, score) in bm25_results {
+                    *combined_scores.entry(doc_id).or_insert(0.0) += alpha * score;
+                }
+                for (doc_id, similarity) in vector_results {
+                    *combined_scores.entry(doc_id).or_insert(0.0) += (1.0 - alpha) * similarity;
+                }
+            }
+            HybridFusion::ReciprocalRank { k } => {
+                for results in [bm25_results, vector_results] {
+                    for (rank, (doc_id, _)) in results.into_iter().enumerate() {
+                        *combined_scores.entry(doc_id).or_insert(0.0) +=
+                            1.0 / (k + rank as f32 + 1.0);
+                    }
                 }
             }
         }
 
-        // Sort by score and return top results
-        let mut results: Vec<(u128, f32)> = doc_scores.into_iter().collect();
+        let mut results = combined_scores.into_iter().collect::<Vec<(u128, f32)>>();
         results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         results.truncate(limit);
+        results
+    }
+}
 
-        debug_println!("found {} results in bm25 search", results.len());
-
-        Ok(results)
+/// Searches the bm25 docs of the vectors stored under `label`
+pub fn bm25_search_label(
+    storage: &HelixGraphStorage,
+    txn: &RoTxn,
+    query: &str,
+    label: &str,
+    limit: usize,
+) -> Result<Vec<(u128, f32)>, GraphError> {
+    match storage.bm25.as_ref() {
+        Some(s) => s.search_query(txn, label, query, limit, |id| {
+            storage
+                .get_vector(txn, &id)
+                .is_ok_and(|vector| vector.label() == label)
+        }),
+        None => Err(GraphError::from("BM25 not enabled!")),
     }
 }
 
+/// Searches the hnsw index of `label`, turning each distance into a similarity
+pub fn vector_search_label(
+    storage: &HelixGraphStorage,
+    txn: &RoTxn,
+    query_vector: &[f64],
+    label: &str,
+    limit: usize,
+) -> Result<Vec<(u128, f32)>, GraphError> {
+    let results = storage.vectors.search::<fn(&HVector, &RoTxn) -> bool>(
+        txn,
+        query_vector,
+        limit,
+        label,
+        None,
+        false,
+        SearchParams::default(),
+    )?;
+    Ok(results
+        .into_iter()
+        .map(|vector| (vector.id, (1.0 / (1.0 + vector.get_distance())) as f32))
+        .collect())
+}
+
+/// Searches the sparse vectors of `label`, the dot product of each already being a similarity
+pub fn sparse_search_label(
+    storage: &HelixGraphStorage,
+    txn: &RoTxn,
+    query_vector: &SparseVector,
+    label: &str,
+    limit: usize,
+) -> Result<Vec<(u128, f32)>, GraphError> {
+    let results = storage.sparse.search::<fn(&HVector, &RoTxn) -> bool>(
+        txn,
+        query_vector,
+        limit,
+        label,
+        None,
+    )?;
+    Ok(results
+        .into_iter()
+        .map(|vector| (vector.id, (1.0 - vector.get_distance()) as f32))
+        .collect())
+}
+
 pub trait HybridSearch {
-    /// Search both hnsw index and bm25 docs
+    /// Search both the hnsw index of `label` and the bm25 docs of its vectors
     fn hybrid_search(
         self,
         query: &str,
         query_vector: &[f64],
-        alpha: f32,
+        label: &str,
+        fusion: HybridFusion,
+        limit: usize,
+    ) -> impl std::future::Future<Output = Result<Vec<(u128, f32)>, GraphError>> + Send;
+
+    /// Search both the sparse vectors of `label` and their bm25 docs
+    fn hybrid_search_sparse(
+        self,
+        query: &str,
+        query_vector: &SparseVector,
+        label: &str,
+        fusion: HybridFusion,
         limit: usize,
     ) -> impl std::future::Future<Output = Result<Vec<(u128, f32)>, GraphError>> + Send;
 }
@@ -358,71 +1215,91 @@ impl HybridSearch for HelixGraphStorage {
         self,
         query: &str,
         query_vector: &[f64],
-        alpha: f32,
+        label: &str,
+        fusion: HybridFusion,
         limit: usize,
     ) -> Result<Vec<(u128, f32)>, GraphError> {
         let query_owned = query.to_string();
         let query_vector_owned = query_vector.to_vec();
+        let label_bm25 = label.to_string();
+        let label_vector = label.to_string();
 
-        let graph_env_bm25 = self.graph_env.clone();
-        let graph_env_vector = self.graph_env.clone();
+        let storage_bm25 = Arc::new(self);
+        let storage_vector = Arc::clone(&storage_bm25);
 
         let bm25_handle = task::spawn_blocking(move || -> Result<Vec<(u128, f32)>, GraphError> {
-            let txn = graph_env_bm25.read_txn()?;
-            match self.bm25.as_ref() {
-                Some(s) => s.search(&txn, &query_owned, limit * 2),
-                None => Err(GraphError::from("BM25 not enabled!")),
-            }
+            let txn = storage_bm25.graph_env.read_txn()?;
+            bm25_search_label(&storage_bm25, &txn, &query_owned, &label_bm25, limit * 2)
         });
 
-        let vector_handle = task::spawn_blocking(move || -> Result<Option<Vec<HVector>>, GraphError> {
-            let txn = graph_env_vector.read_txn()?;
-            let results = self.vectors.search::<fn(&HVector, &RoTxn) -> bool>(
-                &txn,
-                &query_vector_owned,
-                limit * 2,
-                "vector",
-                None,
-                false,
-            )?;
-            Ok(Some(results))
-        });
+        let vector_handle =
+            task::spawn_blocking(move || -> Result<Vec<(u128, f32)>, GraphError> {
+                let txn = storage_vector.graph_env.read_txn()?;
+                vector_search_label(
+                    &storage_vector,
+                    &txn,
+                    &query_vector_owned,
+                    &label_vector,
+                    limit * 2,
+                )
+            });
 
         let (bm25_results, vector_results) = match tokio::try_join!(bm25_handle, vector_handle) {
             Ok((a, b)) => (a, b),
             Err(e) => return Err(GraphError::from(e.to_string())),
         };
 
-        let mut combined_scores: HashMap<u128, f32> = HashMap::new();
+        Ok(fusion.fuse(bm25_results?, vector_results?, limit))
+    }
 
-        for (doc_id, score) in bm25_results? {
-            combined_scores.insert(doc_id, alpha * score);
-        }
+    async fn hybrid_search_sparse(
+        self,
+        query: &str,
+        query_vector: &SparseVector,
+        label: &str,
+        fusion: HybridFusion,
+        limit: usize,
+    ) -> Result<Vec<(u128, f32)>, GraphError> {
+        let query_owned = query.to_string();
+        let query_vector_owned = query_vector.clone();
+        let label_bm25 = label.to_string();
+        let label_sparse = label.to_string();
 
-        // correct_score = alpha * bm25_score + (1.0 - alpha) * vector_score
-        if let Some(vector_results) = vector_results? {
-            for doc in vector_results {
-                let doc_id = doc.id;
-                let score = doc.distance.unwrap_or(0.0);
-                let similarity = (1.0 / (1.0 + score)) as f32;
-                combined_scores
-                    .entry(doc_id)
-                    .and_modify(|existing_score| *existing_score += (1.0 - alpha) * similarity)
-                    .or_insert((1.0 - alpha) * similarity); // correction made here from score as f32 to similarity
-                }
-        }
+        let storage_bm25 = Arc::new(self);
+        let storage_sparse = Arc::clone(&storage_bm25);
 
-        let mut results = combined_scores.into_iter().collect::<Vec<(u128, f32)>>();
-        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        results.truncate(limit);
+        let bm25_handle = task::spawn_blocking(move || -> Result<Vec<(u128, f32)>, GraphError> {
+            let txn = storage_bm25.graph_env.read_txn()?;
+            bm25_search_label(&storage_bm25, &txn, &query_owned, &label_bm25, limit * 2)
+        });
 
-        Ok(results)
+        let sparse_handle =
+            task::spawn_blocking(move || -> Result<Vec<(u128, f32)>, GraphError> {
+                let txn = storage_sparse.graph_env.read_txn()?;
+                sparse_search_label(
+                    &storage_sparse,
+                    &txn,
+                    &query_vector_owned,
+                    &label_sparse,
+                    limit * 2,
+                )
+            });
+
+        let (bm25_results, sparse_results) = match tokio::try_join!(bm25_handle, sparse_handle) {
+            Ok((a, b)) => (a, b),
+            Err(e) => return Err(GraphError::from(e.to_string())),
+        };
+
+        Ok(fusion.fuse(bm25_results?, sparse_results?, limit))
     }
 }
 
 pub trait BM25Flatten {
     /// util func to flatten array of strings to a single string
     fn flatten_bm25(&self) -> String;
+
+    /// The value of a text field, for its analyzer to split
+    fn bm25_field(&self, field: &str) -> Option<&Value>;
 }
 
 impl BM25Flatten for HashMap<String, Value> {
@@ -436,5 +1313,26 @@ impl BM25Flatten for HashMap<String, Value> {
                 s
             })
     }
+
+    fn bm25_field(&self, field: &str) -> Option<&Value> {
+        self.get(field)
+    }
+}
+
+impl BM25Flatten for [(String, Value)] {
+    fn flatten_bm25(&self) -> String {
+        self.iter()
+            .fold(String::with_capacity(self.len() * 4), |mut s, (k, v)| {
+                s.push_str(k);
+                s.push(' ');
+                s.push_str(&v.to_string());
+                s.push(' ');
+                s
+            })
+    }
+
+    fn bm25_field(&self, field: &str) -> Option<&Value> {
+        self.iter().find(|(key, _)| key == field).map(|(_, value)| value)
+    }
 }
 
@@ -2,12 +2,20 @@
 mod tests {
     use crate::{
         helix_engine::{
-            bm25::bm25::{
-                BM25, BM25Flatten, BM25Metadata, HBM25Config, HybridSearch, METADATA_KEY,
+            bm25::{
+                analyzer::{TextAnalyzer, porter_stem},
+                bm25::{
+                    BM25, BM25Flatten, BM25Metadata, FIELD_POSITION_GAP, HBM25Config, HybridFusion,
+                    HybridSearch, METADATA_KEY,
+                },
+                query::{
+                    MAX_EDIT_DISTANCE, Occur, QueryClause, QueryNode, TermExpansion, edit_distance,
+                    parse_query, phrase_slop,
+                },
             },
             storage_core::{HelixGraphStorage, version_info::VersionInfo},
-            traversal_core::config::Config,
-            vector_core::{hnsw::HNSW, vector::HVector},
+            traversal_core::config::{Config, TextField},
+            vector_core::{hnsw::HNSW, sparse_vector::SparseVector, vector::HVector},
         },
         protocol::value::Value,
     };
@@ -1388,6 +1396,30 @@ mod tests {
         assert_eq!(results.len(), 0);
     }
 
+    #[test]
+    fn test_search_with_colons() {
+        let (bm25, _temp_dir) = setup_bm25_config();
+        let mut wtxn = bm25.graph_env.write_txn().unwrap();
+
+        bm25.insert_doc(&mut wtxn, 1u128, "re: meeting moved to 10:30")
+            .unwrap();
+        bm25.insert_doc(&mut wtxn, 2u128, "see http://example.com")
+            .unwrap();
+        wtxn.commit().unwrap();
+
+        // without text fields, no word before a colon scopes a search to a field
+        let rtxn = bm25.graph_env.read_txn().unwrap();
+        for (query, id) in [
+            ("re: meeting", 1),
+            ("subject:meeting", 1),
+            ("http://example.com", 2),
+        ] {
+            let results = bm25.search(&rtxn, query, 10).unwrap();
+            assert_eq!(results.len(), 1, "{query}");
+            assert_eq!(results[0].0, id, "{query}");
+        }
+    }
+
     #[test]
     fn test_edge_cases_empty_document() {
         let (bm25, _temp_dir) = setup_bm25_config();
@@ -1436,7 +1468,13 @@ mod tests {
         let limit = 10;
 
         let result = storage
-            .hybrid_search(query, &query_vector[0], alpha, limit)
+            .hybrid_search(
+                query,
+                &query_vector[0],
+                "vector",
+                HybridFusion::Weighted { alpha },
+                limit,
+            )
             .await;
 
         match result {
@@ -1477,7 +1515,13 @@ mod tests {
 
         // alpha = 0.0 (Vector only)
         let results_vector_only = storage
-            .hybrid_search(query, &query_vector[0], 0.0, 10)
+            .hybrid_search(
+                query,
+                &query_vector[0],
+                "vector",
+                HybridFusion::Weighted { alpha: 0.0 },
+                10,
+            )
             .await;
 
         match results_vector_only {
@@ -1520,7 +1564,13 @@ mod tests {
 
         // alpha = 1.0 (BM25 only)
         let results_bm25_only = storage
-            .hybrid_search(query, &query_vector[0], 1.0, 10)
+            .hybrid_search(
+                query,
+                &query_vector[0],
+                "vector",
+                HybridFusion::Weighted { alpha: 1.0 },
+                10,
+            )
             .await;
 
         // all should be valid results or acceptable errors
@@ -1530,6 +1580,111 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_hybrid_search_sparse() {
+        let (storage, _temp_dir) = setup_helix_storage();
+
+        // each doc is indexed under the id of its sparse vector
+        let docs = [
+            ("machine learning algorithms", [0u32, 1, 2], [0.9, 0.4, 0.1]),
+            ("deep learning neural networks", [1, 3, 4], [0.2, 0.8, 0.5]),
+            ("data science methods", [5, 6, 7], [0.7, 0.3, 0.6]),
+        ];
+        let mut wtxn = storage.graph_env.write_txn().unwrap();
+        let fields = Some(vec![(
+            "label".to_string(),
+            Value::String("splade".to_string()),
+        )]);
+        let mut ids = Vec::new();
+        for (doc, indices, weights) in &docs {
+            let vector = SparseVector::new(indices, weights).unwrap();
+            let id = storage
+                .sparse
+                .insert(&mut wtxn, &vector, fields.clone())
+                .unwrap()
+                .id;
+            storage
+                .bm25
+                .as_ref()
+                .unwrap()
+                .insert_doc(&mut wtxn, id, doc)
+                .unwrap();
+            ids.push(id);
+        }
+        wtxn.commit().unwrap();
+
+        // the text matches the second doc, the sparse vector the first
+        let query_vector = SparseVector::new(&[0, 2], &[1.0, 0.5]).unwrap();
+        let results = storage
+            .hybrid_search_sparse(
+                "neural networks",
+                &query_vector,
+                "splade",
+                HybridFusion::default(),
+                10,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        let found = results.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+        assert!(found.contains(&ids[0]));
+        assert!(found.contains(&ids[1]));
+        assert!(!found.contains(&ids[2]));
+        let sparse_score = results.iter().find(|(id, _)| *id == ids[0]).unwrap().1;
+        assert!((sparse_score - 0.5 * 0.95).abs() < 1e-5);
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_search_reciprocal_rank() {
+        let (storage, _temp_dir) = setup_helix_storage();
+
+        let docs = [
+            ("machine learning algorithms", [0u32, 1, 2], [0.9, 0.4, 0.1]),
+            ("deep learning neural networks", [1, 3, 4], [0.2, 0.8, 0.5]),
+            ("data science methods", [5, 6, 7], [0.7, 0.3, 0.6]),
+        ];
+        let mut wtxn = storage.graph_env.write_txn().unwrap();
+        let fields = Some(vec![(
+            "label".to_string(),
+            Value::String("splade".to_string()),
+        )]);
+        let bm25 = storage.bm25.as_ref().unwrap();
+        let mut ids = Vec::new();
+        for (doc, indices, weights) in &docs {
+            let vector = SparseVector::new(indices, weights).unwrap();
+            let id = storage
+                .sparse
+                .insert(&mut wtxn, &vector, fields.clone())
+                .unwrap()
+                .id;
+            bm25.insert_doc(&mut wtxn, id, doc).unwrap();
+            ids.push(id);
+        }
+        // docs that aren't vectors of the label never take part
+        bm25.insert_doc(&mut wtxn, 42, "learning learning").unwrap();
+        wtxn.commit().unwrap();
+
+        // both rankings put the first doc ahead of the second
+        let query_vector = SparseVector::new(&[1], &[1.0]).unwrap();
+        let results = storage
+            .hybrid_search_sparse(
+                "learning",
+                &query_vector,
+                "splade",
+                HybridFusion::ReciprocalRank { k: 60.0 },
+                10,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, ids[0]);
+        assert!((results[0].1 - 2.0 / 61.0).abs() < 1e-6);
+        assert_eq!(results[1].0, ids[1]);
+        assert!((results[1].1 - 2.0 / 62.0).abs() < 1e-6);
+    }
+
     #[test]
     fn test_bm25_score_properties() {
         let (bm25, _temp_dir) = setup_bm25_config();
@@ -1580,4 +1735,518 @@ mod tests {
 
         wtxn.commit().unwrap();
     }
+
+    #[test]
+    fn test_porter_stem() {
+        let stems = [
+            ("caresses", "caress"),
+            ("ponies", "poni"),
+            ("cats", "cat"),
+            ("agreed", "agre"),
+            ("plastered", "plaster"),
+            ("motoring", "motor"),
+            ("sing", "sing"),
+            ("conflated", "conflat"),
+            ("hopping", "hop"),
+            ("falling", "fall"),
+            ("filing", "file"),
+            ("happy", "happi"),
+            ("relational", "relat"),
+            ("generalization", "gener"),
+            ("adjustment", "adjust"),
+            ("adoption", "adopt"),
+            ("running", "run"),
+            ("runs", "run"),
+            ("go", "go"),
+            ("naïve", "naïve"),
+        ];
+        for (word, stem) in stems {
+            assert_eq!(porter_stem(word), stem, "stem of {word}");
+        }
+    }
+
+    #[test]
+    fn test_text_analyzers() {
+        let text = "The AI is Running on Go-lang";
+        assert_eq!(
+            TextAnalyzer::Standard.analyze(text),
+            vec!["the", "ai", "is", "running", "on", "go", "lang"]
+        );
+        assert_eq!(
+            TextAnalyzer::English.analyze(text),
+            vec!["ai", "run", "go", "lang"]
+        );
+        assert_eq!(
+            TextAnalyzer::Whitespace.analyze(text),
+            vec!["The", "AI", "is", "Running", "on", "Go-lang"]
+        );
+        assert_eq!(
+            TextAnalyzer::NGram.analyze("Graph db"),
+            vec!["gra", "rap", "aph", "db"]
+        );
+        assert_eq!(
+            TextAnalyzer::CjkBigram.analyze("东京大学 in Tokyo 日"),
+            vec!["东京", "京大", "大学", "in", "tokyo", "日"]
+        );
+        assert_eq!(
+            TextAnalyzer::CjkBigram.analyze("helix数据库"),
+            vec!["helix", "数据", "据库"]
+        );
+        for analyzer in TextAnalyzer::ALL {
+            assert_eq!(analyzer.as_str().parse::<TextAnalyzer>().unwrap(), analyzer);
+        }
+        assert!("klingon".parse::<TextAnalyzer>().is_err());
+    }
+
+    #[test]
+    fn test_text_field_analyzers() {
+        let (env, _temp_dir) = setup_test_env();
+        let mut wtxn = env.write_txn().unwrap();
+        let bm25 = HBM25Config::new(&env, &mut wtxn)
+            .unwrap()
+            .with_text_fields(Some(&[
+                TextField::new("article", "title"),
+                TextField::new("article", "body").with_analyzer(TextAnalyzer::English),
+            ]));
+
+        let mut article = HashMap::new();
+        article.insert("title".to_string(), Value::from("AI agents"));
+        article.insert("body".to_string(), Value::from("Running the models"));
+        article.insert("author".to_string(), Value::from("ada"));
+        let fields = bm25.doc_fields("article", &article).unwrap();
+        assert_eq!(fields, vec![vec!["ai", "agents"], vec!["run", "model"]]);
+        assert!(bm25.doc_fields("person", &article).is_none());
+
+        // a query matches the terms of each analyzer of the label, searching each term once
+        assert_eq!(
+            bm25.query_terms("article", "runs AI"),
+            vec!["run", "ai", "runs"]
+        );
+        bm25.insert_fields(&mut wtxn, 1, fields).unwrap();
+        let results = bm25
+            .search_terms(
+                &wtxn,
+                &bm25.query_terms("article", "the model runs"),
+                10,
+                |_| true,
+            )
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, 1);
+        let results = bm25
+            .search_terms(&wtxn, &bm25.query_terms("article", "ai"), 10, |_| true)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        wtxn.commit().unwrap();
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(
+            parse(r#"graph "machine learning"~2 db "vector search"#),
+            vec![
+                clause(Occur::Should, None, terms("graph")),
+                clause(Occur::Should, None, phrase("machine learning", 2)),
+                clause(Occur::Should, None, terms("db")),
+                clause(Occur::Should, None, phrase("vector search", 0)),
+            ]
+        );
+        assert_eq!(
+            parse(r#""" ~"#),
+            vec![clause(Occur::Should, None, terms("~"))]
+        );
+
+        // positions of "machine" and "learning" in a doc
+        assert_eq!(phrase_slop(&[vec![4, 10], vec![5]], 0), Some(0));
+        assert_eq!(phrase_slop(&[vec![1, 10], vec![4]], 1), None);
+        assert_eq!(phrase_slop(&[vec![1, 10], vec![4]], 2), Some(2));
+        assert_eq!(phrase_slop(&[vec![3], vec![2]], 2), Some(2));
+        assert_eq!(phrase_slop(&[vec![3], vec![]], 2), None);
+    }
+
+    /// Parses a query over the text fields `title` and `body`
+    fn parse(query: &str) -> Vec<QueryClause> {
+        parse_query(query, |field| matches!(field, "title" | "body"))
+    }
+
+    fn clause(occur: Occur, field: Option<&str>, node: QueryNode) -> QueryClause {
+        QueryClause {
+            occur,
+            field: field.map(str::to_string),
+            node,
+        }
+    }
+
+    fn terms(text: &str) -> QueryNode {
+        QueryNode::Terms(text.to_string())
+    }
+
+    fn phrase(text: &str, slop: u32) -> QueryNode {
+        QueryNode::Phrase {
+            text: text.to_string(),
+            slop,
+        }
+    }
+
+    #[test]
+    fn test_parse_boolean_query() {
+        assert_eq!(
+            parse(r#"+graph -sql title:"vector search"~1 go-lang"#),
+            vec![
+                clause(Occur::Must, None, terms("graph")),
+                clause(Occur::MustNot, None, terms("sql")),
+                clause(Occur::Should, Some("title"), phrase("vector search", 1)),
+                clause(Occur::Should, None, terms("go-lang")),
+            ]
+        );
+        // a group passes its field on to its clauses
+        assert_eq!(
+            parse("+title:(rust OR zig) -body:c"),
+            vec![
+                clause(
+                    Occur::Must,
+                    Some("title"),
+                    QueryNode::Group(vec![
+                        clause(Occur::Should, Some("title"), terms("rust")),
+                        clause(Occur::Should, Some("title"), terms("zig")),
+                    ])
+                ),
+                clause(Occur::MustNot, Some("body"), terms("c")),
+            ]
+        );
+        assert_eq!(
+            parse("graph AND db NOT sql (vector"),
+            vec![
+                clause(Occur::Must, None, terms("graph")),
+                clause(Occur::Must, None, terms("db")),
+                clause(Occur::MustNot, None, terms("sql")),
+                clause(
+                    Occur::Should,
+                    None,
+                    QueryNode::Group(vec![clause(Occur::Should, None, terms("vector"))])
+                ),
+            ]
+        );
+        assert_eq!(parse("- () ) +"), vec![]);
+        // colons after anything but a text field are part of the word
+        assert_eq!(
+            parse("re: meeting 10:30 author:ada"),
+            vec![
+                clause(Occur::Should, None, terms("re:")),
+                clause(Occur::Should, None, terms("meeting")),
+                clause(Occur::Should, None, terms("10:30")),
+                clause(Occur::Should, None, terms("author:ada")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_boolean_search() {
+        let (env, _temp_dir) = setup_test_env();
+        let mut wtxn = env.write_txn().unwrap();
+        let bm25 = HBM25Config::new(&env, &mut wtxn)
+            .unwrap()
+            .with_text_fields(Some(&[
+                TextField::new("article", "title"),
+                TextField::new("article", "body").with_analyzer(TextAnalyzer::English),
+            ]))
+            .with_positions(true);
+
+        let articles = [
+            ("graph databases", "storing graphs in rust"),
+            ("vector databases", "searching vectors in zig"),
+            ("graph algorithms", "traversing graphs in rust"),
+            ("sql databases", "querying tables"),
+        ];
+        for (id, (title, body)) in articles.iter().enumerate() {
+            let mut article = HashMap::new();
+            article.insert("title".to_string(), Value::from(*title));
+            article.insert("body".to_string(), Value::from(*body));
+            let fields = bm25.doc_fields("article", &article).unwrap();
+            bm25.insert_fields(&mut wtxn, id as u128, fields).unwrap();
+        }
+
+        let search = |query: &str| {
+            let mut ids = bm25
+                .search_query(&wtxn, "article", query, 10, |_| true)
+                .unwrap()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            ids
+        };
+        assert_eq!(search("databases"), vec![0, 1, 3]);
+        assert_eq!(search("+databases -sql"), vec![0, 1]);
+        assert_eq!(search("+databases +(rust OR zig)"), vec![0, 1]);
+        assert_eq!(search("databases AND rust"), vec![0]);
+        assert_eq!(search("graph NOT algorithms"), vec![0]);
+        assert_eq!(search("-sql"), Vec::<u128>::new());
+        // the body is stemmed by the english analyzer, the title is not
+        assert_eq!(search("title:graph"), vec![0, 2]);
+        assert_eq!(search("body:graph"), vec![0, 2]);
+        assert_eq!(search("title:graphs"), Vec::<u128>::new());
+        assert_eq!(search(r#"body:"traversing graphs""#), vec![2]);
+        assert_eq!(search("+body:(zig OR tables) -title:sql"), vec![1]);
+
+        // optional clauses raise the scores of the docs with the required ones
+        let results = bm25
+            .search_query(&wtxn, "article", "+databases rust", 10, |_| true)
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].0, 0);
+
+        // a colon after a word that is not a text field leaves it a plain word
+        assert_eq!(search("author:ada"), Vec::<u128>::new());
+        assert_eq!(search("re: rust"), vec![0, 2]);
+        wtxn.commit().unwrap();
+    }
+
+    #[test]
+    fn test_phrase_search() {
+        let (env, _temp_dir) = setup_test_env();
+        let mut wtxn = env.write_txn().unwrap();
+        let bm25 = HBM25Config::new(&env, &mut wtxn)
+            .unwrap()
+            .with_text_fields(Some(&[
+                TextField::new("article", "title"),
+                TextField::new("article", "body"),
+            ]))
+            .with_positions(true);
+
+        let articles = [
+            ("machine learning", "a guide"),
+            ("learning machine", "a guide"),
+            ("machine and deep learning", "a guide"),
+            ("about machine", "learning"),
+        ];
+        for (id, (title, body)) in articles.iter().enumerate() {
+            let mut article = HashMap::new();
+            article.insert("title".to_string(), Value::from(*title));
+            article.insert("body".to_string(), Value::from(*body));
+            let fields = bm25.doc_fields("article", &article).unwrap();
+            bm25.insert_fields(&mut wtxn, id as u128, fields).unwrap();
+        }
+        let mut key = b"learning".to_vec();
+        key.extend_from_slice(&3u128.to_be_bytes());
+        let positions: Vec<u32> =
+            bincode::deserialize(bm25.positions_db.get(&wtxn, &key).unwrap().unwrap()).unwrap();
+        assert_eq!(positions, vec![FIELD_POSITION_GAP]);
+
+        let search = |query: &str| {
+            let mut ids = bm25
+                .search_query(&wtxn, "article", query, 10, |_| true)
+                .unwrap()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            ids
+        };
+        assert_eq!(search(r#""machine learning""#), vec![0]);
+        assert_eq!(search(r#""machine learning"~2"#), vec![0, 1, 2]);
+        // a phrase never spans two fields
+        assert_eq!(search(r#""machine learning"~100"#), vec![0, 1, 2]);
+        assert_eq!(search(r#"about "machine learning""#), vec![0, 3]);
+
+        // the closer the terms, the higher the score
+        let results = bm25
+            .search_query(&wtxn, "article", r#""machine learning"~2"#, 10, |_| true)
+            .unwrap();
+        assert_eq!(results[0].0, 0);
+        assert_eq!(results[2].0, 2);
+
+        // deleting a doc removes its positions
+        bm25.delete_doc(&mut wtxn, 3).unwrap();
+        assert!(bm25.positions_db.get(&wtxn, &key).unwrap().is_none());
+        wtxn.commit().unwrap();
+    }
+
+    #[test]
+    fn test_phrase_search_without_positions() {
+        let (bm25, _temp_dir) = setup_bm25_config();
+        let mut wtxn = bm25.graph_env.write_txn().unwrap();
+        bm25.insert_doc(&mut wtxn, 1, "machine learning").unwrap();
+        assert!(
+            bm25.search_query(&wtxn, "article", r#""machine learning""#, 10, |_| true)
+                .is_err()
+        );
+        // a phrase of one term needs no positions
+        let results = bm25
+            .search_query(&wtxn, "article", r#""machine""#, 10, |_| true)
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        wtxn.commit().unwrap();
+    }
+
+    #[test]
+    fn test_field_search_without_positions() {
+        let (env, _temp_dir) = setup_test_env();
+        let mut wtxn = env.write_txn().unwrap();
+        let bm25 = HBM25Config::new(&env, &mut wtxn)
+            .unwrap()
+            .with_text_fields(Some(&[
+                TextField::new("article", "title"),
+                TextField::new("article", "body"),
+            ]));
+        assert!(bm25.field_frequencies_missing(&wtxn).unwrap());
+        bm25.clear(&mut wtxn).unwrap();
+        assert!(!bm25.field_frequencies_missing(&wtxn).unwrap());
+
+        let articles = [
+            ("graph databases", "graph graph rust"),
+            ("vector databases", "graph search"),
+            ("sql tables", "databases"),
+        ];
+        for (id, (title, body)) in articles.iter().enumerate() {
+            let mut article = HashMap::new();
+            article.insert("title".to_string(), Value::from(*title));
+            article.insert("body".to_string(), Value::from(*body));
+            let fields = bm25.doc_fields("article", &article).unwrap();
+            bm25.insert_fields(&mut wtxn, id as u128, fields).unwrap();
+        }
+        assert!(bm25.positions_db.is_empty(&wtxn).unwrap());
+
+        let search = |query: &str| {
+            let mut ids = bm25
+                .search_query(&wtxn, "article", query, 10, |_| true)
+                .unwrap()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            ids
+        };
+        assert_eq!(search("title:graph"), vec![0]);
+        assert_eq!(search("body:graph"), vec![0, 1]);
+        assert_eq!(search("title:databases"), vec![0, 1]);
+        assert_eq!(search("+databases -title:sql"), vec![0, 1]);
+        assert_eq!(search("body:(rust OR search)"), vec![0, 1]);
+        assert_eq!(search(r#"title:"graph""#), vec![0]);
+        // phrases of several terms still need the positions
+        assert!(
+            bm25.search_query(&wtxn, "article", r#"title:"graph databases""#, 10, |_| true)
+                .is_err()
+        );
+
+        // deleting a doc removes its field frequencies
+        bm25.delete_doc(&mut wtxn, 0).unwrap();
+        let results = bm25
+            .search_query(&wtxn, "article", "body:graph", 10, |_| true)
+            .unwrap();
+        assert_eq!(
+            results.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![1]
+        );
+        let mut key = b"rust".to_vec();
+        key.extend_from_slice(&0u128.to_be_bytes());
+        assert!(
+            bm25.field_frequencies_db
+                .get(&wtxn, &key)
+                .unwrap()
+                .is_none()
+        );
+        wtxn.commit().unwrap();
+    }
+
+    #[test]
+    fn test_parse_expanded_query() {
+        let expanded = |text: &str, expansion| QueryNode::Expanded {
+            text: text.to_string(),
+            expansion,
+        };
+        assert_eq!(
+            parse("postg* +kuberntes~ title:mongo~1 x~y * ~"),
+            vec![
+                clause(
+                    Occur::Should,
+                    None,
+                    expanded("postg", TermExpansion::Prefix)
+                ),
+                clause(
+                    Occur::Must,
+                    None,
+                    expanded("kuberntes", TermExpansion::Fuzzy(MAX_EDIT_DISTANCE))
+                ),
+                clause(
+                    Occur::Should,
+                    Some("title"),
+                    expanded("mongo", TermExpansion::Fuzzy(1))
+                ),
+                clause(Occur::Should, None, terms("x~y")),
+                clause(Occur::Should, None, terms("*")),
+                clause(Occur::Should, None, terms("~")),
+            ]
+        );
+        assert_eq!(
+            parse("graph~9"),
+            vec![clause(
+                Occur::Should,
+                None,
+                expanded("graph", TermExpansion::Fuzzy(MAX_EDIT_DISTANCE))
+            )]
+        );
+
+        assert_eq!(edit_distance("kubernetes", "kubernetes", 2), Some(0));
+        assert_eq!(edit_distance("kuberntes", "kubernetes", 2), Some(1));
+        assert_eq!(edit_distance("kubernetse", "kubernetes", 2), Some(2));
+        assert_eq!(edit_distance("kubrnts", "kubernetes", 2), None);
+        assert_eq!(edit_distance("postg", "postgresql", 2), None);
+        assert_eq!(edit_distance("naïve", "naive", 1), Some(1));
+    }
+
+    #[test]
+    fn test_expanded_search() {
+        let (bm25, _temp_dir) = setup_bm25_config();
+        let mut wtxn = bm25.graph_env.write_txn().unwrap();
+        let docs = [
+            "postgres replication guide",
+            "postgresql tuning",
+            "kubernetes operators",
+            "mongodb sharding",
+        ];
+        for (id, doc) in docs.iter().enumerate() {
+            bm25.insert_doc(&mut wtxn, id as u128, doc).unwrap();
+        }
+
+        let search = |query: &str, expansion| {
+            let mut ids = bm25
+                .search_expanded_query(&wtxn, "", query, expansion, 10, |_| true)
+                .unwrap()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>();
+            ids.sort_unstable();
+            ids
+        };
+        assert_eq!(search("postg", None), Vec::<u128>::new());
+        assert_eq!(search("postg*", None), vec![0, 1]);
+        assert_eq!(search("postgres", None), vec![0]);
+        assert_eq!(search("kuberntes", None), Vec::<u128>::new());
+        assert_eq!(search("kuberntes~", None), vec![2]);
+        assert_eq!(search("kuberntes~1", None), vec![2]);
+        assert_eq!(search("kubrnts~1", None), Vec::<u128>::new());
+        // fuzzy terms only match terms with the same first character
+        assert_eq!(search("cubernetes~1", None), Vec::<u128>::new());
+        // prefixes are matched as typed, however short
+        assert_eq!(search("po*", None), vec![0, 1]);
+        assert_eq!(search("Mongo*", None), vec![3]);
+        assert_eq!(search("+postg* -tuning", None), vec![0]);
+        // every word is expanded unless it sets its own expansion
+        assert_eq!(
+            search("kuberntes mongdb", Some(TermExpansion::Fuzzy(1))),
+            vec![2, 3]
+        );
+        assert_eq!(
+            search("postg kuberntes~1", Some(TermExpansion::Prefix)),
+            vec![0, 1, 2]
+        );
+
+        // expanded terms score less than the terms they expand to
+        let score = |query: &str| bm25.search_query(&wtxn, "", query, 10, |_| true).unwrap()[0].1;
+        assert!((score("kuberntes~") - score("kubernetes") / 2.0).abs() < 1e-6);
+        assert!((score("kubernetes~") - score("kubernetes")).abs() < 1e-6);
+        assert!((score("mongo*") - score("mongodb") * 5.0 / 7.0).abs() < 1e-6);
+        wtxn.commit().unwrap();
+    }
 }
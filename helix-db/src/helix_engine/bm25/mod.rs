@@ -1,4 +1,6 @@
+pub mod analyzer;
 pub mod bm25;
+pub mod query;
 
 #[cfg(test)]
-pub mod bm25_tests;
\ No newline at end of file
+pub mod bm25_tests;
@@ -0,0 +1,329 @@
+/// The most edits a fuzzy term can be away from the terms it matches,
+/// as the terms within more edits of a word are mostly unrelated to it
+pub const MAX_EDIT_DISTANCE: u32 = 2;
+/// The most terms of the index a prefix or fuzzy term expands to, the ones closest to the word
+/// kept, and the most frequent among those equally close
+pub const MAX_EXPANSIONS: usize = 50;
+/// The leading characters a fuzzy term shares with the terms it matches, so expanding it only
+/// scans the terms that start with them rather than the whole index
+pub const FUZZY_PREFIX_LENGTH: usize = 1;
+
+/// How a word of a BM25 query is expanded to the terms of the index it matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermExpansion {
+    /// The terms starting with the word, e.g. `postg*` matching `postgres`
+    Prefix,
+    /// The terms within the given number of edits of the word,
+    /// e.g. `kuberntes~1` matching `kubernetes`
+    Fuzzy(u32),
+}
+
+/// How a clause of a BM25 query constrains the docs it matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Occur {
+    /// Docs may match the clause, and score higher when they do, e.g. `graph`
+    Should,
+    /// Docs must match the clause, e.g. `+graph` or `graph AND db`
+    Must,
+    /// Docs must not match the clause, e.g. `-graph` or `NOT graph`
+    MustNot,
+}
+
+/// A clause of a BM25 query, matched against the text of each doc
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryClause {
+    pub occur: Occur,
+    /// The text field the clause is matched in, e.g. `title` in `title:graph`,
+    /// or `None` to match it in any field
+    pub field: Option<String>,
+    pub node: QueryNode,
+}
+
+/// What a clause of a BM25 query matches
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryNode {
+    /// A word matched anywhere in a doc, split into terms by the analyzers of the searched label
+    Terms(String),
+    /// A word matched by the terms of the index it expands to, e.g. `postg*` or `kuberntes~`
+    Expanded {
+        text: String,
+        expansion: TermExpansion,
+    },
+    /// Words matched in order next to each other, `"machine learning"`, or with up to `slop`
+    /// moves of their terms between them, `"machine learning"~3`
+    Phrase { text: String, slop: u32 },
+    /// Clauses grouped in parentheses, `(graph OR vector)`, matched as one clause
+    Group(Vec<QueryClause>),
+}
+
+/// Parses a query of words, quoted phrases and parenthesized groups, each of which can be
+/// required with `+`, prohibited with `-`, and scoped to a text field with `field:`,
+/// e.g. `+graph -sql title:"vector search"~2 (rust OR zig)`.
+///
+/// Only the fields `is_field` accepts scope a clause, so the colons of plain words,
+/// e.g. `re: meeting` or `10:30`, are kept as part of them.
+///
+/// A word ending in `*` matches the terms it prefixes, and one ending in `~N` the terms within
+/// `N` edits of it, or `MAX_EDIT_DISTANCE` edits for a bare `~`.
+///
+/// Clauses are optional unless marked, `AND` requires the clauses on both sides of it,
+/// `NOT` prohibits the clause after it, and `OR` only separates optional clauses.
+/// A quote or parenthesis that is never closed runs to the end of the query.
+pub fn parse_query(query: &str, is_field: impl Fn(&str) -> bool) -> Vec<QueryClause> {
+    let mut parser = QueryParser {
+        chars: query.chars().collect(),
+        pos: 0,
+        is_field: &is_field,
+    };
+    parser.parse_clauses(None, false)
+}
+
+struct QueryParser<'a> {
+    chars: Vec<char>,
+    pos: usize,
+    is_field: &'a dyn Fn(&str) -> bool,
+}
+
+impl QueryParser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parses clauses up to the end of the query, or of the group when `nested`,
+    /// scoping those without a field to `field`
+    fn parse_clauses(&mut self, field: Option<&str>, nested: bool) -> Vec<QueryClause> {
+        let mut clauses: Vec<QueryClause> = Vec::new();
+        let mut next_occur = None;
+        loop {
+            self.skip_whitespace();
+            let occur = match self.peek() {
+                None => break,
+                Some(')') => {
+                    self.pos += 1;
+                    if nested {
+                        break;
+                    }
+                    continue;
+                }
+                Some('+') => {
+                    self.pos += 1;
+                    Some(Occur::Must)
+                }
+                Some('-') => {
+                    self.pos += 1;
+                    Some(Occur::MustNot)
+                }
+                Some(_) => None,
+            };
+
+            let scoped = self.parse_field();
+            let is_scoped = scoped.is_some();
+            let clause_field = scoped.as_deref().or(field);
+            let node = match self.peek() {
+                Some('(') => {
+                    self.pos += 1;
+                    QueryNode::Group(self.parse_clauses(clause_field, true))
+                }
+                Some('"') => {
+                    self.pos += 1;
+                    self.parse_phrase()
+                }
+                _ => {
+                    let word = self.parse_word();
+                    if occur.is_none() && !is_scoped {
+                        match word.as_str() {
+                            "OR" => continue,
+                            "AND" => {
+                                if let Some(last) = clauses.last_mut()
+                                    && last.occur == Occur::Should
+                                {
+                                    last.occur = Occur::Must;
+                                }
+                                next_occur = Some(Occur::Must);
+                                continue;
+                            }
+                            "NOT" => {
+                                next_occur = Some(Occur::MustNot);
+                                continue;
+                            }
+                            _ => {}
+                        }
+                    }
+                    expand_word(word)
+                }
+            };
+
+            let occur = occur.or(next_occur.take()).unwrap_or(Occur::Should);
+            let is_empty = match &node {
+                QueryNode::Terms(text)
+                | QueryNode::Expanded { text, .. }
+                | QueryNode::Phrase { text, .. } => text.trim().is_empty(),
+                QueryNode::Group(clauses) => clauses.is_empty(),
+            };
+            if !is_empty {
+                clauses.push(QueryClause {
+                    occur,
+                    field: clause_field.map(str::to_string),
+                    node,
+                });
+            }
+        }
+        clauses
+    }
+
+    /// Parses the `field:` a clause is scoped to, if it starts with one that is a text field
+    fn parse_field(&mut self) -> Option<String> {
+        let len = self.chars[self.pos..]
+            .iter()
+            .take_while(|c| c.is_alphanumeric() || **c == '_')
+            .count();
+        if len == 0 || self.chars.get(self.pos + len) != Some(&':') {
+            return None;
+        }
+        let field = self.chars[self.pos..self.pos + len]
+            .iter()
+            .collect::<String>();
+        if !(self.is_field)(&field) {
+            return None;
+        }
+        self.pos += len + 1;
+        Some(field)
+    }
+
+    /// Parses the text of a phrase after its opening quote, and the `~N` slop after it
+    fn parse_phrase(&mut self) -> QueryNode {
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            if c == '"' {
+                break;
+            }
+            text.push(c);
+        }
+        let mut slop = 0;
+        if self.peek() == Some('~') {
+            self.pos += 1;
+            let mut digits = String::new();
+            while let Some(c) = self.peek().filter(char::is_ascii_digit) {
+                digits.push(c);
+                self.pos += 1;
+            }
+            slop = digits.parse().unwrap_or(0);
+        }
+        QueryNode::Phrase { text, slop }
+    }
+
+    fn parse_word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(c) = self
+            .peek()
+            .filter(|c| !c.is_whitespace() && !matches!(c, '(' | ')' | '"'))
+        {
+            word.push(c);
+            self.pos += 1;
+        }
+        word
+    }
+}
+
+/// A word of a query, expanded when it ends in `*` or `~N`
+fn expand_word(word: String) -> QueryNode {
+    if let Some(text) = word.strip_suffix('*')
+        && !text.is_empty()
+    {
+        return QueryNode::Expanded {
+            text: text.to_string(),
+            expansion: TermExpansion::Prefix,
+        };
+    }
+    if let Some((text, distance)) = word.rsplit_once('~')
+        && !text.is_empty()
+        && distance.chars().all(|c| c.is_ascii_digit())
+    {
+        let distance = distance.parse().unwrap_or(MAX_EDIT_DISTANCE);
+        return QueryNode::Expanded {
+            text: text.to_string(),
+            expansion: TermExpansion::Fuzzy(distance.min(MAX_EDIT_DISTANCE)),
+        };
+    }
+    QueryNode::Terms(word)
+}
+
+/// The number of single character insertions, deletions and substitutions that turn one word
+/// into the other, if it is at most `max_distance`
+pub fn edit_distance(a: &str, b: &str, max_distance: u32) -> Option<u32> {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let max_distance = max_distance as usize;
+    if a.len().abs_diff(b.len()) > max_distance {
+        return None;
+    }
+
+    // the distances from the prefixes of `a` to the previous and current prefix of `b`
+    let mut previous = (0..=a.len()).collect::<Vec<_>>();
+    let mut current = vec![0; a.len() + 1];
+    for (j, b_char) in b.iter().enumerate() {
+        current[0] = j + 1;
+        for (i, a_char) in a.iter().enumerate() {
+            let substitution = previous[i] + usize::from(a_char != b_char);
+            current[i + 1] = substitution.min(previous[i + 1] + 1).min(current[i] + 1);
+        }
+        // the distance never shrinks as `b` grows, so stop once every prefix is too far
+        if current.iter().all(|distance| *distance > max_distance) {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    let distance = previous[a.len()];
+    (distance <= max_distance).then_some(distance as u32)
+}
+
+/// The fewest moves of the terms of a phrase that line them up in order,
+/// given the positions of each term in a doc, if it is at most `max_slop`.
+///
+/// Each term's positions are shifted back by its place in the phrase, so the terms of an exact
+/// match share one offset, and the slop is the smallest range of offsets with one of each term.
+pub fn phrase_slop(positions: &[Vec<u32>], max_slop: u32) -> Option<u32> {
+    let offsets = positions
+        .iter()
+        .enumerate()
+        .map(|(i, positions)| {
+            let mut offsets = positions
+                .iter()
+                .map(|position| *position as i64 - i as i64)
+                .collect::<Vec<_>>();
+            offsets.sort_unstable();
+            offsets
+        })
+        .collect::<Vec<_>>();
+    if offsets.iter().any(Vec::is_empty) {
+        return None;
+    }
+
+    let mut cursors = vec![0; offsets.len()];
+    let mut best: Option<i64> = None;
+    loop {
+        let (mut min, mut min_term, mut max) = (i64::MAX, 0, i64::MIN);
+        for (term, cursor) in cursors.iter().enumerate() {
+            let offset = offsets[term][*cursor];
+            if offset < min {
+                min = offset;
+                min_term = term;
+            }
+            max = max.max(offset);
+        }
+        best = Some(best.map_or(max - min, |best| best.min(max - min)));
+        cursors[min_term] += 1;
+        if best == Some(0) || cursors[min_term] == offsets[min_term].len() {
+            break;
+        }
+    }
+    best.filter(|slop| *slop <= max_slop as i64)
+        .map(|slop| slop as u32)
+}
@@ -0,0 +1,221 @@
+//! Order-preserving encoding of [`Value`]s used as keys in the secondary indices.
+//!
+//! LMDB compares keys byte by byte, so a key's bytes must sort the same way as the
+//! value it encodes for range scans to work. Each key starts with a tag for the
+//! kind of value, followed by a payload:
+//!
+//! - all integer types share one tag and are widened so `5i32` and `5i64` encode the same
+//! - floats are widened to `f64` with their bits flipped so negatives sort first
+//! - strings are escaped and terminated so a string prefix is also a key prefix
+//! - arrays encode their elements in order, objects their fields sorted by name
+//!
+//! Every encoded value is self-delimiting, so the key of a composite index is the
+//! concatenation of its fields' keys and sorts by the first field, then the second, and so on.
+
+use crate::{
+    helix_engine::types::GraphError,
+    protocol::value::Value,
+    utils::filterable::Filterable,
+};
+use std::ops::Bound;
+
+/// Separates the fields in the name of a composite index, e.g. `tenant_id,external_id`.
+pub const COMPOSITE_INDEX_SEPARATOR: char = ',';
+
+const ARRAY_END: u8 = 0x00;
+const TAG_EMPTY: u8 = 0x01;
+const TAG_BOOLEAN: u8 = 0x02;
+const TAG_INTEGER: u8 = 0x03;
+const TAG_FLOAT: u8 = 0x04;
+const TAG_STRING: u8 = 0x05;
+const TAG_DATE: u8 = 0x06;
+const TAG_ID: u8 = 0x07;
+const TAG_ARRAY: u8 = 0x08;
+const TAG_OBJECT: u8 = 0x09;
+
+/// Encodes `value` as a secondary index key.
+pub fn encode_index_key(value: &Value) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(17);
+    encode_value(value, &mut buf);
+    buf
+}
+
+/// Encodes `values` as the key of a composite index, in the order of the index's fields.
+///
+/// The key of a leading subset of the fields is a prefix of the full key.
+pub fn encode_composite_key(values: &[Value]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(17 * values.len());
+    for value in values {
+        encode_value(value, &mut buf);
+    }
+    buf
+}
+
+/// Encodes the key of a node or edge in the secondary index named `index`, which may be composite.
+///
+/// Fails if the item is missing any of the index's fields.
+pub fn encode_item_index_key<T: Filterable>(item: &T, index: &str) -> Result<Vec<u8>, GraphError> {
+    let mut buf = Vec::with_capacity(17);
+    for field in index.split(COMPOSITE_INDEX_SEPARATOR) {
+        encode_value(&*item.check_property(field)?, &mut buf);
+    }
+    Ok(buf)
+}
+
+/// Name of the composite index over `fields`.
+pub fn composite_index_name<S: AsRef<str>>(fields: &[S]) -> String {
+    fields
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<_>>()
+        .join(&COMPOSITE_INDEX_SEPARATOR.to_string())
+}
+
+/// Encodes `prefix` as the start of the key of every string value beginning with it.
+pub fn encode_string_prefix(prefix: &str) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(prefix.len() + 1);
+    buf.push(TAG_STRING);
+    escape_bytes(prefix.as_bytes(), &mut buf);
+    buf
+}
+
+/// Converts a range of values into a range of keys.
+///
+/// An unbounded side is limited to the values of the same kind as the bounded side,
+/// so `GT(5)` never returns strings. If both sides are unbounded the whole index is covered.
+pub fn encode_range(start: Bound<&Value>, end: Bound<&Value>) -> (Bound<Vec<u8>>, Bound<Vec<u8>>) {
+    let tag = match (start, end) {
+        (Bound::Included(v) | Bound::Excluded(v), _)
+        | (_, Bound::Included(v) | Bound::Excluded(v)) => value_tag(v),
+        (Bound::Unbounded, Bound::Unbounded) => return (Bound::Unbounded, Bound::Unbounded),
+    };
+    let start = match start {
+        Bound::Included(v) => Bound::Included(encode_index_key(v)),
+        Bound::Excluded(v) => Bound::Excluded(encode_index_key(v)),
+        Bound::Unbounded => Bound::Included(vec![tag]),
+    };
+    let end = match end {
+        Bound::Included(v) => Bound::Included(encode_index_key(v)),
+        Bound::Excluded(v) => Bound::Excluded(encode_index_key(v)),
+        Bound::Unbounded => Bound::Excluded(vec![tag + 1]),
+    };
+    (start, end)
+}
+
+/// Smallest key greater than every key starting with `prefix`,
+/// or `None` if there is no such key.
+pub fn prefix_successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut successor = prefix.to_vec();
+    while let Some(last) = successor.pop() {
+        if last < u8::MAX {
+            successor.push(last + 1);
+            return Some(successor);
+        }
+    }
+    None
+}
+
+fn value_tag(value: &Value) -> u8 {
+    match value {
+        Value::Empty => TAG_EMPTY,
+        Value::Boolean(_) => TAG_BOOLEAN,
+        Value::I8(_)
+        | Value::I16(_)
+        | Value::I32(_)
+        | Value::I64(_)
+        | Value::U8(_)
+        | Value::U16(_)
+        | Value::U32(_)
+        | Value::U64(_)
+        | Value::U128(_) => TAG_INTEGER,
+        Value::F32(_) | Value::F64(_) => TAG_FLOAT,
+        Value::String(_) => TAG_STRING,
+        Value::Date(_) => TAG_DATE,
+        Value::Id(_) => TAG_ID,
+        Value::Array(_) => TAG_ARRAY,
+        Value::Object(_) => TAG_OBJECT,
+    }
+}
+
+fn encode_value(value: &Value, buf: &mut Vec<u8>) {
+    buf.push(value_tag(value));
+    match value {
+        Value::Empty => {}
+        Value::Boolean(b) => buf.push(*b as u8),
+        Value::I8(i) => encode_signed(*i as i128, buf),
+        Value::I16(i) => encode_signed(*i as i128, buf),
+        Value::I32(i) => encode_signed(*i as i128, buf),
+        Value::I64(i) => encode_signed(*i as i128, buf),
+        Value::U8(u) => encode_unsigned(*u as u128, buf),
+        Value::U16(u) => encode_unsigned(*u as u128, buf),
+        Value::U32(u) => encode_unsigned(*u as u128, buf),
+        Value::U64(u) => encode_unsigned(*u as u128, buf),
+        Value::U128(u) => encode_unsigned(*u, buf),
+        Value::F32(f) => encode_float(*f as f64, buf),
+        Value::F64(f) => encode_float(*f, buf),
+        Value::String(s) => {
+            escape_bytes(s.as_bytes(), buf);
+            buf.extend_from_slice(&[0x00, 0x00]);
+        }
+        Value::Date(d) => {
+            let dt = d.inner();
+            buf.extend_from_slice(&((dt.timestamp() as u64) ^ (1 << 63)).to_be_bytes());
+            buf.extend_from_slice(&dt.timestamp_subsec_nanos().to_be_bytes());
+        }
+        Value::Id(id) => buf.extend_from_slice(&id.inner().to_be_bytes()),
+        Value::Array(values) => {
+            for v in values {
+                encode_value(v, buf);
+            }
+            buf.push(ARRAY_END);
+        }
+        Value::Object(fields) => {
+            let mut fields = fields.iter().collect::<Vec<_>>();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            for (k, v) in fields {
+                escape_bytes(k.as_bytes(), buf);
+                buf.extend_from_slice(&[0x00, 0x00]);
+                encode_value(v, buf);
+            }
+            buf.push(ARRAY_END);
+        }
+    }
+}
+
+/// Integers are widened to 17 bytes: a sign byte then the two's complement
+/// value, which keeps negatives ordered among themselves and below positives.
+fn encode_signed(i: i128, buf: &mut Vec<u8>) {
+    match i < 0 {
+        true => {
+            buf.push(0x00);
+            buf.extend_from_slice(&(i as u128).to_be_bytes());
+        }
+        false => encode_unsigned(i as u128, buf),
+    }
+}
+
+fn encode_unsigned(u: u128, buf: &mut Vec<u8>) {
+    buf.push(0x01);
+    buf.extend_from_slice(&u.to_be_bytes());
+}
+
+fn encode_float(f: f64, buf: &mut Vec<u8>) {
+    // -0.0 and 0.0 are equal so they share a key
+    let f = if f == 0.0 { 0.0 } else { f };
+    let bits = f.to_bits();
+    let bits = match f.is_sign_negative() {
+        true => !bits,
+        false => bits ^ (1 << 63),
+    };
+    buf.extend_from_slice(&bits.to_be_bytes());
+}
+
+/// Escapes `0x00` as `0x00 0xFF` so the `0x00 0x00` terminator never occurs inside a string.
+fn escape_bytes(bytes: &[u8], buf: &mut Vec<u8>) {
+    for &b in bytes {
+        buf.push(b);
+        if b == 0x00 {
+            buf.push(0xFF);
+        }
+    }
+}
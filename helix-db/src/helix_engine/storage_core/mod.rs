@@ -1,24 +1,27 @@
 pub mod graph_visualization;
+pub mod index_key;
 pub mod storage_methods;
 pub mod version_info;
 
 use crate::{
     helix_engine::{
-        bm25::bm25::HBM25Config,
+        bm25::bm25::{BM25, HBM25Config},
         storage_core::{
+            index_key::encode_item_index_key,
             storage_methods::{DBMethods, StorageMethods},
             version_info::VersionInfo,
         },
-        traversal_core::config::Config,
+        traversal_core::config::{Config, GLOBAL_INDEX_LABEL},
         types::GraphError,
         vector_core::{
             hnsw::HNSW,
+            sparse_core::SparseCore,
             vector::HVector,
             vector_core::{HNSWConfig, VectorCore},
         },
     },
+    protocol::value::Value,
     utils::{
-        filterable::Filterable,
         items::{Edge, Node},
         label_hash::hash_label,
     },
@@ -35,6 +38,20 @@ const DB_NODES: &str = "nodes"; // for node data (n:)
 const DB_EDGES: &str = "edges"; // for edge data (e:)
 const DB_OUT_EDGES: &str = "out_edges"; // for outgoing edge indices (o:)
 const DB_IN_EDGES: &str = "in_edges"; // for incoming edge indices (i:)
+const DB_NODE_LABELS: &str = "node_labels"; // for node label indices (label -> node ids)
+const DB_EDGE_LABELS: &str = "edge_labels"; // for edge label indices (label -> edge ids)
+const DB_METADATA: &str = "metadata"; // for markers of one-off work on the database
+const INDEX_BUILT_PREFIX: &[u8] = b"index_built:"; // for each secondary index built from its items
+const DB_NAMES: [&str; 7] = [
+    DB_NODES,
+    DB_EDGES,
+    DB_OUT_EDGES,
+    DB_IN_EDGES,
+    DB_NODE_LABELS,
+    DB_EDGE_LABELS,
+    DB_METADATA,
+];
+const RUNTIME_INDEX_DBS: usize = 32; // room for the indices created with `create_secondary_index`
 
 pub type NodeId = u128;
 pub type EdgeId = u128;
@@ -52,8 +69,18 @@ pub struct HelixGraphStorage {
     pub edges_db: Database<U128<BE>, Bytes>,
     pub out_edges_db: Database<Bytes, Bytes>,
     pub in_edges_db: Database<Bytes, Bytes>,
-    pub secondary_indices: HashMap<String, Database<Bytes, U128<BE>>>,
+    pub node_labels_db: Database<Bytes, U128<BE>>,
+    pub edge_labels_db: Database<Bytes, U128<BE>>,
+    pub metadata_db: Database<Bytes, Unit>,
+    /// Secondary indices keyed by node label and then by index name,
+    /// the field name or the comma separated fields of a composite index.
+    pub secondary_indices: HashMap<String, HashMap<String, Database<Bytes, U128<BE>>>>,
+    /// Names of the indices declared `UNIQUE INDEX`, keyed by node label.
+    pub unique_indices: HashMap<String, HashSet<String>>,
+    /// Secondary indices on edge properties keyed by edge label and then by field name.
+    pub edge_secondary_indices: HashMap<String, HashMap<String, Database<Bytes, U128<BE>>>>,
     pub vectors: VectorCore,
+    pub sparse: SparseCore,
     pub bm25: Option<HBM25Config>,
     pub version_info: VersionInfo,
 
@@ -74,10 +101,24 @@ impl HelixGraphStorage {
             config.db_max_size_gb.unwrap_or(100)
         };
 
+        // each secondary index is its own lmdb db (table) on top of the core ones
+        let graph_config = config.get_graph_config();
+        let num_secondary_indices = graph_config.secondary_indices.as_ref().map_or(0, Vec::len)
+            + graph_config
+                .edge_secondary_indices
+                .as_ref()
+                .map_or(0, Vec::len);
+        let max_dbs = DB_NAMES.len()
+            + VectorCore::DB_NAMES.len()
+            + SparseCore::DB_NAMES.len()
+            + HBM25Config::DB_NAMES.len()
+            + num_secondary_indices
+            + RUNTIME_INDEX_DBS;
+
         let graph_env = unsafe {
             EnvOpenOptions::new()
                 .map_size(db_size * 1024 * 1024 * 1024)
-                .max_dbs(20)
+                .max_dbs(max_dbs as u32)
                 .max_readers(200)
                 .open(Path::new(path))?
         };
@@ -128,23 +169,83 @@ impl HelixGraphStorage {
             .name(DB_IN_EDGES)
             .create(&mut wtxn)?;
 
-        let mut secondary_indices = HashMap::new();
-        if let Some(indexes) = config.get_graph_config().secondary_indices {
+        // Node labels: [label]->[node_id]
+        //              [4 bytes]->[16 bytes]
+        //
+        // DUP_SORT used to store all node ids of a label under a single key so `N<Type>` only reads the nodes of that label.
+        // DUP_FIXED used to ensure all values are the same size meaning 8 byte length header is discarded.
+        let node_labels_db: Database<Bytes, U128<BE>> = graph_env
+            .database_options()
+            .types::<Bytes, U128<BE>>()
+            .flags(DatabaseFlags::DUP_SORT | DatabaseFlags::DUP_FIXED)
+            .name(DB_NODE_LABELS)
+            .create(&mut wtxn)?;
+
+        // Edge labels: [label]->[edge_id]
+        //              [4 bytes]->[16 bytes]
+        //
+        // DUP_SORT used to store all edge ids of a label under a single key so `E<Type>` only reads the edges of that label.
+        // DUP_FIXED used to ensure all values are the same size meaning 8 byte length header is discarded.
+        let edge_labels_db: Database<Bytes, U128<BE>> = graph_env
+            .database_options()
+            .types::<Bytes, U128<BE>>()
+            .flags(DatabaseFlags::DUP_SORT | DatabaseFlags::DUP_FIXED)
+            .name(DB_EDGE_LABELS)
+            .create(&mut wtxn)?;
+
+        // Metadata: [marker]->[]
+        //           [dynamic]->[0 bytes]
+        let metadata_db: Database<Bytes, Unit> = graph_env
+            .database_options()
+            .types::<Bytes, Unit>()
+            .name(DB_METADATA)
+            .create(&mut wtxn)?;
+
+        let mut secondary_indices: HashMap<String, HashMap<String, Database<Bytes, U128<BE>>>> =
+            HashMap::new();
+        let mut unique_indices: HashMap<String, HashSet<String>> = HashMap::new();
+        if let Some(indexes) = graph_config.secondary_indices {
             for index in indexes {
-                secondary_indices.insert(
-                    index.clone(),
-                    graph_env
-                        .database_options()
-                        .types::<Bytes, U128<BE>>()
-                        .flags(DatabaseFlags::DUP_SORT) // DUP_SORT used to store all duplicated node keys under a single key. Saves on space and requires a single read to get all values.
-                        .name(&index)
-                        .create(&mut wtxn)?,
-                );
+                if index.unique {
+                    unique_indices
+                        .entry(index.label.clone())
+                        .or_default()
+                        .insert(index.field.clone());
+                }
+                let db = graph_env
+                    .database_options()
+                    .types::<Bytes, U128<BE>>()
+                    .flags(DatabaseFlags::DUP_SORT) // DUP_SORT used to store all duplicated node keys under a single key. Saves on space and requires a single read to get all values.
+                    .name(&Self::secondary_index_name(&index.label, &index.field))
+                    .create(&mut wtxn)?;
+                secondary_indices
+                    .entry(index.label)
+                    .or_default()
+                    .insert(index.field, db);
+            }
+        }
+
+        let mut edge_secondary_indices: HashMap<
+            String,
+            HashMap<String, Database<Bytes, U128<BE>>>,
+        > = HashMap::new();
+        if let Some(indexes) = graph_config.edge_secondary_indices {
+            for index in indexes {
+                let db = graph_env
+                    .database_options()
+                    .types::<Bytes, U128<BE>>()
+                    .flags(DatabaseFlags::DUP_SORT)
+                    .name(&Self::edge_secondary_index_name(&index.label, &index.field))
+                    .create(&mut wtxn)?;
+                edge_secondary_indices
+                    .entry(index.label)
+                    .or_default()
+                    .insert(index.field, db);
             }
         }
 
         let vector_config = config.get_vector_config();
-        let vectors = VectorCore::new(
+        let mut vectors = VectorCore::new(
             &graph_env,
             &mut wtxn,
             HNSWConfig::new(
@@ -153,11 +254,26 @@ impl HelixGraphStorage {
                 vector_config.ef_search,
             ),
         )?;
+        // a split of a shared vector graph links its vectors in transactions of its own,
+        // before the settings of each vector type are checked against them
+        wtxn.commit()?;
+        vectors.split_shared_graph(&graph_env)?;
+        vectors.build_level_index(&graph_env)?;
+        let mut wtxn = graph_env.write_txn()?;
+        if let Some(indices) = vector_config.vector_indices {
+            vectors.set_indices(&mut wtxn, &indices)?;
+        }
+
+        let sparse = SparseCore::new(&graph_env, &mut wtxn)?;
 
         let bm25 = config
             .get_bm25()
             .then(|| HBM25Config::new(&graph_env, &mut wtxn))
-            .transpose()?;
+            .transpose()?
+            .map(|bm25| {
+                bm25.with_text_fields(graph_config.text_fields.as_deref())
+                    .with_positions(config.get_bm25_positions())
+            });
 
         let storage_config = StorageConfig::new(
             config.schema.unwrap_or("".to_string()),
@@ -166,18 +282,340 @@ impl HelixGraphStorage {
         );
 
         wtxn.commit()?;
-        Ok(Self {
+
+        let storage = Self {
             graph_env,
             nodes_db,
             edges_db,
             out_edges_db,
             in_edges_db,
+            node_labels_db,
+            edge_labels_db,
+            metadata_db,
             secondary_indices,
+            unique_indices,
+            edge_secondary_indices,
             vectors,
+            sparse,
             bm25,
             storage_config,
             version_info,
-        })
+        };
+
+        let mut wtxn = storage.graph_env.write_txn()?;
+        storage.backfill_label_indices(&mut wtxn)?;
+        storage.backfill_secondary_indices(&mut wtxn)?;
+        storage.backfill_edge_secondary_indices(&mut wtxn)?;
+        if let Some(bm25) = &storage.bm25
+            && (bm25.text_fields_changed(&wtxn)?
+                || bm25.positions_changed(&wtxn)?
+                || bm25.field_frequencies_missing(&wtxn)?)
+        {
+            storage.reindex_bm25(&mut wtxn)?;
+        }
+        wtxn.commit()?;
+
+        Ok(storage)
+    }
+
+    /// Populates the label indices for databases created before they existed.
+    ///
+    /// Only runs when a label index is empty while its table is not,
+    /// so it is a no-op after the first start with this version.
+    fn backfill_label_indices(&self, wtxn: &mut RwTxn) -> Result<(), GraphError> {
+        if self.node_labels_db.is_empty(wtxn)? && !self.nodes_db.is_empty(wtxn)? {
+            let mut entries = Vec::new();
+            for result in self.nodes_db.iter(wtxn)? {
+                let (id, bytes) = result?;
+                if let Ok(node) = Node::decode_node(bytes, id) {
+                    entries.push((hash_label(&node.label, None), id));
+                }
+            }
+            for (label_hash, id) in entries {
+                self.node_labels_db.put(wtxn, &label_hash, &id)?;
+            }
+        }
+
+        if self.edge_labels_db.is_empty(wtxn)? && !self.edges_db.is_empty(wtxn)? {
+            let mut entries = Vec::new();
+            for result in self.edges_db.iter(wtxn)? {
+                let (id, bytes) = result?;
+                if let Ok(edge) = Edge::decode_edge(bytes, id) {
+                    entries.push((hash_label(&edge.label, None), id));
+                }
+            }
+            for (label_hash, id) in entries {
+                self.edge_labels_db.put(wtxn, &label_hash, &id)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds the secondary indices that were never built from the nodes already stored,
+    /// e.g. indices newly declared in the schema or created before indices were scoped by label.
+    ///
+    /// Each index is marked as built in the metadata table, so its label is only scanned once.
+    fn backfill_secondary_indices(&self, wtxn: &mut RwTxn) -> Result<(), GraphError> {
+        for (label, indices) in &self.secondary_indices {
+            for (field, db) in indices {
+                if !self.is_index_built(wtxn, &Self::secondary_index_name(label, field))? {
+                    self.build_secondary_index(wtxn, label, field, db)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the edge secondary indices that were never built from the edges already stored.
+    fn backfill_edge_secondary_indices(&self, wtxn: &mut RwTxn) -> Result<(), GraphError> {
+        for (label, indices) in &self.edge_secondary_indices {
+            for (field, db) in indices {
+                let name = Self::edge_secondary_index_name(label, field);
+                if self.is_index_built(wtxn, &name)? {
+                    continue;
+                }
+
+                let label_hash = hash_label(label, None);
+                let mut entries = Vec::new();
+                if self.edge_labels_db.get(wtxn, &label_hash)?.is_some() {
+                    for result in self.edge_labels_db.prefix_iter(wtxn, &label_hash)? {
+                        let (_, id) = result?;
+                        let edge = self.get_edge(wtxn, &id)?;
+                        if edge.label == *label
+                            && let Ok(key) = encode_item_index_key(&edge, field)
+                        {
+                            entries.push((key, id));
+                        }
+                    }
+                }
+
+                // entries left behind by a build that was interrupted
+                db.clear(wtxn)?;
+                for (key, id) in entries {
+                    db.put(wtxn, &key, &id)?;
+                }
+                self.metadata_db.put(wtxn, &Self::index_built_key(&name), &())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fills the secondary index on `field` of `label` from the nodes already stored,
+    /// and marks it as built.
+    fn build_secondary_index(
+        &self,
+        wtxn: &mut RwTxn,
+        label: &str,
+        field: &str,
+        db: &Database<Bytes, U128<BE>>,
+    ) -> Result<(), GraphError> {
+        let mut entries = Vec::new();
+        if label == GLOBAL_INDEX_LABEL {
+            for result in self.nodes_db.iter(wtxn)? {
+                let (id, bytes) = result?;
+                if let Ok(node) = Node::decode_node(bytes, id)
+                    && !self
+                        .secondary_indices
+                        .get(&node.label)
+                        .is_some_and(|own| own.contains_key(field))
+                    && let Ok(key) = encode_item_index_key(&node, field)
+                {
+                    entries.push((key, id));
+                }
+            }
+        } else {
+            let label_hash = hash_label(label, None);
+            if self.node_labels_db.get(wtxn, &label_hash)?.is_some() {
+                for result in self.node_labels_db.prefix_iter(wtxn, &label_hash)? {
+                    let (_, id) = result?;
+                    let node = self.get_node(wtxn, &id)?;
+                    if node.label == label
+                        && let Ok(key) = encode_item_index_key(&node, field)
+                    {
+                        entries.push((key, id));
+                    }
+                }
+            }
+        }
+
+        // entries left behind by a build that was interrupted
+        db.clear(wtxn)?;
+        for (key, id) in entries {
+            db.put(wtxn, &key, &id)?;
+        }
+        let name = Self::secondary_index_name(label, field);
+        self.metadata_db.put(wtxn, &Self::index_built_key(&name), &())?;
+        Ok(())
+    }
+
+    /// Whether the secondary index backed by the table `name` was built from its items.
+    fn is_index_built(&self, txn: &RoTxn, name: &str) -> Result<bool, GraphError> {
+        Ok(self
+            .metadata_db
+            .get(txn, &Self::index_built_key(name))?
+            .is_some())
+    }
+
+    /// Rebuilds the bm25 index from the text fields of every node and vector,
+    /// returning the number of items indexed.
+    ///
+    /// Runs on start when the declared text fields differ from the ones the index was built from.
+    pub fn reindex_bm25(&self, wtxn: &mut RwTxn) -> Result<u64, GraphError> {
+        let Some(bm25) = &self.bm25 else {
+            return Ok(0);
+        };
+
+        let mut docs = Vec::new();
+        for result in self.nodes_db.iter(wtxn)? {
+            let (id, bytes) = result?;
+            if let Ok(node) = Node::decode_node(bytes, id)
+                && let Some(properties) = &node.properties
+                && let Some(fields) = bm25.doc_fields(&node.label, properties)
+            {
+                docs.push((id, fields));
+            }
+        }
+
+        // vectors keep their label and deletion flag among their properties
+        for vector_data_db in [self.vectors.vector_data_db, self.sparse.vector_data_db] {
+            for result in vector_data_db.iter(wtxn)? {
+                let (key, bytes) = result?;
+                let Ok(key) = key.try_into() else {
+                    continue;
+                };
+                let mut properties: HashMap<String, Value> = bincode::deserialize(bytes)?;
+                if let Some(Value::Boolean(true)) = properties.remove("is_deleted") {
+                    continue;
+                }
+                let label = match properties.remove("label") {
+                    Some(Value::String(label)) => label,
+                    _ => String::from("vector"),
+                };
+                // vectors without fields, like those of multi-vector documents, have no text
+                if properties.is_empty() {
+                    continue;
+                }
+                if let Some(fields) = bm25.doc_fields(&label, &properties) {
+                    docs.push((u128::from_be_bytes(key), fields));
+                }
+            }
+        }
+
+        bm25.clear(wtxn)?;
+        let indexed = docs.len() as u64;
+        for (id, fields) in docs {
+            bm25.insert_fields(wtxn, id, fields)?;
+        }
+        Ok(indexed)
+    }
+
+    /// Name of the lmdb db (table) backing the secondary index on `field` of `label`.
+    #[inline(always)]
+    pub fn secondary_index_name(label: &str, field: &str) -> String {
+        format!("idx_{label}_{field}")
+    }
+
+    /// Key of the metadata marking the secondary index backed by the table `name` as built.
+    #[inline(always)]
+    fn index_built_key(name: &str) -> Vec<u8> {
+        [INDEX_BUILT_PREFIX, name.as_bytes()].concat()
+    }
+
+    /// Name of the lmdb db (table) backing the secondary index on `field` of edges with `label`.
+    #[inline(always)]
+    pub fn edge_secondary_index_name(label: &str, field: &str) -> String {
+        format!("edge_idx_{label}_{field}")
+    }
+
+    /// Gets the secondary index on `field` of edges with the given `label`.
+    #[inline(always)]
+    pub fn get_edge_secondary_index(
+        &self,
+        label: &str,
+        field: &str,
+    ) -> Option<&Database<Bytes, U128<BE>>> {
+        self.edge_secondary_indices
+            .get(label)
+            .and_then(|indices| indices.get(field))
+    }
+
+    /// Gets the secondary index on `field` of nodes with the given `label`,
+    /// or the global index on `field` if the label has none of its own.
+    #[inline(always)]
+    pub fn get_secondary_index(
+        &self,
+        label: &str,
+        field: &str,
+    ) -> Option<&Database<Bytes, U128<BE>>> {
+        [label, GLOBAL_INDEX_LABEL]
+            .iter()
+            .find_map(|label| self.secondary_indices.get(*label)?.get(field))
+    }
+
+    /// The secondary indices nodes of `label` are kept in, by indexed field:
+    /// the label's own and the global ones on fields it has no index of its own on.
+    pub fn node_indices(&self, label: &str) -> Vec<(&String, &Database<Bytes, U128<BE>>)> {
+        let own = self.secondary_indices.get(label);
+        let global = self
+            .secondary_indices
+            .get(GLOBAL_INDEX_LABEL)
+            .into_iter()
+            .flatten()
+            .filter(|(field, _)| !own.is_some_and(|own| own.contains_key(*field)));
+        own.into_iter().flatten().chain(global).collect()
+    }
+
+    /// Removes the entries of the edge `edge_id` from the secondary indices on its label.
+    pub fn delete_edge_index_keys(
+        &self,
+        txn: &mut RwTxn,
+        edge_id: &u128,
+        edge: &Edge,
+    ) -> Result<(), GraphError> {
+        if let Some(indices) = self.edge_secondary_indices.get(&edge.label) {
+            for (field, db) in indices {
+                if let Ok(key) = encode_item_index_key(edge, field) {
+                    db.delete_one_duplicate(txn, &key, edge_id)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that no node other than `id` holds the encoded `key` in the
+    /// secondary index on `field` of `label`, if that index is unique.
+    pub fn check_unique(
+        &self,
+        txn: &RoTxn,
+        label: &str,
+        field: &str,
+        key: &[u8],
+        id: &u128,
+    ) -> Result<(), GraphError> {
+        if !self
+            .unique_indices
+            .get(label)
+            .is_some_and(|fields| fields.contains(field))
+        {
+            return Ok(());
+        }
+        let Some(db) = self.get_secondary_index(label, field) else {
+            return Ok(());
+        };
+        if let Some(ids) = db.get_duplicates(txn, key)? {
+            for result in ids {
+                let (_, existing) = result?;
+                if existing != *id {
+                    return Err(GraphError::UniqueConstraintViolation {
+                        label: label.to_string(),
+                        field: field.to_string(),
+                        id: existing,
+                    });
+                }
+            }
+        }
+        Ok(())
     }
 
     /// Used because in the case the key changes in the future.
@@ -256,9 +694,13 @@ impl HelixGraphStorage {
         Ok((edge_id, node_id))
     }
 
-    /// Gets a vector from level 0 of HNSW index (because that's where all are stored)
+    /// Gets a vector from level 0 of HNSW index (because that's where all are stored),
+    /// or from the sparse vectors if it is one
     pub fn get_vector(&self, txn: &RoTxn, id: &u128) -> Result<HVector, GraphError> {
-        Ok(self.vectors.get_vector(txn, *id, 0, true)?)
+        match self.sparse.contains(txn, *id)? {
+            true => Ok(self.sparse.get(txn, *id, true)?),
+            false => Ok(self.vectors.get_vector(txn, *id, 0, true)?),
+        }
     }
 }
 
@@ -277,25 +719,39 @@ impl StorageConfig {
 }
 
 impl DBMethods for HelixGraphStorage {
-    /// Creates a secondary index lmdb db (table) for a given index name
-    fn create_secondary_index(&mut self, name: &str) -> Result<(), GraphError> {
+    /// Creates a secondary index lmdb db (table) for a given label and field
+    fn create_secondary_index(&mut self, label: &str, field: &str) -> Result<(), GraphError> {
         let mut wtxn = self.graph_env.write_txn()?;
-        let db = self.graph_env.create_database(&mut wtxn, Some(name))?;
+        let db = self
+            .graph_env
+            .database_options()
+            .types::<Bytes, U128<BE>>()
+            .flags(DatabaseFlags::DUP_SORT)
+            .name(&Self::secondary_index_name(label, field))
+            .create(&mut wtxn)?;
+        self.build_secondary_index(&mut wtxn, label, field, &db)?;
         wtxn.commit()?;
-        self.secondary_indices.insert(name.to_string(), db);
+        self.secondary_indices
+            .entry(label.to_string())
+            .or_default()
+            .insert(field.to_string(), db);
         Ok(())
     }
 
-    /// Drops a secondary index lmdb db (table) for a given index name
-    fn drop_secondary_index(&mut self, name: &str) -> Result<(), GraphError> {
+    /// Drops a secondary index lmdb db (table) for a given label and field
+    fn drop_secondary_index(&mut self, label: &str, field: &str) -> Result<(), GraphError> {
         let mut wtxn = self.graph_env.write_txn()?;
-        let db = self
-            .secondary_indices
-            .get(name)
-            .ok_or(GraphError::New(format!("Secondary Index {name} not found")))?;
+        let db = self.get_secondary_index(label, field).ok_or(GraphError::New(format!(
+            "Secondary Index {label}.{field} not found"
+        )))?;
         db.clear(&mut wtxn)?;
+        let name = Self::secondary_index_name(label, field);
+        self.metadata_db
+            .delete(&mut wtxn, &Self::index_built_key(&name))?;
         wtxn.commit()?;
-        self.secondary_indices.remove(name);
+        if let Some(indices) = self.secondary_indices.get_mut(label) {
+            indices.remove(field);
+        }
         Ok(())
     }
 }
@@ -346,7 +802,7 @@ impl StorageMethods for HelixGraphStorage {
             let mut label = [0u8; 4];
             label.copy_from_slice(&key[16..20]);
             let (edge_id, to_node_id) = Self::unpack_adj_edge_data(value)?;
-            edges.insert(edge_id);
+            edges.insert((edge_id, label));
             out_edges.insert(label);
             other_in_edges.push((to_node_id, label, edge_id));
         }
@@ -362,7 +818,7 @@ impl StorageMethods for HelixGraphStorage {
             label.copy_from_slice(&key[16..20]);
             let (edge_id, from_node_id) = Self::unpack_adj_edge_data(value)?;
             in_edges.insert(label);
-            edges.insert(edge_id);
+            edges.insert((edge_id, label));
             other_out_edges.push((from_node_id, label, edge_id));
         }
 
@@ -370,8 +826,15 @@ impl StorageMethods for HelixGraphStorage {
 
         // println!("Deleting edges: {}", );
         // Delete all related data
-        for edge in edges {
+        for (edge, label_bytes) in edges {
+            // index keys are built from the edge's properties, so it is read before it goes
+            if !self.edge_secondary_indices.is_empty() {
+                let data = self.get_edge(txn, &edge)?;
+                self.delete_edge_index_keys(txn, &edge, &data)?;
+            }
             self.edges_db.delete(txn, Self::edge_key(&edge))?;
+            self.edge_labels_db
+                .delete_one_duplicate(txn, &label_bytes, &edge)?;
         }
         for label_bytes in out_edges.iter() {
             self.out_edges_db
@@ -399,17 +862,14 @@ impl StorageMethods for HelixGraphStorage {
 
         // delete secondary indices
         let node = self.get_node(txn, id)?;
-        for (index_name, db) in &self.secondary_indices {
-            // Use check_property like we do when adding, to handle id, label, and regular properties consistently
-            match node.check_property(index_name) {
-                Ok(value) => match bincode::serialize(&*value) {
-                    Ok(serialized) => {
-                        if let Err(e) = db.delete_one_duplicate(txn, &serialized, &node.id) {
-                            return Err(GraphError::from(e));
-                        }
+        for (index, db) in self.node_indices(&node.label) {
+            // Encoded like we do when adding, to handle id, label, and regular properties consistently
+            match encode_item_index_key(&node, index) {
+                Ok(key) => {
+                    if let Err(e) = db.delete_one_duplicate(txn, &key, &node.id) {
+                        return Err(GraphError::from(e));
                     }
-                    Err(e) => return Err(GraphError::from(e)),
-                },
+                }
                 Err(_) => {
                     // Property not found - this is expected for some indices
                     // Continue to next index
@@ -418,6 +878,8 @@ impl StorageMethods for HelixGraphStorage {
         }
 
         // Delete node data and label
+        self.node_labels_db
+            .delete_one_duplicate(txn, &hash_label(&node.label, None), id)?;
         self.nodes_db.delete(txn, Self::node_key(id))?;
 
         Ok(())
@@ -435,6 +897,8 @@ impl StorageMethods for HelixGraphStorage {
         let in_edge_value = Self::pack_edge_data(edge_id, &edge.from_node);
         // Delete all edge-related data
         self.edges_db.delete(txn, Self::edge_key(edge_id))?;
+        self.edge_labels_db
+            .delete_one_duplicate(txn, &label_hash, edge_id)?;
         self.out_edges_db.delete_one_duplicate(
             txn,
             &Self::out_edge_key(&edge.from_node, &label_hash),
@@ -445,6 +909,7 @@ impl StorageMethods for HelixGraphStorage {
             &Self::in_edge_key(&edge.to_node, &label_hash),
             &in_edge_value,
         )?;
+        self.delete_edge_index_keys(txn, edge_id, &edge)?;
 
         Ok(())
     }
@@ -466,7 +931,7 @@ impl StorageMethods for HelixGraphStorage {
             let mut label = [0u8; 4];
             label.copy_from_slice(&key[16..20]);
             let (edge_id, to_node_id) = Self::unpack_adj_edge_data(value)?;
-            edges.insert(edge_id);
+            edges.insert((edge_id, label));
             out_edges.insert(label);
             other_in_edges.push((to_node_id, label, edge_id));
         }
@@ -482,7 +947,7 @@ impl StorageMethods for HelixGraphStorage {
             label.copy_from_slice(&key[16..20]);
             let (edge_id, from_node_id) = Self::unpack_adj_edge_data(value)?;
             in_edges.insert(label);
-            edges.insert(edge_id);
+            edges.insert((edge_id, label));
             other_out_edges.push((from_node_id, label, edge_id));
         }
 
@@ -490,8 +955,15 @@ impl StorageMethods for HelixGraphStorage {
 
         // println!("Deleting edges: {}", );
         // Delete all related data
-        for edge in edges {
+        for (edge, label_bytes) in edges {
+            // index keys are built from the edge's properties, so it is read before it goes
+            if !self.edge_secondary_indices.is_empty() {
+                let data = self.get_edge(txn, &edge)?;
+                self.delete_edge_index_keys(txn, &edge, &data)?;
+            }
             self.edges_db.delete(txn, Self::edge_key(&edge))?;
+            self.edge_labels_db
+                .delete_one_duplicate(txn, &label_bytes, &edge)?;
         }
         for label_bytes in out_edges.iter() {
             self.out_edges_db
@@ -518,7 +990,10 @@ impl StorageMethods for HelixGraphStorage {
         }
 
         // Delete vector data
-        self.vectors.delete(txn, *id)?;
+        match self.sparse.contains(txn, *id)? {
+            true => self.sparse.delete(txn, *id)?,
+            false => self.vectors.delete(txn, *id)?,
+        }
 
         Ok(())
     }
@@ -3,11 +3,11 @@ use crate::utils::items::{Edge, Node};
 use heed3::{RoTxn, RwTxn};
 
 pub trait DBMethods {
-    /// Creates a new database with a given name for a secondary index
-    fn create_secondary_index(&mut self, name: &str) -> Result<(), GraphError>;
+    /// Creates a new database for a secondary index on a field of a node label
+    fn create_secondary_index(&mut self, label: &str, field: &str) -> Result<(), GraphError>;
 
-    /// Opens a database with a given name for a secondary index
-    fn drop_secondary_index(&mut self, name: &str) -> Result<(), GraphError>;
+    /// Drops the database of a secondary index on a field of a node label
+    fn drop_secondary_index(&mut self, label: &str, field: &str) -> Result<(), GraphError>;
 }
 
 pub trait BasicStorageMethods {
@@ -1,10 +1,18 @@
 // MAKE SURE TO --release
 use crate::{
-    helix_engine::vector_core::{
-        hnsw::HNSW,
-        vector::HVector,
-        vector_core::{HNSWConfig, VectorCore},
+    helix_engine::{
+        traversal_core::config::VectorIndex,
+        types::VectorError,
+        vector_core::{
+            hnsw::HNSW,
+            vector::HVector,
+            vector_core::{HNSWConfig, SearchParams, VectorCore},
+            vector_distance::DistanceMetric,
+            vector_encoding::VectorEncoding,
+        },
     },
+    protocol::value::Value,
+    utils::filterable::Filterable,
 };
 use heed3::{Env, EnvOpenOptions, RoTxn};
 use rand::{
@@ -59,7 +67,7 @@ fn calc_ground_truths(
                             .iter()
                             .filter_map(|base_vec| {
                                 query_hvector
-                                    .distance_to(base_vec)
+                                    .distance_to(base_vec, DistanceMetric::Cosine)
                                     .map(|dist| (base_vec.id.clone(), dist))
                                     .ok()
                             })
@@ -225,7 +233,7 @@ fn test_hnsw_search() {
     let mut total_recall = 0.0;
     let mut total_precision = 0.0;
     for (qid, query) in query_vectors {
-        let results = index.search::<Filter>(&txn, &query, k, "vector", None, false).unwrap();
+        let results = index.search::<Filter>(&txn, &query, k, "vector", None, false, SearchParams::default()).unwrap();
 
         let result_indices = results
             .into_iter()
@@ -259,6 +267,42 @@ fn test_hnsw_search() {
     assert!(total_precision>= 0.8, "precision not high enough!");
 }
 
+#[test]
+fn test_hnsw_metric_per_vector_type() {
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let mut index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    index
+        .set_indices(&mut txn, &[VectorIndex::new("Point", DistanceMetric::Euclidean)])
+        .unwrap();
+
+    let label = |label: &str| Some(vec![("label".to_string(), Value::String(label.to_string()))]);
+    let near_angle = index.insert::<Filter>(&mut txn, &[1.0, 0.0], label("Point")).unwrap();
+    let near_point = index.insert::<Filter>(&mut txn, &[10.0, 1.0], label("Point")).unwrap();
+
+    // cosine would pick `near_angle`, which points in the same direction as the query
+    let results = index.search::<Filter>(&txn, &[9.0, 0.0], 1, "Point", None, false, SearchParams::default()).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, near_point.id);
+    assert_ne!(results[0].id, near_angle.id);
+    assert_eq!(index.metric("Other"), DistanceMetric::Cosine);
+
+    // the metric is stored with the index
+    let mut index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    assert_eq!(index.metric("Point"), DistanceMetric::Euclidean);
+
+    // and cannot change once the vector type has vectors
+    assert!(
+        index
+            .set_indices(&mut txn, &[VectorIndex::new("Point", DistanceMetric::Cosine)])
+            .is_err()
+    );
+    index
+        .set_indices(&mut txn, &[VectorIndex::new("Other", DistanceMetric::InnerProduct)])
+        .unwrap();
+    assert_eq!(index.metric("Other"), DistanceMetric::InnerProduct);
+}
+
 #[test]
 fn test_hnsw_search_property_ordering() {
 }
@@ -269,5 +313,823 @@ fn test_hnsw_search_filter_ordering() {
 
 #[test]
 fn test_hnsw_delete() {
+    let n_base = 1_000;
+    let n_delete = 500;
+    let dims = 128;
+    let n_query = 100;
+    let k = 10;
+    let mut rng = rand::rng();
+    let mut vectors = gen_sim_vecs(n_base + n_query, dims, 0.8);
+
+    vectors.shuffle(&mut rng);
+    let query_vectors = vectors[n_base..]
+        .iter()
+        .enumerate()
+        .map(|(i, x)| (i + 1, x.clone()))
+        .collect::<Vec<(usize, Vec<f64>)>>();
+
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+
+    let mut base_all_vectors: Vec<HVector> = Vec::new();
+    for data in vectors[..n_base].iter() {
+        base_all_vectors.push(index.insert::<Filter>(&mut txn, data, None).unwrap());
+    }
+
+    base_all_vectors.shuffle(&mut rng);
+    let remaining = base_all_vectors.split_off(n_delete);
+    for vector in &base_all_vectors {
+        index.delete(&mut txn, vector.id).unwrap();
+    }
+    assert!(matches!(
+        index.delete(&mut txn, base_all_vectors[0].id),
+        Err(VectorError::VectorNotFound(_))
+    ));
+    txn.commit().unwrap();
+
+    let txn = env.read_txn().unwrap();
+    let deleted = base_all_vectors
+        .iter()
+        .map(|vector| vector.id)
+        .collect::<HashSet<u128>>();
+
+    for id in &deleted {
+        assert!(index.get_vector(&txn, *id, 0, false).is_err());
+        let out_edges = index
+            .edges_db
+            .prefix_iter(&txn, &id.to_be_bytes())
+            .unwrap()
+            .count();
+        assert_eq!(out_edges, 0);
+    }
+
+    let ground_truths = calc_ground_truths(remaining, &query_vectors, k);
+
+    let mut total_recall = 0.0;
+    for (qid, query) in query_vectors {
+        let results = index.search::<Filter>(&txn, &query, k, "vector", None, false, SearchParams::default()).unwrap();
+        assert_eq!(results.len(), k);
+
+        let result_indices = results
+            .into_iter()
+            .map(|hvec| hvec.get_id())
+            .collect::<HashSet<u128>>();
+        assert!(result_indices.is_disjoint(&deleted));
+
+        let gt_indices = ground_truths
+            .get(&qid)
+            .unwrap()
+            .clone()
+            .into_iter()
+            .collect::<HashSet<u128>>();
+
+        let true_positives = result_indices.intersection(&gt_indices).count();
+        total_recall += true_positives as f64 / gt_indices.len() as f64;
+    }
+
+    total_recall = total_recall / n_query as f64;
+    println!("avg. recall after deleting {n_delete} of {n_base}: {total_recall:.4?}");
+    assert!(total_recall >= 0.8, "recall not high enough after deletes!");
+}
+
+#[test]
+fn test_hnsw_delete_all() {
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+
+    let vectors = gen_sim_vecs(200, 32, 0.8);
+    let inserted = vectors
+        .iter()
+        .map(|data| index.insert::<Filter>(&mut txn, data, None).unwrap())
+        .collect::<Vec<_>>();
+
+    // every delete eventually removes the entry point, which must be replaced
+    for vector in &inserted {
+        index.delete(&mut txn, vector.id).unwrap();
+        assert!(index.get_vector(&txn, vector.id, 0, false).is_err());
+    }
+
+    assert_eq!(index.num_inserted_vectors(&txn).unwrap(), 0);
+    assert_eq!(index.edges_db.len(&txn).unwrap(), 0);
+    assert!(index.search::<Filter>(&txn, &vectors[0], 10, "vector", None, false, SearchParams::default()).is_err());
+
+    let reinserted = index.insert::<Filter>(&mut txn, &vectors[0], None).unwrap();
+    let results = index.search::<Filter>(&txn, &vectors[0], 10, "vector", None, false, SearchParams::default()).unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id, reinserted.id);
+}
+
+
+#[test]
+fn test_hnsw_graph_per_vector_type() {
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+
+    let label = |label: &str| Some(vec![("label".to_string(), Value::String(label.to_string()))]);
+    let docs = gen_sim_vecs(200, 64, 0.8)
+        .iter()
+        .map(|data| index.insert::<Filter>(&mut txn, data, label("Doc")).unwrap().id)
+        .collect::<HashSet<u128>>();
+    // a different dimension would fail the distance calculation if the graphs were shared
+    let images = gen_sim_vecs(200, 16, 0.8)
+        .iter()
+        .map(|data| index.insert::<Filter>(&mut txn, data, label("Image")).unwrap().id)
+        .collect::<HashSet<u128>>();
+
+    let results = index
+        .search::<Filter>(&txn, &gen_sim_vecs(1, 64, 0.8)[0], 50, "Doc", None, false, SearchParams::default())
+        .unwrap();
+    assert_eq!(results.len(), 50);
+    assert!(results.iter().all(|v| docs.contains(&v.id)));
+
+    let results = index
+        .search::<Filter>(&txn, &gen_sim_vecs(1, 16, 0.8)[0], 50, "Image", None, false, SearchParams::default())
+        .unwrap();
+    assert_eq!(results.len(), 50);
+    assert!(results.iter().all(|v| images.contains(&v.id)));
+
+    // deleting every vector of one type leaves the other searchable
+    for id in &images {
+        index.delete(&mut txn, *id).unwrap();
+    }
+    assert!(index.search::<Filter>(&txn, &gen_sim_vecs(1, 16, 0.8)[0], 10, "Image", None, false, SearchParams::default()).is_err());
+    let results = index
+        .search::<Filter>(&txn, &gen_sim_vecs(1, 64, 0.8)[0], 10, "Doc", None, false, SearchParams::default())
+        .unwrap();
+    assert_eq!(results.len(), 10);
+}
+
+#[test]
+fn test_hnsw_config_per_vector_type() {
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let mut index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    index
+        .set_indices(
+            &mut txn,
+            &[VectorIndex::new("Doc", DistanceMetric::Cosine).with_hnsw(Some(32), None, Some(64))],
+        )
+        .unwrap();
+
+    assert_eq!(index.config("Doc").m, 32);
+    assert_eq!(index.config("Doc").m_max_0, 64);
+    assert_eq!(index.config("Doc").ef, 64);
+    assert_eq!(index.config("Doc").ef_construct, index.config.ef_construct);
+    assert_eq!(index.config("Image").m, index.config.m);
+}
+
+#[test]
+fn test_hnsw_split_shared_graph() {
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+
+    let label = |label: &str| Some(vec![("label".to_string(), Value::String(label.to_string()))]);
+    let docs = gen_sim_vecs(100, 32, 0.8)
+        .iter()
+        .map(|data| index.insert::<Filter>(&mut txn, data, label("Doc")).unwrap().id)
+        .collect::<HashSet<u128>>();
+    index.insert::<Filter>(&mut txn, &[1.0, 0.0], label("Image")).unwrap();
+
+    // a database from before each type had its own graph has a single entry point
+    let ep = *docs.iter().next().unwrap();
+    index
+        .vectors_db
+        .put(&mut txn, b"entry_point", &ep.to_be_bytes())
+        .unwrap();
+    txn.commit().unwrap();
+
+    index.split_shared_graph(&env).unwrap();
+    let txn = env.read_txn().unwrap();
+    assert!(index.vectors_db.get(&txn, b"entry_point").unwrap().is_none());
+
+    let results = index
+        .search::<Filter>(&txn, &gen_sim_vecs(1, 32, 0.8)[0], 10, "Doc", None, false, SearchParams::default())
+        .unwrap();
+    assert_eq!(results.len(), 10);
+    assert!(results.iter().all(|v| docs.contains(&v.id)));
+    let results = index.search::<Filter>(&txn, &[0.5, 0.5], 10, "Image", None, false, SearchParams::default()).unwrap();
+    assert_eq!(results.len(), 1);
+}
+
+#[test]
+fn test_hnsw_split_shared_graph_purges_deleted_vectors() {
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+
+    let fields = |is_deleted: bool| {
+        Some(vec![
+            ("label".to_string(), Value::String("Doc".to_string())),
+            ("is_deleted".to_string(), Value::Boolean(is_deleted)),
+        ])
+    };
+    let live = index.insert::<Filter>(&mut txn, &[1.0, 0.0], fields(false)).unwrap().id;
+    let deleted = index.insert::<Filter>(&mut txn, &[0.9, 0.1], fields(true)).unwrap().id;
+    index
+        .vectors_db
+        .put(&mut txn, b"entry_point", &deleted.to_be_bytes())
+        .unwrap();
+    txn.commit().unwrap();
+
+    index.split_shared_graph(&env).unwrap();
+    let txn = env.read_txn().unwrap();
+    assert!(matches!(
+        index.get_vector(&txn, deleted, 0, true),
+        Err(VectorError::VectorNotFound(_))
+    ));
+    let results = index
+        .search::<Filter>(&txn, &[1.0, 0.0], 10, "Doc", None, false, SearchParams::default())
+        .unwrap();
+    assert_eq!(results.iter().map(|v| v.id).collect::<Vec<_>>(), vec![live]);
+}
+
+#[test]
+fn test_hnsw_build_level_index() {
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+
+    let label = |label: &str| Some(vec![("label".to_string(), Value::String(label.to_string()))]);
+    let mut docs = gen_sim_vecs(200, 32, 0.8)
+        .iter()
+        .map(|data| index.insert::<Filter>(&mut txn, data, label("Doc")).unwrap().id)
+        .collect::<HashSet<u128>>();
+    index.insert::<Filter>(&mut txn, &[1.0, 0.0], label("Image")).unwrap();
+
+    // a database from before the levels were indexed only has the vectors
+    let keys = index
+        .vectors_db
+        .prefix_iter(&txn, b"level:")
+        .unwrap()
+        .map(|result| result.unwrap().0.to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(keys.len(), 201);
+    for key in keys {
+        index.vectors_db.delete(&mut txn, &key).unwrap();
+    }
+    txn.commit().unwrap();
+
+    index.build_level_index(&env).unwrap();
+    let mut txn = env.write_txn().unwrap();
+    assert_eq!(index.vectors_db.prefix_iter(&txn, b"level:Doc\0").unwrap().count(), 200);
+    assert_eq!(index.vectors_db.prefix_iter(&txn, b"level:Image\0").unwrap().count(), 1);
+
+    // deleting the entry point makes a vector of the type on the highest level left the new one
+    let top_levels = |txn: &RoTxn| {
+        let mut levels: HashMap<u128, usize> = HashMap::new();
+        for result in index.vectors_db.prefix_iter(txn, b"v:").unwrap() {
+            let key = result.unwrap().0;
+            let id = u128::from_be_bytes(key[2..18].try_into().unwrap());
+            let level = usize::from_be_bytes(key[18..].try_into().unwrap());
+            let top = levels.entry(id).or_default();
+            *top = (*top).max(level);
+        }
+        levels
+    };
+    let entry_point = |txn: &RoTxn| {
+        let bytes = index.vectors_db.get(txn, b"entry_point:Doc").unwrap().unwrap();
+        u128::from_be_bytes(bytes.try_into().unwrap())
+    };
+    for _ in 0..20 {
+        let ep = entry_point(&txn);
+        index.delete(&mut txn, ep).unwrap();
+        docs.remove(&ep);
+
+        let levels = top_levels(&txn);
+        let ep = entry_point(&txn);
+        assert!(docs.contains(&ep));
+        assert_eq!(
+            levels[&ep],
+            docs.iter().map(|id| levels[id]).max().unwrap()
+        );
+    }
+
+    for id in docs {
+        index.delete(&mut txn, id).unwrap();
+    }
+    assert!(index.search::<Filter>(&txn, &gen_sim_vecs(1, 32, 0.8)[0], 10, "Doc", None, false, SearchParams::default()).is_err());
+    let results = index.search::<Filter>(&txn, &[0.5, 0.5], 10, "Image", None, false, SearchParams::default()).unwrap();
+    assert_eq!(results.len(), 1);
+}
+
+#[test]
+fn test_hnsw_dimension_per_vector_type() {
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let mut index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    index
+        .set_indices(&mut txn, &[VectorIndex::new("Doc", DistanceMetric::Cosine).with_dimension(3)])
+        .unwrap();
+
+    let label = |label: &str| Some(vec![("label".to_string(), Value::String(label.to_string()))]);
+    assert!(matches!(
+        index.insert::<Filter>(&mut txn, &[1.0, 2.0], label("Doc")),
+        Err(VectorError::InvalidVectorDimension { expected: 3, found: 2, .. })
+    ));
+    index.insert::<Filter>(&mut txn, &[1.0, 2.0, 3.0], label("Doc")).unwrap();
+
+    assert!(matches!(
+        index.search::<Filter>(&txn, &[1.0, 2.0], 1, "Doc", None, false, SearchParams::default()),
+        Err(VectorError::InvalidVectorDimension { expected: 3, found: 2, .. })
+    ));
+
+    // the dimension cannot change once the vector type has vectors
+    let mut index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    assert!(
+        index
+            .set_indices(&mut txn, &[VectorIndex::new("Doc", DistanceMetric::Cosine).with_dimension(4)])
+            .is_err()
+    );
+}
+
+#[test]
+fn test_hnsw_encoding_per_vector_type() {
+    let n_base = 500;
+    let dims = 64;
+    let mut rng = rand::rng();
+    let vectors: Vec<Vec<f64>> = (0..n_base)
+        .map(|_| (0..dims).map(|_| rng.random_range(-1.0..1.0)).collect())
+        .collect();
+
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let mut index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    let encodings = [VectorEncoding::F32, VectorEncoding::Int8, VectorEncoding::Binary];
+    let indices: Vec<VectorIndex> = encodings
+        .iter()
+        .map(|encoding| {
+            VectorIndex::new(encoding.as_str(), DistanceMetric::Cosine).with_encoding(*encoding)
+        })
+        .collect();
+    index.set_indices(&mut txn, &indices).unwrap();
+
+    let label = |label: &str| Some(vec![("label".to_string(), Value::String(label.to_string()))]);
+    for encoding in encodings {
+        let inserted: Vec<HVector> = vectors
+            .iter()
+            .map(|data| index.insert::<Filter>(&mut txn, data, label(encoding.as_str())).unwrap())
+            .collect();
+
+        // each vector is its own nearest neighbor, and comes back close to how it was inserted
+        let mut found = 0;
+        for (vector, data) in inserted.iter().zip(&vectors) {
+            let results = index
+                .search::<Filter>(&txn, data, 1, encoding.as_str(), None, false, SearchParams::default())
+                .unwrap();
+            if results[0].id == vector.id {
+                found += 1;
+                assert!(results[0].get_distance() < 0.01);
+            }
+            assert_eq!(results[0].get_data().len(), dims);
+        }
+        assert!(found as f64 / n_base as f64 > 0.9, "{encoding}: {found}/{n_base}");
+
+        let stored = index.get_vector(&txn, inserted[0].id, 0, true).unwrap();
+        for (a, b) in stored.get_data().iter().zip(&vectors[0]) {
+            assert!((a - b).abs() < 0.01);
+        }
+
+        // binary vectors are rescored with an int8 copy, a byte per dimension after its scale
+        if encoding == VectorEncoding::Binary {
+            let key = [b"r:".as_slice(), &inserted[0].id.to_be_bytes()].concat();
+            let copy = index.vectors_db.get(&txn, &key).unwrap().unwrap();
+            assert_eq!(copy.len(), 4 + dims);
+        }
+    }
+
+    // the encoding is stored with the index, and cannot change once the vector type has vectors
+    let mut index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    assert_eq!(index.encoding("int8"), VectorEncoding::Int8);
+    assert_eq!(index.encoding("Other"), VectorEncoding::F64);
+    assert!(
+        index
+            .set_indices(&mut txn, &[VectorIndex::new("int8", DistanceMetric::Cosine)])
+            .is_err()
+    );
+}
+
+#[test]
+fn test_hnsw_search_prefilter() {
+    let n_base = 2_000;
+    let dims = 32;
+    let k = 10;
+    let vectors = gen_sim_vecs(n_base, dims, 0.8);
+
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    for (i, data) in vectors.iter().enumerate() {
+        let fields = vec![
+            ("label".to_string(), Value::String("Doc".to_string())),
+            ("n".to_string(), Value::I32(i as i32)),
+        ];
+        index.insert::<Filter>(&mut txn, data, Some(fields)).unwrap();
+    }
+    txn.commit().unwrap();
+    let txn = env.read_txn().unwrap();
+
+    let n = |v: &HVector| match v.check_property("n").unwrap().as_ref() {
+        Value::I32(n) => *n,
+        _ => panic!("n is not an I32"),
+    };
+    let query = &vectors[0];
+
+    // a quarter of the vectors match, the graph still finds k of them
+    let filter = |v: &HVector, _: &RoTxn| n(v) % 4 == 0;
+    let results = index.search(&txn, query, k, "Doc", Some(&[filter]), true, SearchParams::default()).unwrap();
+    assert_eq!(results.len(), k);
+    assert!(results.iter().all(|v| n(v) % 4 == 0));
+    assert!(results.windows(2).all(|w| w[0].get_distance() <= w[1].get_distance()));
+
+    // too few vectors match for the graph, the label is scanned instead
+    let filter = |v: &HVector, _: &RoTxn| n(v) % 500 == 7;
+    let results = index.search(&txn, query, k, "Doc", Some(&[filter]), true, SearchParams::default()).unwrap();
+    let found: HashSet<i32> = results.iter().map(n).collect();
+    assert_eq!(found, HashSet::from([7, 507, 1007, 1507]));
+}
+
+#[test]
+fn test_hnsw_search_params() {
+    let n_base = 1_000;
+    let k = 20;
+    let vectors = gen_sim_vecs(n_base + 1, 32, 0.8);
+    let query = &vectors[n_base];
+
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, Some(10))).unwrap();
+    let mut base_vectors = Vec::new();
+    for data in &vectors[..n_base] {
+        base_vectors.push(index.insert::<Filter>(&mut txn, data, None).unwrap());
+    }
+    txn.commit().unwrap();
+    let txn = env.read_txn().unwrap();
+
+    let ground_truth: HashSet<u128> = calc_ground_truths(base_vectors, &vec![(0, query.clone())], k)
+        .remove(&0)
+        .unwrap()
+        .into_iter()
+        .collect();
+    let recall = |params: SearchParams| {
+        let results = index
+            .search::<Filter>(&txn, query, k, "vector", None, false, params)
+            .unwrap();
+        assert_eq!(results.len(), k);
+        results.iter().filter(|v| ground_truth.contains(&v.id)).count() as f64 / k as f64
+    };
+
+    // the configured ef of 10 is raised to k, as is a smaller ef or candidate list of a search
+    recall(SearchParams::default());
+    recall(SearchParams::new(Some(1), Some(1)));
+
+    // an ef above the configured cap of 512 searches most of the graph
+    assert!(recall(SearchParams::new(Some(1_000), None)) >= 0.9);
+    assert!(recall(SearchParams::new(Some(1_000), Some(k))) >= 0.9);
+}
+
+#[test]
+fn test_hnsw_rebuild() {
+    let n_base = 1_000;
+    let n_concurrent = 100;
+    let dims = 32;
+    let label = "Doc";
+    let fields = || Some(vec![("label".to_string(), Value::String(label.to_string()))]);
+    let vectors = gen_sim_vecs(n_base + n_concurrent, dims, 0.8);
+
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let mut index =
+        VectorCore::new(&env, &mut txn, HNSWConfig::new(Some(5), Some(40), None)).unwrap();
+    let base: Vec<HVector> = vectors[..n_base]
+        .iter()
+        .map(|data| index.insert::<Filter>(&mut txn, data, fields()).unwrap())
+        .collect();
+
+    // the vector type is re-tuned, which only applies to the vectors inserted from now on
+    let retuned = VectorIndex::new(label, DistanceMetric::Cosine).with_hnsw(Some(16), Some(80), None);
+    index.set_indices(&mut txn, &[retuned]).unwrap();
+    txn.commit().unwrap();
+    let index = Arc::new(index);
+
+    // vectors are inserted and deleted while the graph is rebuilt
+    let writer = {
+        let (env, index) = (env.clone(), Arc::clone(&index));
+        let vectors = vectors[n_base..].to_vec();
+        let deleted: Vec<u128> = base.iter().step_by(10).map(|v| v.id).collect();
+        thread::spawn(move || {
+            let mut inserted = Vec::new();
+            for (data, id) in vectors.iter().zip(deleted.iter().cycle()) {
+                let mut txn = env.write_txn().unwrap();
+                let vector = index.insert::<Filter>(&mut txn, data, fields()).unwrap();
+                inserted.push((vector.id, data.clone()));
+                if index.delete(&mut txn, *id).is_ok() {
+                    inserted.retain(|(i, _)| i != id);
+                }
+                txn.commit().unwrap();
+            }
+            (inserted, deleted)
+        })
+    };
+
+    // reads opened before the rebuild keep their graph
+    let before = env.read_txn().unwrap();
+    let rebuild = {
+        let (env, index) = (env.clone(), Arc::clone(&index));
+        thread::spawn(move || index.rebuild(&env, label, None, None, None).unwrap())
+    };
+    let count = rebuild.join().unwrap();
+    let (inserted, deleted) = writer.join().unwrap();
+    let results = index
+        .search::<Filter>(&before, &vectors[0], 10, label, None, false, SearchParams::default())
+        .unwrap();
+    assert_eq!(results.len(), 10);
+    drop(before);
+
+    let txn = env.read_txn().unwrap();
+    let deleted: HashSet<u128> = deleted.into_iter().collect();
+    let remaining: Vec<(u128, Vec<f64>)> = base
+        .iter()
+        .zip(&vectors)
+        .filter(|(v, _)| !deleted.contains(&v.id))
+        .map(|(v, data)| (v.id, data.clone()))
+        .chain(inserted)
+        .collect();
+    assert_eq!(count, remaining.len());
+
+    // the edges of the old graph are dropped
+    let edges = |namespace: &str| {
+        index
+            .edges_db
+            .prefix_iter(&txn, &[namespace.as_bytes(), &[0]].concat())
+            .unwrap()
+            .count()
+    };
+    assert_eq!(edges(label), 0);
+    assert!(edges("Doc#1") > 0);
+
+    // every vector, including those inserted during the rebuild, is reachable
+    let mut found = 0;
+    for (id, data) in &remaining {
+        let results = index
+            .search::<Filter>(&txn, data, 10, label, None, false, SearchParams::default())
+            .unwrap();
+        assert!(results.iter().all(|v| !deleted.contains(&v.id)));
+        if results.iter().any(|v| v.id == *id) {
+            found += 1;
+        }
+    }
+    let recall = found as f64 / remaining.len() as f64;
+    assert!(recall > 0.95, "recall: {recall}");
+
+    // a second rebuild moves to the next generation
+    drop(txn);
+    assert_eq!(index.rebuild(&env, label, None, None, None).unwrap(), remaining.len());
+    let txn = env.read_txn().unwrap();
+    assert_eq!(
+        index
+            .edges_db
+            .prefix_iter(&txn, b"Doc#1\0")
+            .unwrap()
+            .count(),
+        0
+    );
 }
 
+#[test]
+fn test_hnsw_rebuild_params() {
+    let label = "Doc";
+    let fields = || Some(vec![("label".to_string(), Value::String(label.to_string()))]);
+    let vectors = gen_sim_vecs(200, 16, 0.8);
+
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    for data in &vectors {
+        index.insert::<Filter>(&mut txn, data, fields()).unwrap();
+    }
+
+    // a rebuild claimed by another process is refused until its claim lapses
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    index
+        .vectors_db
+        .put(&mut txn, b"rebuild:Doc", &bincode::serialize(&(1u128, now)).unwrap())
+        .unwrap();
+    txn.commit().unwrap();
+    assert!(index.rebuild(&env, label, None, None, None).is_err());
+
+    let mut txn = env.write_txn().unwrap();
+    index
+        .vectors_db
+        .put(&mut txn, b"rebuild:Doc", &bincode::serialize(&(1u128, 0u64)).unwrap())
+        .unwrap();
+    txn.commit().unwrap();
+    assert_eq!(index.rebuild(&env, label, None, None, None).unwrap(), 200);
+
+    // the params a graph is rebuilt with are kept with it, and the claim is released
+    assert_eq!(index.rebuild(&env, label, Some(32), None, Some(64)).unwrap(), 200);
+    let txn = env.read_txn().unwrap();
+    assert!(index.vectors_db.get(&txn, b"rebuild:Doc").unwrap().is_none());
+    let config: HNSWConfig =
+        bincode::deserialize(index.vectors_db.get(&txn, b"hnsw:Doc#2").unwrap().unwrap()).unwrap();
+    assert_eq!(config.m, 32);
+    assert_eq!(config.ef, 64);
+    assert_eq!(config.ef_construct, index.config.ef_construct);
+    let results = index
+        .search::<Filter>(&txn, &vectors[0], 10, label, None, false, SearchParams::default())
+        .unwrap();
+    assert_eq!(results.len(), 10);
+    drop(txn);
+
+    // a rebuild without params goes back to the declared ones
+    let mut txn = env.write_txn().unwrap();
+    index.insert::<Filter>(&mut txn, &vectors[0], fields()).unwrap();
+    txn.commit().unwrap();
+    assert_eq!(index.rebuild(&env, label, None, None, None).unwrap(), 201);
+    let txn = env.read_txn().unwrap();
+    assert!(index.vectors_db.get(&txn, b"hnsw:Doc#2").unwrap().is_none());
+    assert!(index.vectors_db.get(&txn, b"hnsw:Doc#3").unwrap().is_none());
+}
+
+#[test]
+fn test_hnsw_evaluate() {
+    let n = 500;
+    let dims = 16;
+    let label = "Doc";
+    let fields = |label: &str| Some(vec![("label".to_string(), Value::String(label.to_string()))]);
+    let vectors = gen_sim_vecs(n, dims, 0.8);
+
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    let inserted: Vec<HVector> = vectors
+        .iter()
+        .map(|data| index.insert::<Filter>(&mut txn, data, fields(label)).unwrap())
+        .collect();
+    // vectors of another type are neither queries nor neighbors
+    for data in gen_sim_vecs(100, dims, 0.8) {
+        index.insert::<Filter>(&mut txn, &data, fields("Other")).unwrap();
+    }
+    txn.commit().unwrap();
+
+    let txn = env.read_txn().unwrap();
+    let evaluation = index
+        .evaluate(&txn, label, 10, None, 50, SearchParams::default())
+        .unwrap();
+    assert_eq!(evaluation.label, label);
+    assert_eq!(evaluation.queries, 50);
+    assert_eq!(evaluation.vectors, n);
+    assert_eq!(evaluation.levels.values().sum::<usize>(), n);
+    assert!(evaluation.levels[&0] > evaluation.levels.get(&1).copied().unwrap_or(0));
+    assert!(evaluation.recall > 0.9, "recall: {}", evaluation.recall);
+    assert!(evaluation.average_degree > 1.0);
+    assert!(evaluation.average_degree <= index.config.m_max_0 as f64);
+    assert!(evaluation.unreachable < n / 20);
+    let latency = evaluation.latency_us;
+    assert!(latency.p50 <= latency.p95 && latency.p95 <= latency.p99 && latency.p99 <= latency.max);
+
+    // supplied queries are searched instead of sampled vectors
+    let queries = gen_sim_vecs(3, dims, 0.8);
+    let evaluation = index
+        .evaluate(&txn, label, 10, Some(queries), 50, SearchParams::default())
+        .unwrap();
+    assert_eq!(evaluation.queries, 3);
+    let unreachable = evaluation.unreachable;
+    drop(txn);
+
+    // a vector whose links are all removed can no longer be reached
+    let isolated = inserted.iter().find(|v| v.level == 0).unwrap().id;
+    let mut txn = env.write_txn().unwrap();
+    let keys: Vec<Vec<u8>> = index
+        .edges_db
+        .prefix_iter(&txn, b"Doc\0")
+        .unwrap()
+        .map(|result| result.unwrap().0.to_vec())
+        .filter(|key| {
+            let id = |at: usize| u128::from_be_bytes(key[at..at + 16].try_into().unwrap());
+            id(4) == isolated || id(4 + 16 + 8) == isolated
+        })
+        .collect();
+    assert!(!keys.is_empty());
+    for key in keys {
+        index.edges_db.delete(&mut txn, &key).unwrap();
+    }
+    txn.commit().unwrap();
+
+    let txn = env.read_txn().unwrap();
+    let evaluation = index
+        .evaluate(&txn, label, 10, None, 10, SearchParams::default())
+        .unwrap();
+    assert!(evaluation.unreachable > unreachable);
+
+    // a vector type without vectors cannot be evaluated
+    assert!(index.evaluate(&txn, "Missing", 10, None, 10, SearchParams::default()).is_err());
+}
+
+#[test]
+fn test_hnsw_multi_vector() {
+    let dims = 8;
+    let k = 5;
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let mut index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    index
+        .set_indices(
+            &mut txn,
+            &[VectorIndex::new("Passage", DistanceMetric::Euclidean).with_multi()],
+        )
+        .unwrap();
+    let fields = |n: i64| {
+        Some(vec![
+            ("label".to_string(), Value::String("Passage".to_string())),
+            ("n".to_string(), Value::I64(n)),
+        ])
+    };
+
+    let mut rng = rand::rng();
+    let documents: Vec<(u128, Vec<Vec<f64>>)> = (0..60)
+        .map(|n| {
+            let tokens = gen_sim_vecs(rng.random_range(2..6), dims, 0.5);
+            let document = index.insert_multi::<Filter>(&mut txn, &tokens, fields(n)).unwrap();
+            (document.id, tokens)
+        })
+        .collect();
+
+    // a multi-vector type only takes documents, and a single vector type only vectors
+    assert!(index.insert::<Filter>(&mut txn, &[0.0; 8], fields(0)).is_err());
+    assert!(index.insert_multi::<Filter>(&mut txn, &[], fields(0)).is_err());
+    let other = Some(vec![("label".to_string(), Value::String("Other".to_string()))]);
+    assert!(index.insert_multi::<Filter>(&mut txn, &[vec![0.0; 8]], other).is_err());
+    txn.commit().unwrap();
+
+    let maxsim = |queries: &[Vec<f64>], tokens: &[Vec<f64>]| -> f64 {
+        queries
+            .iter()
+            .map(|query| {
+                tokens
+                    .iter()
+                    .map(|token| DistanceMetric::Euclidean.distance(query, token).unwrap())
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .sum()
+    };
+    let queries = gen_sim_vecs(3, dims, 0.5);
+    let mut exact: Vec<(f64, u128)> = documents
+        .iter()
+        .map(|(id, tokens)| (maxsim(&queries, tokens), *id))
+        .collect();
+    exact.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let txn = env.read_txn().unwrap();
+    let results = index
+        .search_multi::<Filter>(&txn, &queries, k, "Passage", None, SearchParams::default())
+        .unwrap();
+    // documents are returned, scored on all their vectors
+    let found: Vec<u128> = results.iter().map(|document| document.id).collect();
+    let expected: Vec<u128> = exact.iter().take(k).map(|(_, id)| *id).collect();
+    assert_eq!(found, expected);
+    for (document, (score, _)) in results.iter().zip(&exact) {
+        assert!((document.get_distance() - score).abs() < 1e-9);
+        assert!(document.check_property("n").is_ok());
+    }
+
+    // filters apply to the properties of the documents
+    let even: &[Filter] = &[|document, _| {
+        matches!(document.check_property("n").unwrap().as_ref(), Value::I64(n) if n % 2 == 0)
+    }];
+    let results = index
+        .search_multi(&txn, &queries, k, "Passage", Some(even), SearchParams::default())
+        .unwrap();
+    assert_eq!(results.len(), k);
+    assert!(results.iter().all(|document| even[0](document, &txn)));
+
+    let document = index.get_vector(&txn, expected[0], 0, true).unwrap();
+    assert_eq!(document.label(), "Passage");
+    drop(txn);
+
+    // deleting a document deletes its vectors
+    let mut txn = env.write_txn().unwrap();
+    index.delete(&mut txn, expected[0]).unwrap();
+    txn.commit().unwrap();
+
+    let txn = env.read_txn().unwrap();
+    assert!(index.get_vector(&txn, expected[0], 0, true).is_err());
+    let tokens = &documents.iter().find(|(id, _)| *id == expected[0]).unwrap().1;
+    for token in tokens {
+        let results = index
+            .search::<Filter>(&txn, token, 1, "Passage", None, false, SearchParams::default())
+            .unwrap();
+        assert!(results.iter().all(|vector| vector.distance.unwrap() > 1e-9));
+    }
+    let results = index
+        .search_multi::<Filter>(&txn, &queries, k, "Passage", None, SearchParams::default())
+        .unwrap();
+    let found: Vec<u128> = results.iter().map(|document| document.id).collect();
+    assert_eq!(found, exact[1..=k].iter().map(|(_, id)| *id).collect::<Vec<_>>());
+}
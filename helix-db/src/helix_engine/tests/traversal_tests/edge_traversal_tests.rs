@@ -20,7 +20,7 @@ use crate::{
             },
             traversal_value::{Traversable, TraversalValue},
         },
-        vector_core::vector::HVector,
+        vector_core::{vector::HVector, vector_core::SearchParams},
     },
     props,
     utils::filterable::Filterable,
@@ -435,7 +435,13 @@ fn test_add_e_between_node_and_vector() {
     println!(
         "vectors: {:?}",
         G::new(Arc::clone(&storage), &txn)
-            .search_v::<fn(&HVector, &RoTxn) -> bool, _>(&[1.0, 2.0, 3.0], 10, "vector", None)
+            .search_v::<fn(&HVector, &RoTxn) -> bool, _>(
+                &[1.0, 2.0, 3.0],
+                10,
+                "vector",
+                None,
+                SearchParams::default(),
+            )
             .collect_to::<Vec<_>>()
     );
 
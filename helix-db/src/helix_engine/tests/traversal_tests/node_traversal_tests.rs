@@ -15,7 +15,7 @@ use crate::{
                     n_from_id::NFromIdAdapter,
                     n_from_type::NFromTypeAdapter,
                 },
-                util::{filter_ref::FilterRefAdapter, props::PropsAdapter},
+                util::{drop::Drop, filter_ref::FilterRefAdapter, props::PropsAdapter},
             },
             traversal_value::{Traversable, TraversalValue},
         },
@@ -466,3 +466,129 @@ fn test_double_add_and_double_fetch() {
         panic!("e[0] is not an edge");
     }
 }
+
+#[test]
+fn test_n_from_type_uses_label_index() {
+    let (db, _temp_dir) = setup_test_db();
+    let mut txn = db.graph_env.write_txn().unwrap();
+
+    let person1 = G::new_mut(Arc::clone(&db), &mut txn)
+        .add_n("person", Some(props! { "name" => "person1" }), None)
+        .collect_to_val();
+    let person2 = G::new_mut(Arc::clone(&db), &mut txn)
+        .add_n("person", Some(props! { "name" => "person2" }), None)
+        .collect_to_val();
+    let company = G::new_mut(Arc::clone(&db), &mut txn)
+        .add_n("company", Some(props! { "name" => "company1" }), None)
+        .collect_to_val();
+
+    G::new_mut(Arc::clone(&db), &mut txn)
+        .add_e("knows", None, person1.id(), person2.id(), false, EdgeType::Node)
+        .collect_to_val();
+    G::new_mut(Arc::clone(&db), &mut txn)
+        .add_e("works_at", None, person1.id(), company.id(), false, EdgeType::Node)
+        .collect_to_val();
+    txn.commit().unwrap();
+
+    let txn = db.graph_env.read_txn().unwrap();
+    assert_eq!(db.node_labels_db.len(&txn).unwrap(), 3);
+    assert_eq!(db.edge_labels_db.len(&txn).unwrap(), 2);
+
+    let people = G::new(Arc::clone(&db), &txn)
+        .n_from_type("person")
+        .collect_to::<Vec<_>>();
+    assert_eq!(people.len(), 2);
+    assert!(people.iter().all(|n| n.label() == "person"));
+
+    let companies = G::new(Arc::clone(&db), &txn)
+        .n_from_type("company")
+        .collect_to::<Vec<_>>();
+    assert_eq!(companies.len(), 1);
+    assert_eq!(companies[0].id(), company.id());
+
+    let works_at = G::new(Arc::clone(&db), &txn)
+        .e_from_type("works_at")
+        .collect_to::<Vec<_>>();
+    assert_eq!(works_at.len(), 1);
+    drop(txn);
+
+    // dropping a node removes it and all of its edges from the label indices
+    let mut txn = db.graph_env.write_txn().unwrap();
+    let traversal = G::new(Arc::clone(&db), &txn)
+        .n_from_id(&person1.id())
+        .collect_to::<Vec<_>>();
+    Drop::<Vec<_>>::drop_traversal(traversal, Arc::clone(&db), &mut txn).unwrap();
+    txn.commit().unwrap();
+
+    let txn = db.graph_env.read_txn().unwrap();
+    assert_eq!(db.node_labels_db.len(&txn).unwrap(), 2);
+    assert_eq!(db.edge_labels_db.len(&txn).unwrap(), 0);
+
+    let people = G::new(Arc::clone(&db), &txn)
+        .n_from_type("person")
+        .collect_to::<Vec<_>>();
+    assert_eq!(people.len(), 1);
+    assert_eq!(people[0].id(), person2.id());
+
+    let knows = G::new(Arc::clone(&db), &txn)
+        .e_from_type("knows")
+        .collect_to::<Vec<_>>();
+    assert!(knows.is_empty());
+}
+
+#[test]
+fn test_label_index_backfill() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().to_str().unwrap();
+
+    let (person1, person2) = {
+        let db = Arc::new(
+            HelixGraphStorage::new(
+                db_path,
+                crate::helix_engine::traversal_core::config::Config::default(),
+                Default::default(),
+            )
+            .unwrap(),
+        );
+        let mut txn = db.graph_env.write_txn().unwrap();
+        let person1 = G::new_mut(Arc::clone(&db), &mut txn)
+            .add_n("person", None, None)
+            .collect_to_val();
+        let person2 = G::new_mut(Arc::clone(&db), &mut txn)
+            .add_n("person", None, None)
+            .collect_to_val();
+        G::new_mut(Arc::clone(&db), &mut txn)
+            .add_e("knows", None, person1.id(), person2.id(), false, EdgeType::Node)
+            .collect_to_val();
+
+        // simulate a database written before the label indices existed
+        db.node_labels_db.clear(&mut txn).unwrap();
+        db.edge_labels_db.clear(&mut txn).unwrap();
+        txn.commit().unwrap();
+        (person1, person2)
+    };
+
+    let db = Arc::new(
+        HelixGraphStorage::new(
+            db_path,
+            crate::helix_engine::traversal_core::config::Config::default(),
+            Default::default(),
+        )
+        .unwrap(),
+    );
+    let txn = db.graph_env.read_txn().unwrap();
+
+    let mut people = G::new(Arc::clone(&db), &txn)
+        .n_from_type("person")
+        .map(|n| n.unwrap().id())
+        .collect::<Vec<_>>();
+    people.sort();
+    let mut expected = vec![person1.id(), person2.id()];
+    expected.sort();
+    assert_eq!(people, expected);
+
+    let knows = G::new(Arc::clone(&db), &txn)
+        .e_from_type("knows")
+        .collect_to::<Vec<_>>();
+    assert_eq!(knows.len(), 1);
+}
@@ -1,20 +1,29 @@
-use std::sync::Arc;
+use std::{ops::Bound, sync::Arc};
 
 use crate::{
     helix_engine::{
-        storage_core::HelixGraphStorage,
+        storage_core::{HelixGraphStorage, storage_methods::DBMethods},
         traversal_core::{
+            config::{Config, SecondaryIndex},
             ops::{
                 g::G,
                 source::{
-                    add_n::AddNAdapter, n_from_id::NFromIdAdapter, n_from_index::NFromIndexAdapter,
+                    add_e::{AddEAdapter, EdgeType},
+                    add_n::AddNAdapter,
+                    e_from_index::EFromIndexAdapter,
+                    n_from_id::NFromIdAdapter,
+                    n_from_index::NFromIndexAdapter,
+                    n_from_index_range::NFromIndexRangeAdapter,
+                    n_from_type::NFromTypeAdapter,
                 },
                 util::{drop::Drop, update::UpdateAdapter},
             },
             traversal_value::{Traversable, TraversalValue},
         },
+        types::GraphError,
     },
     props,
+    protocol::value::Value,
 };
 
 use tempfile::TempDir;
@@ -25,7 +34,7 @@ fn setup_test_db() -> (Arc<HelixGraphStorage>, TempDir) {
     let db_path = temp_dir.path().to_str().unwrap();
     let storage = HelixGraphStorage::new(
         db_path,
-        crate::helix_engine::traversal_core::config::Config::default(),
+        Config::default(),
         Default::default(),
     )
     .unwrap();
@@ -37,8 +46,9 @@ fn test_delete_node_with_secondary_index() {
     let (storage, _) = {
         let temp_dir = TempDir::new().unwrap();
         let db_path = temp_dir.path().to_str().unwrap();
-        let mut config = crate::helix_engine::traversal_core::config::Config::default();
-        config.graph_config.as_mut().unwrap().secondary_indices = Some(vec!["name".to_string()]);
+        let mut config = Config::default();
+        config.graph_config.as_mut().unwrap().secondary_indices =
+            Some(vec![SecondaryIndex::new("person", "name")]);
         let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
         (Arc::new(storage), temp_dir)
     };
@@ -91,13 +101,25 @@ fn test_delete_node_with_secondary_index() {
     assert_eq!(node.len(), 0);
 }
 
+#[test]
+fn test_n_from_index_missing_index() {
+    let (storage, _temp_dir) = setup_test_db();
+    let txn = storage.graph_env.read_txn().unwrap();
+
+    let name = "Jane".to_string();
+    let mut nodes = G::new(Arc::clone(&storage), &txn).n_from_index("person", "name", &name);
+    assert!(matches!(nodes.next(), Some(Err(GraphError::New(_)))));
+    assert!(nodes.next().is_none());
+}
+
 #[test]
 fn test_update_of_secondary_indices() {
     let (storage, _) = {
         let temp_dir = TempDir::new().unwrap();
         let db_path = temp_dir.path().to_str().unwrap();
-        let mut config = crate::helix_engine::traversal_core::config::Config::default();
-        config.graph_config.as_mut().unwrap().secondary_indices = Some(vec!["name".to_string()]);
+        let mut config = Config::default();
+        config.graph_config.as_mut().unwrap().secondary_indices =
+            Some(vec![SecondaryIndex::new("person", "name")]);
         let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
         (Arc::new(storage), temp_dir)
     };
@@ -136,3 +158,729 @@ fn test_update_of_secondary_indices() {
 
     txn.commit().unwrap();
 }
+
+#[test]
+fn test_secondary_indices_are_scoped_by_label() {
+    let (storage, _temp_dir) = {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap();
+        let mut config = Config::default();
+        config.graph_config.as_mut().unwrap().secondary_indices = Some(vec![
+            SecondaryIndex::new("person", "name"),
+            SecondaryIndex::new("company", "name"),
+        ]);
+        let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
+        (Arc::new(storage), temp_dir)
+    };
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let person = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("person", Some(props! { "name" => "Acme" }), Some(&["name"]))
+        .collect_to_val();
+    let company = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("company", Some(props! { "name" => "Acme" }), Some(&["name"]))
+        .collect_to_val();
+    // same field on a label without an index is not written to the other labels' indices
+    let _ = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("city", Some(props! { "name" => "Acme" }), None)
+        .collect_to_val();
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let people = G::new(Arc::clone(&storage), &txn)
+        .n_from_index("person", "name", &"Acme".to_string())
+        .collect_to::<Vec<_>>();
+    assert_eq!(people.len(), 1);
+    assert_eq!(people[0].id(), person.id());
+
+    let companies = G::new(Arc::clone(&storage), &txn)
+        .n_from_index("company", "name", &"Acme".to_string())
+        .collect_to::<Vec<_>>();
+    assert_eq!(companies.len(), 1);
+    assert_eq!(companies[0].id(), company.id());
+
+    assert_eq!(
+        storage
+            .get_secondary_index("person", "name")
+            .unwrap()
+            .len(&txn)
+            .unwrap(),
+        1
+    );
+    assert!(storage.get_secondary_index("city", "name").is_none());
+    drop(txn);
+
+    // updating and dropping a node only touches the indices of its own label
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let _ = G::new_mut_from(Arc::clone(&storage), &mut txn, person.clone())
+        .update(Some(props! { "name" => "Globex" }))
+        .collect_to_val();
+    Drop::<Vec<_>>::drop_traversal(
+        G::new(Arc::clone(&storage), &txn)
+            .n_from_type("company")
+            .collect_to::<Vec<_>>(),
+        Arc::clone(&storage),
+        &mut txn,
+    )
+    .unwrap();
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let people = G::new(Arc::clone(&storage), &txn)
+        .n_from_index("person", "name", &"Globex".to_string())
+        .collect_to::<Vec<_>>();
+    assert_eq!(people.len(), 1);
+    assert_eq!(people[0].id(), person.id());
+    assert!(
+        G::new(Arc::clone(&storage), &txn)
+            .n_from_index("company", "name", &"Acme".to_string())
+            .collect_to::<Vec<_>>()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_secondary_index_backfill() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().to_str().unwrap();
+
+    let node_id = {
+        let storage = Arc::new(
+            HelixGraphStorage::new(db_path, Config::default(), Default::default()).unwrap(),
+        );
+        let mut txn = storage.graph_env.write_txn().unwrap();
+        let node = G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_n("person", Some(props! { "name" => "John" }), None)
+            .collect_to_val();
+        txn.commit().unwrap();
+        node.id()
+    };
+
+    // declaring the index later builds it from the nodes already stored
+    let mut config = Config::default();
+    config.graph_config.as_mut().unwrap().secondary_indices =
+        Some(vec![SecondaryIndex::new("person", "name")]);
+    let storage = Arc::new(HelixGraphStorage::new(db_path, config, Default::default()).unwrap());
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let nodes = G::new(Arc::clone(&storage), &txn)
+        .n_from_index("person", "name", &"John".to_string())
+        .collect_to::<Vec<_>>();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0].id(), node_id);
+}
+
+#[test]
+fn test_secondary_index_built_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().to_str().unwrap();
+    let config = || {
+        let mut config = Config::default();
+        config.graph_config.as_mut().unwrap().secondary_indices =
+            Some(vec![SecondaryIndex::new("person", "name")]);
+        config
+    };
+    let count = |storage: &HelixGraphStorage| {
+        let txn = storage.graph_env.read_txn().unwrap();
+        storage.get_secondary_index("person", "name").unwrap().len(&txn).unwrap()
+    };
+    let marker = b"index_built:idx_person_name".as_slice();
+
+    {
+        let storage = Arc::new(HelixGraphStorage::new(db_path, config(), Default::default()).unwrap());
+        let mut txn = storage.graph_env.write_txn().unwrap();
+        G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_n("person", Some(props! { "name" => "John" }), Some(&["name"]))
+            .collect_to_val();
+        txn.commit().unwrap();
+
+        // an index emptied behind its back is not rebuilt, as it was already built once
+        let mut txn = storage.graph_env.write_txn().unwrap();
+        assert!(storage.metadata_db.get(&txn, marker).unwrap().is_some());
+        storage.get_secondary_index("person", "name").unwrap().clear(&mut txn).unwrap();
+        txn.commit().unwrap();
+    }
+    let storage = HelixGraphStorage::new(db_path, config(), Default::default()).unwrap();
+    assert_eq!(count(&storage), 0);
+
+    // without its marker, the index is built again from the label's nodes
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    storage.metadata_db.delete(&mut txn, marker).unwrap();
+    txn.commit().unwrap();
+    drop(storage);
+    let storage = HelixGraphStorage::new(db_path, config(), Default::default()).unwrap();
+    assert_eq!(count(&storage), 1);
+}
+
+#[test]
+fn test_create_secondary_index_at_runtime() {
+    let (storage, _temp_dir) = setup_test_db();
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let node = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("person", Some(props! { "age" => 30 }), None)
+        .collect_to_val();
+    txn.commit().unwrap();
+
+    // more indices than the tables opened on start leave room for
+    let mut storage = Arc::try_unwrap(storage).ok().unwrap();
+    for i in 0..20 {
+        storage.create_secondary_index(&format!("label{i}"), "age").unwrap();
+    }
+    storage.create_secondary_index("person", "age").unwrap();
+
+    // an index created at runtime is built from the nodes already stored
+    let storage = Arc::new(storage);
+    let txn = storage.graph_env.read_txn().unwrap();
+    let people = G::new(Arc::clone(&storage), &txn)
+        .n_from_index("person", "age", &30)
+        .collect_to::<Vec<_>>();
+    assert_eq!(people.len(), 1);
+    assert_eq!(people[0].id(), node.id());
+}
+
+#[test]
+fn test_global_index_from_legacy_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().to_str().unwrap();
+
+    let node_id = {
+        let storage = Arc::new(
+            HelixGraphStorage::new(db_path, Config::default(), Default::default()).unwrap(),
+        );
+        let mut txn = storage.graph_env.write_txn().unwrap();
+        let node = G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_n("person", Some(props! { "name" => "John" }), None)
+            .collect_to_val();
+        txn.commit().unwrap();
+        node.id()
+    };
+
+    // configs from before indices were scoped by label list bare fields
+    let config: Config = sonic_rs::from_str(
+        r#"{"graph_config": {"secondary_indices": ["name", {"label": "company", "field": "name"}]}}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.get_graph_config().secondary_indices.unwrap(),
+        vec![SecondaryIndex::global("name"), SecondaryIndex::new("company", "name")]
+    );
+    let storage = Arc::new(HelixGraphStorage::new(db_path, config, Default::default()).unwrap());
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let dog = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("dog", Some(props! { "name" => "John" }), Some(&["name"]))
+        .collect_to_val();
+    G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("company", Some(props! { "name" => "John" }), Some(&["name"]))
+        .collect_to_val();
+    txn.commit().unwrap();
+
+    // the global index covers the labels without an index of their own on the field
+    let txn = storage.graph_env.read_txn().unwrap();
+    let global = storage.get_secondary_index("person", "name").unwrap();
+    assert_eq!(global.len(&txn).unwrap(), 2);
+    let people = G::new(Arc::clone(&storage), &txn)
+        .n_from_index("person", "name", &"John".to_string())
+        .collect_to::<Vec<_>>();
+    assert_eq!(people.len(), 1);
+    assert_eq!(people[0].id(), node_id);
+    drop(txn);
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    Drop::<Vec<_>>::drop_traversal(vec![dog], Arc::clone(&storage), &mut txn).unwrap();
+    txn.commit().unwrap();
+    let txn = storage.graph_env.read_txn().unwrap();
+    assert_eq!(global.len(&txn).unwrap(), 1);
+}
+
+#[test]
+fn test_unique_index_rejects_duplicate_add() {
+    let (storage, _temp_dir) = {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap();
+        let mut config = Config::default();
+        config.graph_config.as_mut().unwrap().secondary_indices =
+            Some(vec![SecondaryIndex::unique("user", "email")]);
+        let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
+        (Arc::new(storage), temp_dir)
+    };
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let first = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("user", Some(props! { "email" => "a@b.c" }), Some(&["email"]))
+        .try_collect_to_obj()
+        .unwrap();
+    txn.commit().unwrap();
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let result = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("user", Some(props! { "email" => "a@b.c" }), Some(&["email"]))
+        .try_collect_to_obj();
+    match result {
+        Err(GraphError::UniqueConstraintViolation { label, field, id }) => {
+            assert_eq!(label, "user");
+            assert_eq!(field, "email");
+            assert_eq!(id, first.id());
+        }
+        _ => panic!("expected a unique constraint violation"),
+    }
+    drop(txn);
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let users = G::new(Arc::clone(&storage), &txn)
+        .n_from_type("user")
+        .collect_to::<Vec<_>>();
+    assert_eq!(users.len(), 1);
+}
+
+#[test]
+fn test_unique_index_rejects_duplicate_update() {
+    let (storage, _temp_dir) = {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap();
+        let mut config = Config::default();
+        config.graph_config.as_mut().unwrap().secondary_indices =
+            Some(vec![SecondaryIndex::unique("user", "email")]);
+        let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
+        (Arc::new(storage), temp_dir)
+    };
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let first = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("user", Some(props! { "email" => "a@b.c" }), Some(&["email"]))
+        .collect_to_val();
+    let second = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("user", Some(props! { "email" => "d@e.f" }), Some(&["email"]))
+        .collect_to_val();
+
+    // rewriting a node's own value is not a conflict
+    G::new_mut_from(Arc::clone(&storage), &mut txn, first.clone())
+        .update(Some(props! { "email" => "a@b.c" }))
+        .try_collect_to_obj()
+        .unwrap();
+
+    let result = G::new_mut_from(Arc::clone(&storage), &mut txn, second.clone())
+        .update(Some(props! { "email" => "a@b.c" }))
+        .try_collect_to_obj();
+    match result {
+        Err(GraphError::UniqueConstraintViolation { label, field, id }) => {
+            assert_eq!(label, "user");
+            assert_eq!(field, "email");
+            assert_eq!(id, first.id());
+        }
+        _ => panic!("expected a unique constraint violation"),
+    }
+    txn.commit().unwrap();
+
+    // the rejected update left the second node and its index entry untouched
+    let txn = storage.graph_env.read_txn().unwrap();
+    let users = G::new(Arc::clone(&storage), &txn)
+        .n_from_index("user", "email", &"d@e.f".to_string())
+        .collect_to::<Vec<_>>();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].id(), second.id());
+    let users = G::new(Arc::clone(&storage), &txn)
+        .n_from_index("user", "email", &"a@b.c".to_string())
+        .collect_to::<Vec<_>>();
+    assert_eq!(users.len(), 1);
+    assert_eq!(users[0].id(), first.id());
+}
+
+#[test]
+fn test_n_from_index_range() {
+    let (storage, _temp_dir) = {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap();
+        let mut config = Config::default();
+        config.graph_config.as_mut().unwrap().secondary_indices = Some(vec![
+            SecondaryIndex::new("event", "start"),
+            SecondaryIndex::new("event", "score"),
+        ]);
+        let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
+        (Arc::new(storage), temp_dir)
+    };
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    for (start, score) in [(10i64, 1.5f64), (-5, -2.5), (0, 0.0), (-1, -0.5), (3, 2.0)] {
+        G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_n(
+                "event",
+                Some(props! { "start" => start, "score" => score }),
+                Some(&["start", "score"]),
+            )
+            .collect_to_val();
+    }
+    txn.commit().unwrap();
+
+    let starts = |nodes: Vec<TraversalValue>| {
+        nodes
+            .iter()
+            .map(|node| match node.check_property("start").unwrap().into_owned() {
+                Value::I64(start) => start,
+                _ => panic!("start should be an I64"),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    // keys of any integer width compare by value, and come back in order
+    let nodes = G::new(Arc::clone(&storage), &txn)
+        .n_from_index_range("event", "start", Bound::Included(&-1i32), Bound::Unbounded)
+        .collect_to::<Vec<_>>();
+    assert_eq!(starts(nodes), vec![-1, 0, 3, 10]);
+
+    let nodes = G::new(Arc::clone(&storage), &txn)
+        .n_from_index_range("event", "start", Bound::Excluded(&-5i64), Bound::Excluded(&3i64))
+        .collect_to::<Vec<_>>();
+    assert_eq!(starts(nodes), vec![-1, 0]);
+
+    let nodes = G::new(Arc::clone(&storage), &txn)
+        .n_from_index_range("event", "start", Bound::Unbounded, Bound::Included(&0u8))
+        .collect_to::<Vec<_>>();
+    assert_eq!(starts(nodes), vec![-5, -1, 0]);
+
+    let nodes = G::new(Arc::clone(&storage), &txn)
+        .n_from_index_range("event", "score", Bound::Excluded(&-2.5f64), Bound::Included(&1.5f64))
+        .collect_to::<Vec<_>>();
+    assert_eq!(starts(nodes), vec![-1, 0, 10]);
+
+    let nodes = G::new(Arc::clone(&storage), &txn)
+        .n_from_index("event", "start", &3i32)
+        .collect_to::<Vec<_>>();
+    assert_eq!(starts(nodes), vec![3]);
+}
+
+#[test]
+fn test_n_from_index_prefix() {
+    let (storage, _temp_dir) = {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap();
+        let mut config = Config::default();
+        config.graph_config.as_mut().unwrap().secondary_indices =
+            Some(vec![SecondaryIndex::new("person", "name")]);
+        let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
+        (Arc::new(storage), temp_dir)
+    };
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    for name in ["alicia", "bob", "al", "alice", "ali\0ce"] {
+        G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_n("person", Some(props! { "name" => name }), Some(&["name"]))
+            .collect_to_val();
+    }
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let names = G::new(Arc::clone(&storage), &txn)
+        .n_from_index_prefix("person", "name", "ali")
+        .collect_to::<Vec<_>>()
+        .iter()
+        .map(|node| Value::to_string(&node.check_property("name").unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["ali\0ce", "alice", "alicia"]);
+
+    let names = G::new(Arc::clone(&storage), &txn)
+        .n_from_index_range(
+            "person",
+            "name",
+            Bound::Included(&"al".to_string()),
+            Bound::Excluded(&"alice".to_string()),
+        )
+        .collect_to::<Vec<_>>();
+    assert_eq!(names.len(), 2);
+
+    assert!(
+        G::new(Arc::clone(&storage), &txn)
+            .n_from_index_prefix("person", "name", "carol")
+            .collect_to::<Vec<_>>()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_composite_index_lookup() {
+    let (storage, _temp_dir) = {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap();
+        let mut config = Config::default();
+        config.graph_config.as_mut().unwrap().secondary_indices =
+            Some(vec![SecondaryIndex::new("account", "tenant_id,external_id")]);
+        let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
+        (Arc::new(storage), temp_dir)
+    };
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    for (tenant_id, external_id) in [("t2", 1i64), ("t1", 3), ("t10", 1), ("t1", -2), ("t1", 1)] {
+        G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_n(
+                "account",
+                Some(props! { "tenant_id" => tenant_id, "external_id" => external_id }),
+                Some(&["tenant_id,external_id"]),
+            )
+            .collect_to_val();
+    }
+    txn.commit().unwrap();
+
+    let keys = |nodes: Vec<TraversalValue>| {
+        nodes
+            .iter()
+            .map(|node| {
+                (
+                    Value::to_string(&node.check_property("tenant_id").unwrap()),
+                    node.check_property("external_id").unwrap().into_owned(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let nodes = G::new(Arc::clone(&storage), &txn)
+        .n_from_composite_index(
+            "account",
+            "tenant_id,external_id",
+            &[Value::from("t1"), Value::from(1i32)],
+        )
+        .collect_to::<Vec<_>>();
+    assert_eq!(keys(nodes), vec![("t1".to_string(), Value::I64(1))]);
+
+    // a leading prefix of the fields returns every match, ordered by the remaining fields
+    let nodes = G::new(Arc::clone(&storage), &txn)
+        .n_from_composite_index("account", "tenant_id,external_id", &[Value::from("t1")])
+        .collect_to::<Vec<_>>();
+    assert_eq!(
+        keys(nodes),
+        vec![
+            ("t1".to_string(), Value::I64(-2)),
+            ("t1".to_string(), Value::I64(1)),
+            ("t1".to_string(), Value::I64(3)),
+        ]
+    );
+
+    let nodes = G::new(Arc::clone(&storage), &txn)
+        .n_from_composite_index(
+            "account",
+            "tenant_id,external_id",
+            &[Value::from("t3"), Value::from(1i64)],
+        )
+        .collect_to::<Vec<_>>();
+    assert!(nodes.is_empty());
+}
+
+#[test]
+fn test_composite_index_update_and_drop() {
+    let (storage, _temp_dir) = {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap();
+        let mut config = Config::default();
+        config.graph_config.as_mut().unwrap().secondary_indices =
+            Some(vec![SecondaryIndex::unique("account", "tenant_id,external_id")]);
+        let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
+        (Arc::new(storage), temp_dir)
+    };
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let first = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n(
+            "account",
+            Some(props! { "tenant_id" => "t1", "external_id" => 1i64 }),
+            Some(&["tenant_id,external_id"]),
+        )
+        .collect_to_val();
+    let second = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n(
+            "account",
+            Some(props! { "tenant_id" => "t2", "external_id" => 1i64 }),
+            Some(&["tenant_id,external_id"]),
+        )
+        .collect_to_val();
+
+    // uniqueness covers the combination of the fields, not each field on its own
+    let result = G::new_mut_from(Arc::clone(&storage), &mut txn, second.clone())
+        .update(Some(props! { "tenant_id" => "t1" }))
+        .try_collect_to_obj();
+    match result {
+        Err(GraphError::UniqueConstraintViolation { field, id, .. }) => {
+            assert_eq!(field, "tenant_id,external_id");
+            assert_eq!(id, first.id());
+        }
+        _ => panic!("expected a unique constraint violation"),
+    }
+
+    // updating one field moves the node's composite key
+    G::new_mut_from(Arc::clone(&storage), &mut txn, second.clone())
+        .update(Some(props! { "external_id" => 2i64 }))
+        .try_collect_to_obj()
+        .unwrap();
+
+    Drop::<Vec<_>>::drop_traversal(vec![first.clone()], Arc::clone(&storage), &mut txn).unwrap();
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let lookup = |keys: &[Value]| {
+        G::new(Arc::clone(&storage), &txn)
+            .n_from_composite_index("account", "tenant_id,external_id", keys)
+            .map(|node| node.unwrap().id())
+            .collect::<Vec<_>>()
+    };
+    assert!(lookup(&[Value::from("t2"), Value::from(1i64)]).is_empty());
+    assert_eq!(lookup(&[Value::from("t2"), Value::from(2i64)]), vec![second.id()]);
+    assert!(lookup(&[Value::from("t1")]).is_empty());
+}
+
+#[test]
+fn test_edge_secondary_index() {
+    let (storage, _temp_dir) = {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap();
+        let mut config = Config::default();
+        config.graph_config.as_mut().unwrap().edge_secondary_indices =
+            Some(vec![SecondaryIndex::new("pays", "transaction_id")]);
+        let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
+        (Arc::new(storage), temp_dir)
+    };
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let alice = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("person", None, None)
+        .collect_to_val();
+    let bob = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("person", None, None)
+        .collect_to_val();
+    let mut add_payment = |transaction_id: &str| {
+        G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_e(
+                "pays",
+                Some(props! { "transaction_id" => transaction_id }),
+                alice.id(),
+                bob.id(),
+                false,
+                EdgeType::Node,
+            )
+            .collect_to_val()
+    };
+    let first = add_payment("tx-1");
+    let second = add_payment("tx-2");
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let edges = G::new(Arc::clone(&storage), &txn)
+        .e_from_index("pays", "transaction_id", &"tx-2".to_string())
+        .collect_to::<Vec<_>>();
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].id(), second.id());
+    drop(txn);
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    G::new_mut_from(Arc::clone(&storage), &mut txn, first.clone())
+        .update(Some(props! { "transaction_id" => "tx-3" }))
+        .collect_to::<Vec<_>>();
+    Drop::<Vec<_>>::drop_traversal(vec![second.clone()], Arc::clone(&storage), &mut txn).unwrap();
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let lookup = |transaction_id: &str| {
+        G::new(Arc::clone(&storage), &txn)
+            .e_from_index("pays", "transaction_id", &transaction_id.to_string())
+            .map(|edge| edge.unwrap().id())
+            .collect::<Vec<_>>()
+    };
+    assert!(lookup("tx-1").is_empty());
+    assert!(lookup("tx-2").is_empty());
+    assert_eq!(lookup("tx-3"), vec![first.id()]);
+}
+
+#[test]
+fn test_edge_secondary_index_node_drop() {
+    let (storage, _temp_dir) = {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap();
+        let mut config = Config::default();
+        config.graph_config.as_mut().unwrap().edge_secondary_indices =
+            Some(vec![SecondaryIndex::new("pays", "transaction_id")]);
+        let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
+        (Arc::new(storage), temp_dir)
+    };
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let alice = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("person", None, None)
+        .collect_to_val();
+    let bob = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("person", None, None)
+        .collect_to_val();
+    let carol = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("person", None, None)
+        .collect_to_val();
+    let mut add_payment = |from: u128, to: u128| {
+        G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_e(
+                "pays",
+                Some(props! { "transaction_id" => "tx-1" }),
+                from,
+                to,
+                false,
+                EdgeType::Node,
+            )
+            .collect_to_val()
+    };
+    add_payment(alice.id(), bob.id());
+    add_payment(carol.id(), alice.id());
+    let kept = add_payment(bob.id(), carol.id());
+    txn.commit().unwrap();
+
+    // dropping a node drops its edges, and their index entries with them
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    Drop::<Vec<_>>::drop_traversal(vec![alice], Arc::clone(&storage), &mut txn).unwrap();
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let edges = G::new(Arc::clone(&storage), &txn)
+        .e_from_index("pays", "transaction_id", &"tx-1".to_string())
+        .map(|edge| edge.unwrap().id())
+        .collect::<Vec<_>>();
+    assert_eq!(edges, vec![kept.id()]);
+}
+
+#[test]
+fn test_edge_secondary_index_backfill() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().to_str().unwrap();
+
+    let edge_id = {
+        let storage = Arc::new(
+            HelixGraphStorage::new(db_path, Config::default(), Default::default()).unwrap(),
+        );
+        let mut txn = storage.graph_env.write_txn().unwrap();
+        let from = G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_n("person", None, None)
+            .collect_to_val();
+        let to = G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_n("person", None, None)
+            .collect_to_val();
+        let edge = G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_e(
+                "pays",
+                Some(props! { "transaction_id" => "tx-1" }),
+                from.id(),
+                to.id(),
+                false,
+                EdgeType::Node,
+            )
+            .collect_to_val();
+        txn.commit().unwrap();
+        edge.id()
+    };
+
+    // declaring the index later builds it from the edges already stored
+    let mut config = Config::default();
+    config.graph_config.as_mut().unwrap().edge_secondary_indices =
+        Some(vec![SecondaryIndex::new("pays", "transaction_id")]);
+    let storage = Arc::new(HelixGraphStorage::new(db_path, config, Default::default()).unwrap());
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let edges = G::new(Arc::clone(&storage), &txn)
+        .e_from_index("pays", "transaction_id", &"tx-1".to_string())
+        .collect_to::<Vec<_>>();
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].id(), edge_id);
+}
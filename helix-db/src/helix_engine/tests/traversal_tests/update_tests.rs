@@ -2,11 +2,16 @@ use std::sync::Arc;
 
 use crate::{
     helix_engine::{
-        storage_core::HelixGraphStorage,
+        storage_core::{HelixGraphStorage, storage_methods::StorageMethods},
         traversal_core::{
             ops::{
                 g::G,
-                source::{add_n::AddNAdapter, n_from_id::NFromIdAdapter},
+                source::{
+                    add_e::{AddEAdapter, EdgeType},
+                    add_n::AddNAdapter,
+                    e_from_id::EFromIdAdapter,
+                    n_from_id::NFromIdAdapter,
+                },
                 util::update::UpdateAdapter,
             },
             traversal_value::Traversable,
@@ -66,3 +71,52 @@ fn test_update_node() {
         "john"
     );
 }
+
+#[test]
+fn test_update_edge() {
+    let (storage, _temp_dir) = setup_test_db();
+    let mut txn = storage.graph_env.write_txn().unwrap();
+
+    let node1 = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("person", Some(props!()), None)
+        .collect_to_val();
+    let node2 = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("person", Some(props!()), None)
+        .collect_to_val();
+    let edge = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_e(
+            "knows",
+            Some(props!("since" => 2020)),
+            node1.id(),
+            node2.id(),
+            false,
+            EdgeType::Node,
+        )
+        .collect_to_val();
+
+    txn.commit().unwrap();
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let _ = {
+        let update_tr = G::new(Arc::clone(&storage), &txn)
+            .e_from_id(&edge.id())
+            .collect_to::<Vec<_>>();
+        G::new_mut_from(Arc::clone(&storage), &mut txn, update_tr)
+            .update(Some(props! { "since" => 2024 }))
+            .collect_to::<Vec<_>>()
+    };
+    txn.commit().unwrap();
+    let txn = storage.graph_env.read_txn().unwrap();
+    let updated_edges = G::new(Arc::clone(&storage), &txn)
+        .e_from_id(&edge.id())
+        .collect_to::<Vec<_>>();
+    assert_eq!(updated_edges.len(), 1);
+    assert_eq!(
+        updated_edges[0]
+            .check_property("since")
+            .unwrap()
+            .into_owned()
+            .to_string(),
+        "2024"
+    );
+    assert!(storage.get_node(&txn, &edge.id()).is_err());
+}
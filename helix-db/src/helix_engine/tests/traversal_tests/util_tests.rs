@@ -17,7 +17,7 @@ use crate::{
             },
             traversal_value::Traversable,
         },
-        vector_core::vector::HVector,
+        vector_core::{vector::HVector, vector_core::SearchParams},
     },
     props,
 };
@@ -227,7 +227,7 @@ fn test_order_vector_by_asc() {
 
     let txn = storage.graph_env.read_txn().unwrap();
     let traversal = G::new(Arc::clone(&storage), &txn)
-        .search_v::<FnTy, _>(&[1.0, 2.0, 3.0], 10, "vector", None)
+        .search_v::<FnTy, _>(&[1.0, 2.0, 3.0], 10, "vector", None, SearchParams::default())
         .order_by_asc("age")
         .collect_to::<Vec<_>>();
 
@@ -259,7 +259,7 @@ fn test_order_vector_by_desc() {
 
     let txn = storage.graph_env.read_txn().unwrap();
     let traversal = G::new(Arc::clone(&storage), &txn)
-        .search_v::<FnTy, _>(&[1.0, 2.0, 3.0], 10, "vector", None)
+        .search_v::<FnTy, _>(&[1.0, 2.0, 3.0], 10, "vector", None, SearchParams::default())
         .order_by_desc("age")
         .collect_to::<Vec<_>>();
 
@@ -2,9 +2,14 @@ use std::sync::Arc;
 
 use crate::{
     helix_engine::{
+        bm25::{bm25::HybridFusion, query::TermExpansion},
         storage_core::HelixGraphStorage,
         traversal_core::{
+            config::{Config, TextField, VectorConfig, VectorIndex},
             ops::{
+                bm25::{
+                    hybrid_search_bm25::HybridSearchBM25Adapter, search_bm25::SearchBM25Adapter,
+                },
                 g::G,
                 in_::{in_e::InEdgesAdapter, to_v::ToVAdapter},
                 out::{from_v::FromVAdapter, out::OutAdapter, out_e::OutEdgesAdapter},
@@ -23,9 +28,15 @@ use crate::{
             },
             traversal_value::{Traversable, TraversalValue},
         },
-        vector_core::vector::HVector,
+        types::GraphError,
+        vector_core::{
+            sparse_vector::SparseVector, vector::HVector, vector_core::SearchParams,
+            vector_distance::DistanceMetric,
+        },
     },
     props,
+    protocol::value::Value,
+    utils::filterable::Filterable,
 };
 
 use heed3::RoTxn;
@@ -162,6 +173,48 @@ fn test_brute_force_vector_search() {
     assert_eq!(traversal[2].id(), vector_ids[2]);
 }
 
+#[test]
+fn test_brute_force_vector_search_radius() {
+    let (storage, _temp_dir) = setup_test_db();
+    let mut txn = storage.graph_env.write_txn().unwrap();
+
+    let node = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("person", None, None)
+        .collect_to_val();
+    let mut vector_ids = Vec::new();
+    for vector in [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0], [-1.0, -2.0, -3.0]] {
+        let vector_id = G::new_mut(Arc::clone(&storage), &mut txn)
+            .insert_v::<fn(&HVector, &RoTxn) -> bool>(&vector, "vector", None)
+            .collect_to_val()
+            .id();
+        G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_e("embedding", None, node.id(), vector_id, false, EdgeType::Vec)
+            .collect_to_val();
+        vector_ids.push(vector_id);
+    }
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let search = |query: &[f64]| {
+        G::new(Arc::clone(&storage), &txn)
+            .n_from_id(&node.id())
+            .out_e("embedding")
+            .to_v()
+            .brute_force_search_v_radius(query, 0.5)
+            .collect::<Vec<_>>()
+    };
+    let ids = search(&[1.0, 2.0, 3.0])
+        .into_iter()
+        .map(|v| v.unwrap().id())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![vector_ids[0], vector_ids[1]]);
+
+    // a query of the wrong length is an error rather than a panic
+    let results = search(&[1.0, 2.0]);
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_err());
+}
+
 #[test]
 fn test_order_by_desc() {
     let (storage, _temp_dir) = setup_test_db();
@@ -250,6 +303,7 @@ fn test_vector_search() {
             2000,
             "vector",
             None,
+            SearchParams::default(),
         )
         .collect_to::<Vec<_>>();
     // traversal.reverse();
@@ -286,6 +340,7 @@ fn test_delete_vector() {
             2000,
             "vector",
             None,
+            SearchParams::default(),
         )
         .collect_to::<Vec<_>>();
 
@@ -302,6 +357,7 @@ fn test_delete_vector() {
                 2000,
                 "vector",
                 None,
+                SearchParams::default(),
             )
             .collect_to::<Vec<_>>(),
         Arc::clone(&storage),
@@ -318,6 +374,7 @@ fn test_delete_vector() {
             2000,
             "vector",
             None,
+            SearchParams::default(),
         )
         .collect_to::<Vec<_>>();
 
@@ -434,6 +491,7 @@ fn test_drop_vectors_then_add_them_back() {
             2000,
             "Entity_Embedding",
             None,
+            SearchParams::default(),
         )
         .collect_to::<Vec<_>>();
     assert_eq!(traversal.len(), 1);
@@ -512,6 +570,7 @@ fn test_drop_vectors_then_add_them_back() {
             2000,
             "Entity_Embedding",
             None,
+            SearchParams::default(),
         )
         .collect_to::<Vec<_>>();
     assert_eq!(traversal.len(), 1);
@@ -525,3 +584,647 @@ fn test_drop_vectors_then_add_them_back() {
 
     txn.commit().unwrap();
 }
+
+#[test]
+fn test_insert_vector_of_wrong_dimension() {
+    let (storage, _temp_dir) = {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap();
+        let mut config = Config::default();
+        config.vector_config = Some(VectorConfig {
+            vector_indices: Some(vec![
+                VectorIndex::new("doc", DistanceMetric::Cosine).with_dimension(3),
+            ]),
+            ..Default::default()
+        });
+        let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
+        (Arc::new(storage), temp_dir)
+    };
+    let mut txn = storage.graph_env.write_txn().unwrap();
+
+    let result = G::new_mut(Arc::clone(&storage), &mut txn)
+        .insert_v::<fn(&HVector, &RoTxn) -> bool>(&[1.0, 2.0], "doc", None)
+        .try_collect_to_obj();
+    match result {
+        Err(GraphError::VectorError(msg)) => {
+            assert!(msg.contains("doc have 3 dimensions, but this one has 2"))
+        }
+        _ => panic!("expected a dimension error"),
+    }
+
+    let results = G::new_mut(Arc::clone(&storage), &mut txn)
+        .insert_vs::<fn(&HVector, &RoTxn) -> bool>(
+            &[vec![1.0, 2.0, 3.0], vec![1.0, 2.0, 3.0, 4.0]],
+            Some(vec![("label".to_string(), Value::String("doc".to_string()))]),
+        )
+        .collect::<Vec<_>>();
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+
+    // types without a declared dimension take any length
+    G::new_mut(Arc::clone(&storage), &mut txn)
+        .insert_v::<fn(&HVector, &RoTxn) -> bool>(&[1.0, 2.0], "vector", None)
+        .try_collect_to_obj()
+        .unwrap();
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let result = G::new(Arc::clone(&storage), &txn)
+        .search_v::<fn(&HVector, &RoTxn) -> bool, usize>(
+            &[1.0, 2.0],
+            10,
+            "doc",
+            None,
+            SearchParams::default(),
+        )
+        .next();
+    assert!(matches!(result, Some(Err(GraphError::VectorError(_)))));
+    let result = G::new(Arc::clone(&storage), &txn)
+        .search_v::<fn(&HVector, &RoTxn) -> bool, usize>(
+            &[1.0, 2.0, 3.0],
+            10,
+            "doc",
+            None,
+            SearchParams::default(),
+        )
+        .collect_to::<Vec<_>>();
+    assert_eq!(result.len(), 1);
+}
+
+#[test]
+fn test_search_v_batch() {
+    let (storage, _temp_dir) = setup_test_db();
+    let mut txn = storage.graph_env.write_txn().unwrap();
+
+    let mut rng = rand::rng();
+    let mut random_vector = || (0..8).map(|_| rng.random::<f64>()).collect::<Vec<f64>>();
+    for i in 0..500 {
+        G::new_mut(Arc::clone(&storage), &mut txn)
+            .insert_v::<fn(&HVector, &RoTxn) -> bool>(
+                &random_vector(),
+                "doc",
+                Some(vec![("group".to_string(), Value::I64(i % 2))]),
+            )
+            .collect_to_val();
+    }
+    txn.commit().unwrap();
+    let queries: Vec<Vec<f64>> = (0..20).map(|_| random_vector()).collect();
+
+    let vector = |value: &TraversalValue| match value {
+        TraversalValue::Vector(vector) => vector.clone(),
+        _ => panic!("expected a vector"),
+    };
+    let ids = |results: &[TraversalValue]| {
+        results
+            .iter()
+            .map(|v| (v.id(), vector(v).get_distance().to_bits()))
+            .collect::<Vec<_>>()
+    };
+    let txn = storage.graph_env.read_txn().unwrap();
+    let expected: Vec<_> = queries
+        .iter()
+        .map(|query| {
+            let results = G::new(Arc::clone(&storage), &txn)
+                .search_v::<fn(&HVector, &RoTxn) -> bool, _>(
+                    query,
+                    10,
+                    "doc",
+                    None,
+                    SearchParams::default(),
+                )
+                .collect_to::<Vec<_>>();
+            ids(&results)
+        })
+        .collect();
+
+    // the results of each query are those of a search of the query on its own
+    for threads in [1, 4, 64] {
+        let results = G::new(Arc::clone(&storage), &txn)
+            .search_v_batch::<fn(&HVector, &RoTxn) -> bool, _>(
+                &queries,
+                10,
+                "doc",
+                None,
+                SearchParams::default(),
+                threads,
+            )
+            .unwrap();
+        assert_eq!(results.len(), queries.len());
+        assert_eq!(results.iter().map(|r| ids(r)).collect::<Vec<_>>(), expected);
+    }
+
+    let results = G::new(Arc::clone(&storage), &txn)
+        .search_v_batch::<_, _>(
+            &queries,
+            5,
+            "doc",
+            Some(&[|v: &HVector, _: &RoTxn| {
+                matches!(v.check_property("group").as_deref(), Ok(Value::I64(0)))
+            }]),
+            SearchParams::default(),
+            4,
+        )
+        .unwrap();
+    assert!(results.iter().all(|r| r.len() == 5));
+    assert!(results.iter().flatten().all(|v| {
+        matches!(vector(v).check_property("group").as_deref(), Ok(Value::I64(0)))
+    }));
+
+    // a write committed while the batch's transaction is open is not seen by any query
+    {
+        let (storage, query) = (Arc::clone(&storage), queries[0].clone());
+        std::thread::spawn(move || {
+            let mut txn = storage.graph_env.write_txn().unwrap();
+            G::new_mut(Arc::clone(&storage), &mut txn)
+                .insert_v::<fn(&HVector, &RoTxn) -> bool>(&query, "doc", None)
+                .collect_to_val();
+            txn.commit().unwrap();
+        })
+        .join()
+        .unwrap();
+    }
+    let results = G::new(Arc::clone(&storage), &txn)
+        .search_v_batch::<fn(&HVector, &RoTxn) -> bool, _>(
+            &queries,
+            10,
+            "doc",
+            None,
+            SearchParams::default(),
+            4,
+        )
+        .unwrap();
+    assert_eq!(results.iter().map(|r| ids(r)).collect::<Vec<_>>(), expected);
+    drop(txn);
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let results = G::new(Arc::clone(&storage), &txn)
+        .search_v_batch::<fn(&HVector, &RoTxn) -> bool, _>(
+            &queries[..1],
+            1,
+            "doc",
+            None,
+            SearchParams::default(),
+            4,
+        )
+        .unwrap();
+    assert!(vector(&results[0][0]).get_distance() < 1e-9);
+}
+
+#[test]
+fn test_search_v_multi() {
+    let (storage, _temp_dir) = {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().to_str().unwrap();
+        let mut config = Config::default();
+        config.vector_config = Some(VectorConfig {
+            vector_indices: Some(vec![
+                VectorIndex::new("passage", DistanceMetric::Cosine).with_multi(),
+            ]),
+            ..Default::default()
+        });
+        let storage = HelixGraphStorage::new(db_path, config, Default::default()).unwrap();
+        (Arc::new(storage), temp_dir)
+    };
+    let mut txn = storage.graph_env.write_txn().unwrap();
+
+    let mut rng = rand::rng();
+    let mut random_vector = || (0..8).map(|_| rng.random::<f64>()).collect::<Vec<f64>>();
+    let passages: Vec<Vec<Vec<f64>>> = (0..100)
+        .map(|_| (0..4).map(|_| random_vector()).collect())
+        .collect();
+    for (i, tokens) in passages.iter().enumerate() {
+        G::new_mut(Arc::clone(&storage), &mut txn)
+            .insert_multi_v::<fn(&HVector, &RoTxn) -> bool>(
+                tokens,
+                "passage",
+                Some(vec![("n".to_string(), Value::I64(i as i64))]),
+            )
+            .collect_to_val();
+    }
+    let result = G::new_mut(Arc::clone(&storage), &mut txn)
+        .insert_v::<fn(&HVector, &RoTxn) -> bool>(&random_vector(), "passage", None)
+        .collect::<Result<Vec<_>, _>>();
+    assert!(result.is_err());
+    txn.commit().unwrap();
+
+    // the passage the query vectors are taken from matches all of them exactly
+    let queries = vec![passages[7][0].clone(), passages[7][2].clone()];
+    let txn = storage.graph_env.read_txn().unwrap();
+    let results = G::new(Arc::clone(&storage), &txn)
+        .search_v_multi::<fn(&HVector, &RoTxn) -> bool, _>(
+            &queries,
+            5,
+            "passage",
+            None,
+            SearchParams::default(),
+        )
+        .collect_to::<Vec<_>>();
+    assert_eq!(results.len(), 5);
+    assert_eq!(
+        results[0].check_property("n").unwrap().into_owned(),
+        Value::I64(7)
+    );
+    let passage = results[0].id();
+    drop(txn);
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    Drop::<Vec<_>>::drop_traversal(results[..1].to_vec(), Arc::clone(&storage), &mut txn)
+        .unwrap();
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let results = G::new(Arc::clone(&storage), &txn)
+        .search_v_multi::<fn(&HVector, &RoTxn) -> bool, _>(
+            &queries,
+            5,
+            "passage",
+            None,
+            SearchParams::default(),
+        )
+        .collect_to::<Vec<_>>();
+    assert!(results.iter().all(|result| result.id() != passage));
+}
+
+#[test]
+fn test_search_sparse_v() {
+    let (storage, _temp_dir) = setup_test_db();
+    let mut txn = storage.graph_env.write_txn().unwrap();
+
+    let mut rng = rand::rng();
+    let mut random_sparse = || {
+        let mut indices = (0..1000u32)
+            .filter(|_| rng.random_bool(0.01))
+            .collect::<Vec<_>>();
+        indices.push(rng.random_range(0..1000));
+        indices.sort_unstable();
+        indices.dedup();
+        let weights = indices
+            .iter()
+            .map(|_| rng.random::<f64>())
+            .collect::<Vec<_>>();
+        SparseVector::new(&indices, &weights).unwrap()
+    };
+    let vectors = (0..200).map(|_| random_sparse()).collect::<Vec<_>>();
+    let mut ids = Vec::new();
+    for (i, vector) in vectors.iter().enumerate() {
+        let (indices, weights): (Vec<u32>, Vec<f64>) = vector.entries.iter().copied().unzip();
+        let inserted = G::new_mut(Arc::clone(&storage), &mut txn)
+            .insert_sparse_v::<fn(&HVector, &RoTxn) -> bool>(
+                &indices,
+                &weights,
+                "splade",
+                Some(vec![("n".to_string(), Value::I64(i as i64))]),
+            )
+            .collect_to_val();
+        ids.push(inserted.id());
+    }
+    // vectors of another label share the inverted index but are never returned
+    G::new_mut(Arc::clone(&storage), &mut txn)
+        .insert_sparse_v::<fn(&HVector, &RoTxn) -> bool>(
+            &[0, 1, 2],
+            &[9.0, 9.0, 9.0],
+            "other",
+            None,
+        )
+        .collect_to_val();
+    let result = G::new_mut(Arc::clone(&storage), &mut txn)
+        .insert_sparse_v::<fn(&HVector, &RoTxn) -> bool>(&[1, 1], &[0.5, 0.5], "splade", None)
+        .collect::<Result<Vec<_>, _>>();
+    assert!(result.is_err());
+    // the label of a vector inserted without fields is still stored
+    let unlabeled = storage
+        .sparse
+        .insert(&mut txn, &SparseVector::new(&[3], &[1.0]).unwrap(), None)
+        .unwrap();
+    assert_eq!(storage.sparse.get(&txn, unlabeled.id, true).unwrap().label(), "vector");
+    assert!(
+        storage
+            .sparse
+            .vector_data_db
+            .get(&txn, &unlabeled.id.to_be_bytes())
+            .unwrap()
+            .is_some()
+    );
+    txn.commit().unwrap();
+
+    let query = random_sparse();
+    let (indices, weights): (Vec<u32>, Vec<f64>) = query.entries.iter().copied().unzip();
+    let mut expected = vectors
+        .iter()
+        .zip(&ids)
+        .map(|(vector, id)| (*id, query.dot(vector)))
+        .filter(|(_, dot)| *dot > 0.0)
+        .collect::<Vec<_>>();
+    expected.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    expected.truncate(10);
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let results = G::new(Arc::clone(&storage), &txn)
+        .search_sparse_v::<fn(&HVector, &RoTxn) -> bool, _>(&indices, &weights, 10, "splade", None)
+        .collect_to::<Vec<_>>();
+    assert_eq!(results.len(), expected.len());
+    for (result, (id, dot)) in results.iter().zip(&expected) {
+        let TraversalValue::Vector(vector) = result else {
+            panic!("expected a vector");
+        };
+        assert_eq!(vector.id, *id);
+        assert!((1.0 - vector.get_distance() - dot).abs() < 1e-9);
+        assert_eq!(vector.label(), "splade");
+    }
+
+    let even = |v: &HVector, _: &RoTxn| {
+        v.check_property("n")
+            .is_ok_and(|n| matches!(n.as_ref(), Value::I64(n) if n % 2 == 0))
+    };
+    let results = G::new(Arc::clone(&storage), &txn)
+        .search_sparse_v(&indices, &weights, 10, "splade", Some(&[even]))
+        .collect_to::<Vec<_>>();
+    assert!(!results.is_empty());
+    assert!(results.iter().all(|result| {
+        matches!(result.check_property("n").unwrap().as_ref(), Value::I64(n) if n % 2 == 0)
+    }));
+    let dropped = results[0].clone();
+    drop(txn);
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    Drop::<Vec<_>>::drop_traversal(vec![dropped.clone()], Arc::clone(&storage), &mut txn).unwrap();
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    assert!(storage.get_vector(&txn, &dropped.id()).is_err());
+    let results = G::new(Arc::clone(&storage), &txn)
+        .search_sparse_v::<fn(&HVector, &RoTxn) -> bool, _>(&indices, &weights, 10, "splade", None)
+        .collect_to::<Vec<_>>();
+    assert!(results.iter().all(|result| result.id() != dropped.id()));
+}
+
+#[test]
+fn test_hybrid_search_bm25() {
+    let (storage, _temp_dir) = setup_test_db();
+    let mut txn = storage.graph_env.write_txn().unwrap();
+
+    let docs = [
+        ("graph databases store edges", [1.0, 0.0, 0.0, 0.0]),
+        ("vector search over embeddings", [0.0, 1.0, 0.0, 0.0]),
+        ("keyword search with bm25", [0.0, 0.0, 1.0, 0.0]),
+    ];
+    let mut ids = Vec::new();
+    for (title, data) in &docs {
+        let inserted = G::new_mut(Arc::clone(&storage), &mut txn)
+            .insert_v::<fn(&HVector, &RoTxn) -> bool>(
+                data,
+                "doc",
+                Some(vec![("title".to_string(), Value::from(*title))]),
+            )
+            .collect_to_val();
+        ids.push(inserted.id());
+    }
+    // nodes share the bm25 index but are never returned, nor do vectors leak into node search
+    let node = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("doc", Some(props! { "title" => "vector search" }), None)
+        .collect_to_val();
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let results = G::new(Arc::clone(&storage), &txn)
+        .hybrid_search_bm25(
+            "doc",
+            "vector search",
+            &[0.1, 0.9, 0.0, 0.0],
+            2,
+            HybridFusion::ReciprocalRank { k: 60.0 },
+        )
+        .collect_to::<Vec<_>>();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].id(), ids[1]);
+    assert!(results.iter().all(|result| result.id() != node.id()));
+    assert!(matches!(&results[0], TraversalValue::Vector(vector) if vector.label() == "doc"));
+
+    let results = G::new(Arc::clone(&storage), &txn)
+        .search_bm25("doc", "vector search", 10)
+        .unwrap()
+        .collect_to::<Vec<_>>();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].id(), node.id());
+    drop(txn);
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let dropped = G::new(Arc::clone(&storage), &txn)
+        .hybrid_search_bm25(
+            "doc",
+            "vector search",
+            &[0.1, 0.9, 0.0, 0.0],
+            1,
+            HybridFusion::default(),
+        )
+        .collect_to::<Vec<_>>();
+    Drop::<Vec<_>>::drop_traversal(dropped, Arc::clone(&storage), &mut txn).unwrap();
+    txn.commit().unwrap();
+
+    let txn = storage.graph_env.read_txn().unwrap();
+    let bm25 = storage.bm25.as_ref().unwrap();
+    assert!(bm25.doc_lengths_db.get(&txn, &ids[1]).unwrap().is_none());
+    let results = G::new(Arc::clone(&storage), &txn)
+        .hybrid_search_bm25(
+            "doc",
+            "vector search",
+            &[0.1, 0.9, 0.0, 0.0],
+            2,
+            HybridFusion::default(),
+        )
+        .collect_to::<Vec<_>>();
+    assert!(results.iter().all(|result| result.id() != ids[1]));
+}
+
+fn search_bm25_ids(storage: &Arc<HelixGraphStorage>, label: &str, query: &str) -> Vec<u128> {
+    let txn = storage.graph_env.read_txn().unwrap();
+    G::new(Arc::clone(storage), &txn)
+        .search_bm25(label, query, 10)
+        .unwrap()
+        .map(|result| result.unwrap().id())
+        .collect()
+}
+
+#[test]
+fn test_bm25_text_fields() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().to_str().unwrap();
+    let mut config = Config::default();
+    config.graph_config.as_mut().unwrap().text_fields = Some(vec![
+        TextField::new("article", "title"),
+        TextField::new("doc", "title"),
+    ]);
+    let storage = Arc::new(HelixGraphStorage::new(db_path, config, Default::default()).unwrap());
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let article = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n(
+            "article",
+            Some(props! { "title" => "graph databases", "author" => "ada" }),
+            None,
+        )
+        .collect_to_val();
+    let untitled = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("article", Some(props! { "author" => "graph" }), None)
+        .collect_to_val();
+    let doc = G::new_mut(Arc::clone(&storage), &mut txn)
+        .insert_v::<fn(&HVector, &RoTxn) -> bool>(
+            &[1.0, 0.0, 0.0],
+            "doc",
+            Some(vec![
+                ("title".to_string(), Value::from("graph search")),
+                ("author".to_string(), Value::from("ada")),
+            ]),
+        )
+        .collect_to_val();
+    txn.commit().unwrap();
+
+    // only the declared fields are indexed, not the others nor the label
+    assert_eq!(search_bm25_ids(&storage, "article", "graph"), vec![article.id()]);
+    assert!(search_bm25_ids(&storage, "article", "ada").is_empty());
+    assert!(search_bm25_ids(&storage, "article", "article").is_empty());
+    let bm25 = storage.bm25.as_ref().unwrap();
+    let txn = storage.graph_env.read_txn().unwrap();
+    assert!(bm25.doc_lengths_db.get(&txn, &untitled.id()).unwrap().is_none());
+    assert_eq!(bm25.doc_lengths_db.get(&txn, &doc.id()).unwrap(), Some(2));
+    drop(txn);
+
+    // updating a text field reindexes the node, updating another field leaves it be
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    G::new_mut_from(Arc::clone(&storage), &mut txn, article.clone())
+        .update(Some(props! { "title" => "vector indices" }))
+        .collect_to_val();
+    G::new_mut_from(Arc::clone(&storage), &mut txn, article.clone())
+        .update(Some(props! { "author" => "grace" }))
+        .collect_to_val();
+    txn.commit().unwrap();
+
+    assert!(search_bm25_ids(&storage, "article", "graph").is_empty());
+    assert_eq!(search_bm25_ids(&storage, "article", "indices"), vec![article.id()]);
+}
+
+#[test]
+fn test_bm25_reindex_on_text_fields_change() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().to_str().unwrap();
+
+    // without declared text fields every field is indexed
+    let (article, doc) = {
+        let storage = Arc::new(
+            HelixGraphStorage::new(db_path, Config::default(), Default::default()).unwrap(),
+        );
+        let mut txn = storage.graph_env.write_txn().unwrap();
+        let article = G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_n(
+                "article",
+                Some(props! { "title" => "graph databases", "author" => "ada" }),
+                None,
+            )
+            .collect_to_val();
+        let doc = G::new_mut(Arc::clone(&storage), &mut txn)
+            .insert_v::<fn(&HVector, &RoTxn) -> bool>(
+                &[1.0, 0.0, 0.0],
+                "doc",
+                Some(vec![
+                    ("title".to_string(), Value::from("graph search")),
+                    ("author".to_string(), Value::from("ada")),
+                ]),
+            )
+            .collect_to_val();
+        txn.commit().unwrap();
+        assert_eq!(search_bm25_ids(&storage, "article", "ada"), vec![article.id()]);
+        (article, doc)
+    };
+
+    // declaring text fields rebuilds the index from them on start
+    let mut config = Config::default();
+    config.graph_config.as_mut().unwrap().text_fields = Some(vec![
+        TextField::new("article", "title"),
+        TextField::new("doc", "author"),
+    ]);
+    let storage = Arc::new(HelixGraphStorage::new(db_path, config, Default::default()).unwrap());
+    assert!(search_bm25_ids(&storage, "article", "ada").is_empty());
+    assert_eq!(search_bm25_ids(&storage, "article", "graph"), vec![article.id()]);
+
+    let bm25 = storage.bm25.as_ref().unwrap();
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    assert!(!bm25.text_fields_changed(&txn).unwrap());
+    assert_eq!(bm25.doc_lengths_db.get(&txn, &doc.id()).unwrap(), Some(1));
+    assert_eq!(storage.reindex_bm25(&mut txn).unwrap(), 2);
+}
+
+#[test]
+fn test_search_bm25_expanded() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().to_str().unwrap();
+    let mut config = Config::default();
+    config.graph_config.as_mut().unwrap().text_fields =
+        Some(vec![TextField::new("article", "title")]);
+    let storage = Arc::new(HelixGraphStorage::new(db_path, config, Default::default()).unwrap());
+
+    let mut txn = storage.graph_env.write_txn().unwrap();
+    let postgres = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("article", Some(props! { "title" => "postgres internals" }), None)
+        .collect_to_val();
+    let kubernetes = G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("article", Some(props! { "title" => "kubernetes operators" }), None)
+        .collect_to_val();
+    G::new_mut(Arc::clone(&storage), &mut txn)
+        .add_n("user", Some(props! { "title" => "postgres admin" }), None)
+        .collect_to_val();
+    txn.commit().unwrap();
+
+    let search = |query: &str, expansion| {
+        let txn = storage.graph_env.read_txn().unwrap();
+        G::new(Arc::clone(&storage), &txn)
+            .search_bm25_expanded("article", query, 10, expansion)
+            .unwrap()
+            .map(|result| result.unwrap().id())
+            .collect::<Vec<_>>()
+    };
+    assert!(search_bm25_ids(&storage, "article", "postg").is_empty());
+    assert_eq!(search("postg", TermExpansion::Prefix), vec![postgres.id()]);
+    assert_eq!(search("kuberntes", TermExpansion::Fuzzy(1)), vec![kubernetes.id()]);
+    assert_eq!(search_bm25_ids(&storage, "article", "postg*"), vec![postgres.id()]);
+}
+
+#[test]
+fn test_bm25_reindex_on_positions_enabled() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().to_str().unwrap();
+
+    // an index without positions can't answer phrase queries
+    let article = {
+        let storage = Arc::new(
+            HelixGraphStorage::new(db_path, Config::default(), Default::default()).unwrap(),
+        );
+        let mut txn = storage.graph_env.write_txn().unwrap();
+        let article = G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_n("article", Some(props! { "title" => "graph databases" }), None)
+            .collect_to_val();
+        G::new_mut(Arc::clone(&storage), &mut txn)
+            .add_n("article", Some(props! { "title" => "databases of graph" }), None)
+            .collect_to_val();
+        txn.commit().unwrap();
+
+        let txn = storage.graph_env.read_txn().unwrap();
+        assert!(
+            G::new(Arc::clone(&storage), &txn)
+                .search_bm25("article", "\"graph databases\"", 10)
+                .is_err()
+        );
+        article
+    };
+
+    // enabling positions rebuilds the index with them on start
+    let mut config = Config::default();
+    config.bm25_positions = Some(true);
+    let storage = Arc::new(HelixGraphStorage::new(db_path, config, Default::default()).unwrap());
+    assert_eq!(
+        search_bm25_ids(&storage, "article", "\"graph databases\""),
+        vec![article.id()]
+    );
+    let txn = storage.graph_env.read_txn().unwrap();
+    assert!(!storage.bm25.as_ref().unwrap().positions_changed(&txn).unwrap());
+}
+
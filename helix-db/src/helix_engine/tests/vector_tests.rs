@@ -1,6 +1,88 @@
-use crate::helix_engine::vector_core::vector_distance::{MAX_DISTANCE, MIN_DISTANCE, ORTHOGONAL};
+use crate::helix_engine::vector_core::vector_distance::{
+    DistanceMetric, MAX_DISTANCE, MIN_DISTANCE, ORTHOGONAL,
+};
 
+use crate::helix_engine::traversal_core::config::VectorIndex;
+use crate::helix_engine::vector_core::hnsw::HNSW;
 use crate::helix_engine::vector_core::vector::HVector;
+use crate::helix_engine::vector_core::vector_core::{HNSWConfig, SearchParams, VectorCore};
+use crate::helix_engine::vector_core::vector_encoding::{EncodedVector, VectorEncoding};
+use crate::protocol::value::Value;
+use crate::utils::filterable::Filterable;
+use heed3::{Env, EnvOpenOptions, RoTxn};
+use rand::Rng;
+use std::collections::HashSet;
+
+type Filter = fn(&HVector, &RoTxn) -> bool;
+
+fn setup_temp_env() -> Env {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let path = temp_dir.path().to_str().unwrap();
+
+    unsafe {
+        EnvOpenOptions::new()
+            .map_size(1024 * 1024 * 1024) // 1 GB
+            .max_dbs(10)
+            .open(path)
+            .unwrap()
+    }
+}
+
+/// Inserts `n` random vectors of the vector type, alternating its `group` property between 0 and 1
+fn insert_random_vectors(
+    env: &Env,
+    index: &VectorCore,
+    label: &str,
+    n: usize,
+    dims: usize,
+) -> Vec<(u128, Vec<f64>, i32)> {
+    let mut rng = rand::rng();
+    let mut txn = env.write_txn().unwrap();
+    let inserted = (0..n)
+        .map(|i| {
+            let data: Vec<f64> = (0..dims).map(|_| rng.random_range(-1.0..1.0)).collect();
+            let group = (i % 2) as i32;
+            let fields = vec![
+                ("label".to_string(), Value::String(label.to_string())),
+                ("group".to_string(), Value::I32(group)),
+                ("is_deleted".to_string(), Value::Boolean(false)),
+            ];
+            let vector = index.insert::<Filter>(&mut txn, &data, Some(fields)).unwrap();
+            (vector.id, data, group)
+        })
+        .collect();
+    txn.commit().unwrap();
+    inserted
+}
+
+/// Ids of the vectors within `radius` of the query, found by comparing it to each of them
+fn brute_force_radius(
+    vectors: &[(u128, Vec<f64>, i32)],
+    query: &[f64],
+    radius: f64,
+    metric: DistanceMetric,
+) -> HashSet<u128> {
+    vectors
+        .iter()
+        .filter(|(_, data, _)| metric.distance(query, data).unwrap() <= radius)
+        .map(|(id, _, _)| *id)
+        .collect()
+}
+
+/// Distance from the query to its `n`th closest vector
+fn nth_distance(
+    vectors: &[(u128, Vec<f64>, i32)],
+    query: &[f64],
+    n: usize,
+    metric: DistanceMetric,
+) -> f64 {
+    let mut distances: Vec<f64> = vectors
+        .iter()
+        .map(|(_, data, _)| metric.distance(query, data).unwrap())
+        .collect();
+    distances.sort_by(f64::total_cmp);
+    distances[n - 1]
+}
 
 #[test]
 fn test_hvector_new() {
@@ -20,7 +102,7 @@ fn test_hvector_from_slice() {
 fn test_hvector_distance_orthogonal() {
     let v1 = HVector::new(vec![1.0, 0.0]);
     let v2 = HVector::new(vec![0.0, 1.0]);
-    let distance = v1.distance_to(&v2).unwrap();
+    let distance = v1.distance_to(&v2, DistanceMetric::Cosine).unwrap();
     assert!(distance == ORTHOGONAL);
 }
 
@@ -28,7 +110,7 @@ fn test_hvector_distance_orthogonal() {
 fn test_hvector_distance_min() {
     let v1 = HVector::new(vec![1.0, 2.0, 3.0]);
     let v2 = HVector::new(vec![1.0, 2.0, 3.0]);
-    let distance = v2.distance_to(&v1).unwrap();
+    let distance = v2.distance_to(&v1, DistanceMetric::Cosine).unwrap();
     assert!(distance.abs() == MIN_DISTANCE);
 }
 
@@ -36,7 +118,7 @@ fn test_hvector_distance_min() {
 fn test_hvector_distance_max() {
     let v1 = HVector::new(vec![0.0, 0.0]);
     let v2 = HVector::new(vec![3.0, 4.0]);
-    let distance = v1.distance_to(&v2).unwrap();
+    let distance = v1.distance_to(&v2, DistanceMetric::Cosine).unwrap();
     assert!(distance == MAX_DISTANCE);
 }
 
@@ -69,7 +151,7 @@ fn test_hvector_is_empty() {
 fn test_hvector_distance_different_dimensions() {
     let v1 = HVector::new(vec![1.0, 2.0, 3.0]);
     let v2 = HVector::new(vec![1.0, 2.0, 3.0, 4.0]);
-    let distance = v1.distance_to(&v2).unwrap();
+    let distance = v1.distance_to(&v2, DistanceMetric::Cosine).unwrap();
     println!("distance: {distance}");
     assert!(distance.is_finite());
 }
@@ -78,7 +160,7 @@ fn test_hvector_distance_different_dimensions() {
 fn test_hvector_large_values() {
     let v1 = HVector::new(vec![1e6, 2e6]);
     let v2 = HVector::new(vec![1e6, 2e6]);
-    let distance = v1.distance_to(&v2).unwrap();
+    let distance = v1.distance_to(&v2, DistanceMetric::Cosine).unwrap();
     assert!(distance.abs() < 1e-10);
 }
 
@@ -86,7 +168,7 @@ fn test_hvector_large_values() {
 fn test_hvector_negative_values() {
     let v1 = HVector::new(vec![-1.0, -2.0]);
     let v2 = HVector::new(vec![1.0, 2.0]);
-    let distance = v1.distance_to(&v2).unwrap();
+    let distance = v1.distance_to(&v2, DistanceMetric::Cosine).unwrap();
     // used round to avoid floating point precision issues
     assert!(distance.round() == MAX_DISTANCE);
 }
@@ -95,6 +177,232 @@ fn test_hvector_negative_values() {
 fn test_hvector_cosine_similarity() {
     let v1 = HVector::new(vec![1.0, 2.0, 3.0]);
     let v2 = HVector::new(vec![4.0, 5.0, 6.0]);
-    let similarity = v1.distance_to(&v2).unwrap();
+    let similarity = v1.distance_to(&v2, DistanceMetric::Cosine).unwrap();
     assert!(similarity == 1.0 - 0.9746318461970762);
 }
+
+#[test]
+fn test_hvector_euclidean_distance() {
+    let v1 = HVector::new(vec![0.0, 0.0]);
+    let v2 = HVector::new(vec![3.0, 4.0]);
+    let distance = v1.distance_to(&v2, DistanceMetric::Euclidean).unwrap();
+    assert_eq!(distance, 5.0);
+}
+
+#[test]
+fn test_hvector_inner_product_distance() {
+    let v1 = HVector::new(vec![1.0, 2.0, 3.0]);
+    let v2 = HVector::new(vec![4.0, 5.0, 6.0]);
+    let distance = v1.distance_to(&v2, DistanceMetric::InnerProduct).unwrap();
+    assert_eq!(distance, 1.0 - 32.0);
+
+    // a larger dot product is closer, even when the angle is wider
+    let v3 = HVector::new(vec![10.0, 10.0, 10.0]);
+    assert!(v1.distance_to(&v3, DistanceMetric::InnerProduct).unwrap() < distance);
+}
+
+#[test]
+fn test_hvector_hamming_distance() {
+    let v1 = HVector::new(vec![1.0, 0.0, 1.0, 1.0]);
+    let v2 = HVector::new(vec![1.0, 1.0, 0.0, 1.0]);
+    let distance = v1.distance_to(&v2, DistanceMetric::Hamming).unwrap();
+    assert_eq!(distance, 2.0);
+}
+
+#[test]
+fn test_distance_metric_names() {
+    for metric in DistanceMetric::ALL {
+        assert_eq!(metric.as_str().parse::<DistanceMetric>().unwrap(), metric);
+    }
+    assert!("manhattan".parse::<DistanceMetric>().is_err());
+}
+
+#[test]
+fn test_vector_encoding_roundtrip() {
+    let data = vec![0.5, -1.0, 0.25, 0.0, 2.0];
+    for encoding in VectorEncoding::ALL {
+        let encoded = encoding.encode(&data);
+        assert_eq!(encoded.len(), data.len());
+        let bytes = encoded.to_bytes();
+        assert_eq!(EncodedVector::from_bytes(encoding, &bytes).unwrap(), encoded);
+        assert_eq!(encoding.as_str().parse::<VectorEncoding>().unwrap(), encoding);
+    }
+
+    // f64 vectors keep the layout of `HVector::to_bytes`
+    let bytes = VectorEncoding::F64.encode(&data).to_bytes();
+    assert_eq!(bytes, HVector::from_slice(0, data.clone()).to_bytes());
+
+    // each encoding is smaller than the previous one
+    let sizes: Vec<usize> = VectorEncoding::ALL
+        .iter()
+        .map(|encoding| encoding.encode(&[0.1; 128]).to_bytes().len())
+        .collect();
+    assert_eq!(sizes, vec![1024, 512, 4 + 128, 4 + 16]);
+
+    let decoded = VectorEncoding::Int8.encode(&data).decode();
+    for (a, b) in data.iter().zip(decoded) {
+        assert!((a - b).abs() < 0.01);
+    }
+    assert_eq!(
+        VectorEncoding::Binary.encode(&data).decode(),
+        vec![1.0, -1.0, 1.0, -1.0, 1.0]
+    );
+    assert!("int4".parse::<VectorEncoding>().is_err());
+}
+
+#[test]
+fn test_encoded_vector_distance() {
+    let v1 = vec![1.0, 2.0, 3.0, -1.0];
+    let v2 = vec![4.0, -5.0, 6.0, 2.0];
+    for metric in [
+        DistanceMetric::Cosine,
+        DistanceMetric::Euclidean,
+        DistanceMetric::InnerProduct,
+    ] {
+        let exact = metric.distance(&v1, &v2).unwrap();
+        for encoding in [VectorEncoding::F64, VectorEncoding::F32, VectorEncoding::Int8] {
+            let distance = encoding
+                .encode(&v1)
+                .distance(&encoding.encode(&v2), metric)
+                .unwrap();
+            assert!((distance - exact).abs() / exact.abs().max(1.0) < 0.05);
+        }
+    }
+
+    // binary vectors count the differing signs
+    let distance = VectorEncoding::Binary
+        .encode(&v1)
+        .distance(&VectorEncoding::Binary.encode(&v2), DistanceMetric::Cosine)
+        .unwrap();
+    assert_eq!(distance, 2.0);
+
+    // encoded HVectors are compared on their encoding
+    let mut h1 = HVector::from_slice(0, v1.clone());
+    let mut h2 = HVector::from_slice(0, v2.clone());
+    h1.encode(VectorEncoding::Binary);
+    h2.encode(VectorEncoding::Binary);
+    assert_eq!(h1.distance_to(&h2, DistanceMetric::Cosine).unwrap(), 2.0);
+    assert!(
+        VectorEncoding::F32
+            .encode(&v1)
+            .distance(&VectorEncoding::F32.encode(&[1.0]), DistanceMetric::Cosine)
+            .is_err()
+    );
+}
+
+#[test]
+fn test_radius_search_matches_brute_force() {
+    let (n, dims) = (400, 16);
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let mut index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    let metrics = [DistanceMetric::Cosine, DistanceMetric::Euclidean];
+    let indices: Vec<VectorIndex> = metrics
+        .iter()
+        .map(|metric| VectorIndex::new(metric.as_str(), *metric))
+        .collect();
+    index.set_indices(&mut txn, &indices).unwrap();
+    txn.commit().unwrap();
+
+    let mut rng = rand::rng();
+    for metric in metrics {
+        let label = metric.as_str();
+        let vectors = insert_random_vectors(&env, &index, label, n, dims);
+        let txn = env.read_txn().unwrap();
+
+        let (mut expected, mut found) = (0, 0);
+        for _ in 0..20 {
+            let query: Vec<f64> = (0..dims).map(|_| rng.random_range(-1.0..1.0)).collect();
+            let radius = nth_distance(&vectors, &query, 25, metric);
+            let truth = brute_force_radius(&vectors, &query, radius, metric);
+
+            let results = index
+                .search_radius::<Filter>(&txn, &query, radius, label, None, SearchParams::default())
+                .unwrap();
+            // every result is within the radius, closest first, and found once
+            assert!(results.iter().all(|v| v.get_distance() <= radius));
+            assert!(results.windows(2).all(|w| w[0].get_distance() <= w[1].get_distance()));
+            let ids: HashSet<u128> = results.iter().map(|v| v.id).collect();
+            assert_eq!(ids.len(), results.len());
+            assert!(ids.is_subset(&truth));
+
+            expected += truth.len();
+            found += ids.len();
+
+            // filters apply to every vector within the radius
+            let group_one: Filter = |v, _| v.check_property("group").is_ok_and(|g| *g == Value::I32(1));
+            let results = index
+                .search_radius(&txn, &query, radius, label, Some(&[group_one]), SearchParams::default())
+                .unwrap();
+            assert!(results.iter().all(|v| group_one(v, &txn)));
+            assert!(results.iter().all(|v| truth.contains(&v.id)));
+
+            // the candidates cap how many vectors come back
+            let results = index
+                .search_radius::<Filter>(&txn, &query, radius, label, None, SearchParams::new(None, Some(5)))
+                .unwrap();
+            assert!(results.len() <= 5);
+        }
+        let recall = found as f64 / expected as f64;
+        assert!(recall >= 0.95, "{label}: recall {recall}");
+
+        // a radius covering every vector finds all those the graph still links to
+        let results = index
+            .search_radius::<Filter>(&txn, &vectors[0].1, f64::MAX, label, None, SearchParams::default())
+            .unwrap();
+        assert!(results.len() as f64 >= 0.95 * n as f64, "{label}: {}/{n}", results.len());
+
+        // a radius close to 0 only finds the vector itself
+        let results = index
+            .search_radius::<Filter>(&txn, &vectors[0].1, 1e-9, label, None, SearchParams::default())
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, vectors[0].0);
+    }
+}
+
+#[test]
+fn test_radius_search_encoded_vectors() {
+    let (n, dims) = (400, 32);
+    let env = setup_temp_env();
+    let mut txn = env.write_txn().unwrap();
+    let mut index = VectorCore::new(&env, &mut txn, HNSWConfig::new(None, None, None)).unwrap();
+    let encodings = [VectorEncoding::F32, VectorEncoding::Int8, VectorEncoding::Binary];
+    let indices: Vec<VectorIndex> = encodings
+        .iter()
+        .map(|encoding| {
+            VectorIndex::new(encoding.as_str(), DistanceMetric::Cosine).with_encoding(*encoding)
+        })
+        .collect();
+    index.set_indices(&mut txn, &indices).unwrap();
+    txn.commit().unwrap();
+
+    let mut rng = rand::rng();
+    for encoding in encodings {
+        let label = encoding.as_str();
+        let vectors = insert_random_vectors(&env, &index, label, n, dims);
+        let txn = env.read_txn().unwrap();
+
+        let (mut expected, mut found) = (0, 0);
+        for _ in 0..20 {
+            let query: Vec<f64> = (0..dims).map(|_| rng.random_range(-1.0..1.0)).collect();
+            let radius = nth_distance(&vectors, &query, 25, DistanceMetric::Cosine);
+            let truth = brute_force_radius(&vectors, &query, radius, DistanceMetric::Cosine);
+
+            let results = index
+                .search_radius::<Filter>(&txn, &query, radius, label, None, SearchParams::default())
+                .unwrap();
+            // the radius is compared to the distances of the decoded vectors
+            for vector in &results {
+                assert!(vector.get_distance() <= radius);
+                let exact = DistanceMetric::Cosine.distance(&query, vector.get_data()).unwrap();
+                assert_eq!(vector.get_distance(), exact);
+            }
+
+            expected += truth.len();
+            found += results.iter().filter(|v| truth.contains(&v.id)).count();
+        }
+        let recall = found as f64 / expected as f64;
+        assert!(recall >= 0.9, "{encoding}: recall {recall}");
+    }
+}
@@ -1,6 +1,12 @@
 use crate::{
-    helix_engine::types::GraphError,
-    helixc::analyzer::analyzer::{INTROSPECTION_DATA, SECONDARY_INDICES},
+    helix_engine::{
+        bm25::analyzer::TextAnalyzer,
+        types::GraphError,
+        vector_core::{vector_distance::DistanceMetric, vector_encoding::VectorEncoding},
+    },
+    helixc::analyzer::analyzer::{
+        EDGE_SECONDARY_INDICES, INTROSPECTION_DATA, SECONDARY_INDICES, TEXT_FIELDS, VECTOR_INDICES,
+    },
 };
 use serde::{Deserialize, Serialize};
 use std::{fmt, path::PathBuf};
@@ -10,6 +16,8 @@ pub struct VectorConfig {
     pub m: Option<usize>,
     pub ef_construction: Option<usize>,
     pub ef_search: Option<usize>,
+    /// Settings of each vector type declared in the schema.
+    pub vector_indices: Option<Vec<VectorIndex>>,
 }
 
 impl Default for VectorConfig {
@@ -18,13 +26,237 @@ impl Default for VectorConfig {
             m: Some(16),
             ef_construction: Some(128),
             ef_search: Some(768),
+            vector_indices: None,
         }
     }
 }
 
+/// The vector index of a vector type.
+///
+/// Vector types that are not declared use the cosine distance and accept vectors of any length,
+/// and HNSW parameters that are not set fall back to the `VectorConfig` ones.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VectorIndex {
+    pub label: String,
+    #[serde(default)]
+    pub metric: DistanceMetric,
+    #[serde(default)]
+    pub m: Option<usize>,
+    #[serde(default)]
+    pub ef_construction: Option<usize>,
+    #[serde(default)]
+    pub ef_search: Option<usize>,
+    /// The declared length of the type's vectors, e.g. `1536` in `V::Doc(1536)`
+    #[serde(default)]
+    pub dimension: Option<usize>,
+    /// How the type's vectors are stored, e.g. `int8` in `V::Doc(encoding: int8)`
+    #[serde(default)]
+    pub encoding: VectorEncoding,
+    /// Whether each item of the type holds several vectors, e.g. `V::Passage(128, multi)`
+    #[serde(default)]
+    pub multi: bool,
+}
+
+impl VectorIndex {
+    pub fn new(label: &str, metric: DistanceMetric) -> Self {
+        Self {
+            label: label.to_string(),
+            metric,
+            m: None,
+            ef_construction: None,
+            ef_search: None,
+            dimension: None,
+            encoding: VectorEncoding::F64,
+            multi: false,
+        }
+    }
+
+    pub fn with_encoding(mut self, encoding: VectorEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn with_multi(mut self) -> Self {
+        self.multi = true;
+        self
+    }
+
+    pub fn with_dimension(mut self, dimension: usize) -> Self {
+        self.dimension = Some(dimension);
+        self
+    }
+
+    pub fn with_hnsw(
+        mut self,
+        m: Option<usize>,
+        ef_construction: Option<usize>,
+        ef_search: Option<usize>,
+    ) -> Self {
+        self.m = m;
+        self.ef_construction = ef_construction;
+        self.ef_search = ef_search;
+        self
+    }
+}
+
+impl fmt::Display for VectorIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "VectorIndex::new(\"{}\", DistanceMetric::{:?})",
+            self.label, self.metric
+        )?;
+        if self.m.is_some() || self.ef_construction.is_some() || self.ef_search.is_some() {
+            write!(
+                f,
+                ".with_hnsw({:?}, {:?}, {:?})",
+                self.m, self.ef_construction, self.ef_search
+            )?;
+        }
+        if let Some(dimension) = self.dimension {
+            write!(f, ".with_dimension({dimension})")?;
+        }
+        if self.encoding != VectorEncoding::F64 {
+            write!(f, ".with_encoding(VectorEncoding::{:?})", self.encoding)?;
+        }
+        if self.multi {
+            write!(f, ".with_multi()")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct GraphConfig {
-    pub secondary_indices: Option<Vec<String>>,
+    pub secondary_indices: Option<Vec<SecondaryIndex>>,
+    /// Secondary indices on edge properties, declared with `INDEX` in an edge's `Properties`.
+    pub edge_secondary_indices: Option<Vec<SecondaryIndex>>,
+    /// Fields of nodes and vectors whose text is indexed for BM25, declared with `TEXT`.
+    /// Every field is indexed when this is not set.
+    #[serde(default)]
+    pub text_fields: Option<Vec<TextField>>,
+}
+
+/// Label of the node indices that cover every label, listed by field alone in configs
+/// written before indices were scoped by label.
+pub const GLOBAL_INDEX_LABEL: &str = "*";
+
+/// A secondary index on a field, or on several fields, of a node or edge label.
+///
+/// Indices are scoped to their label so the same field name on two labels
+/// never shares an index.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "SecondaryIndexEntry")]
+pub struct SecondaryIndex {
+    pub label: String,
+    /// The indexed field, or the comma separated fields of a composite index.
+    pub field: String,
+    /// Declared `UNIQUE INDEX`, at most one node of the label may hold a given value.
+    #[serde(default)]
+    pub unique: bool,
+}
+
+impl SecondaryIndex {
+    pub fn new(label: &str, field: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            field: field.to_string(),
+            unique: false,
+        }
+    }
+
+    pub fn unique(label: &str, field: &str) -> Self {
+        Self {
+            unique: true,
+            ..Self::new(label, field)
+        }
+    }
+
+    /// An index on `field` of every node label, used by a label without an index of its own on it
+    pub fn global(field: &str) -> Self {
+        Self::new(GLOBAL_INDEX_LABEL, field)
+    }
+}
+
+/// A secondary index as written in `config.hx.json`.
+///
+/// Configs written before indices were scoped by label list bare field names,
+/// which become global indices. The tables those versions kept them in, named after the field
+/// and keyed by bincode, are abandoned: global indices are rebuilt in tables of their own.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SecondaryIndexEntry {
+    Field(String),
+    Index {
+        label: String,
+        field: String,
+        #[serde(default)]
+        unique: bool,
+    },
+}
+
+impl From<SecondaryIndexEntry> for SecondaryIndex {
+    fn from(entry: SecondaryIndexEntry) -> Self {
+        match entry {
+            SecondaryIndexEntry::Field(field) => Self::global(&field),
+            SecondaryIndexEntry::Index {
+                label,
+                field,
+                unique,
+            } => Self {
+                label,
+                field,
+                unique,
+            },
+        }
+    }
+}
+
+impl fmt::Display for SecondaryIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SecondaryIndex::{}(\"{}\", \"{}\")",
+            if self.unique { "unique" } else { "new" },
+            self.label,
+            self.field
+        )
+    }
+}
+
+/// A field of a node or vector label whose text is indexed for BM25.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextField {
+    pub label: String,
+    pub field: String,
+    /// How the field's text is split into terms, e.g. `english` in `TEXT(english) title: String`
+    #[serde(default)]
+    pub analyzer: TextAnalyzer,
+}
+
+impl TextField {
+    pub fn new(label: &str, field: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            field: field.to_string(),
+            analyzer: TextAnalyzer::Standard,
+        }
+    }
+
+    pub fn with_analyzer(mut self, analyzer: TextAnalyzer) -> Self {
+        self.analyzer = analyzer;
+        self
+    }
+}
+
+impl fmt::Display for TextField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TextField::new(\"{}\", \"{}\")", self.label, self.field)?;
+        if self.analyzer != TextAnalyzer::Standard {
+            write!(f, ".with_analyzer(TextAnalyzer::{:?})", self.analyzer)?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -34,6 +266,9 @@ pub struct Config {
     pub db_max_size_gb: Option<usize>,
     pub mcp: Option<bool>,
     pub bm25: Option<bool>,
+    /// Whether the bm25 index stores the positions of terms, for phrase and proximity queries
+    #[serde(default)]
+    pub bm25_positions: Option<bool>,
     pub schema: Option<String>,
     pub embedding_model: Option<String>,
     pub graphvis_node_label: Option<String>,
@@ -56,13 +291,17 @@ impl Config {
                 m: Some(m),
                 ef_construction: Some(ef_construction),
                 ef_search: Some(ef_search),
+                vector_indices: None,
             }),
             graph_config: Some(GraphConfig {
                 secondary_indices: None,
+                edge_secondary_indices: None,
+                text_fields: None,
             }),
             db_max_size_gb: Some(db_max_size_gb),
             mcp: Some(mcp),
             bm25: Some(bm25),
+            bm25_positions: None,
             schema,
             embedding_model,
             graphvis_node_label,
@@ -134,6 +373,10 @@ impl Config {
         self.bm25.unwrap_or(true)
     }
 
+    pub fn get_bm25_positions(&self) -> bool {
+        self.bm25_positions.unwrap_or(false)
+    }
+
     pub fn get_schema(&self) -> Option<String> {
         self.schema.clone()
     }
@@ -146,13 +389,17 @@ impl Default for Config {
                 m: Some(16),
                 ef_construction: Some(128),
                 ef_search: Some(768),
+                vector_indices: None,
             }),
             graph_config: Some(GraphConfig {
                 secondary_indices: None,
+                edge_secondary_indices: None,
+                text_fields: None,
             }),
             db_max_size_gb: Some(10),
             mcp: Some(true),
             bm25: Some(true),
+            bm25_positions: None,
             schema: None,
             embedding_model: Some("text-embedding-ada-002".to_string()),
             graphvis_node_label: None,
@@ -192,22 +439,58 @@ impl fmt::Display for Config {
                 .ef_search
                 .unwrap_or(768)
         )?;
+        writeln!(
+            f,
+            "vector_indices: {},",
+            match VECTOR_INDICES.get() {
+                Some(indices) => format!(
+                    "Some(vec![{}])",
+                    indices
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                None => "None".to_string(),
+            }
+        )?;
         writeln!(f, "}}),")?;
         writeln!(f, "graph_config: Some(GraphConfig {{")?;
+        let write_indices = |indices: Option<&Vec<SecondaryIndex>>| match indices {
+            Some(indices) => {
+                format!(
+                    "Some(vec![{}])",
+                    indices
+                        .iter()
+                        .map(|i| i.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+            None => "None".to_string(),
+        };
         writeln!(
             f,
             "secondary_indices: {},",
-            match SECONDARY_INDICES.get() {
-                Some(indices) => {
-                    format!(
-                        "Some(vec![{}])",
-                        indices
-                            .iter()
-                            .map(|i| format!("\"{i}\".to_string()"))
-                            .collect::<Vec<_>>()
-                            .join(", ")
-                    )
-                }
+            write_indices(SECONDARY_INDICES.get())
+        )?;
+        writeln!(
+            f,
+            "edge_secondary_indices: {},",
+            write_indices(EDGE_SECONDARY_INDICES.get())
+        )?;
+        writeln!(
+            f,
+            "text_fields: {},",
+            match TEXT_FIELDS.get() {
+                Some(fields) => format!(
+                    "Some(vec![{}])",
+                    fields
+                        .iter()
+                        .map(|field| field.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
                 None => "None".to_string(),
             }
         )?;
@@ -219,6 +502,11 @@ impl fmt::Display for Config {
         )?;
         writeln!(f, "mcp: Some({}),", self.mcp.unwrap_or(true))?;
         writeln!(f, "bm25: Some({}),", self.bm25.unwrap_or(true))?;
+        writeln!(
+            f,
+            "bm25_positions: Some({}),",
+            self.bm25_positions.unwrap_or(false)
+        )?;
         if let Some(data) = INTROSPECTION_DATA.get()
             && let Ok(stringified) = sonic_rs::to_string_pretty(data)
         {
@@ -1,82 +1,117 @@
-/*
-use heed3::RoTxn;
-
-use super::super::tr_val::TraversalValue;
 use crate::helix_engine::{
-    bm25::bm25::{BM25, HybridSearch},
-    graph_core::traversal_iter::RoTraversalIterator,
-    storage_core::{HelixGraphStorage, storage_methods::StorageMethods},
+    bm25::bm25::{HybridFusion, bm25_search_label, sparse_search_label, vector_search_label},
+    storage_core::HelixGraphStorage,
+    traversal_core::{traversal_iter::RoTraversalIterator, traversal_value::TraversalValue},
     types::GraphError,
+    vector_core::sparse_vector::SparseVector,
 };
-use std::sync::Arc;
-
-pub struct HybridSearchBM25<'scope, 'inner> {
-    txn: &'scope RoTxn<'scope>,
-    iter: std::vec::IntoIter<(u128, f32)>,
-    storage: Arc<HelixGraphStorage>,
-    label: &'inner str,
-}
-
-// implementing iterator for HybridSearchBM25
-impl<'scope, 'inner> Iterator for HybridSearchBM25<'scope, 'inner> {
-    type Item = Result<TraversalValue, GraphError>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let next = self.iter.next()?;
-        match self.storage.get_node(self.txn, &next.0) {
-            Ok(node) => {
-                if node.label == self.label {
-                    Some(Ok(TraversalValue::Node(node)))
-                } else {
-                    return None;
-                }
-            }
-            Err(e) => Some(Err(e)),
-        }
-    }
-}
+use heed3::RoTxn;
 
 pub trait HybridSearchBM25Adapter<'a>: Iterator<Item = Result<TraversalValue, GraphError>> {
-    fn hybrid_search_bm25(
+    /// Searches both the hnsw index of the label and the bm25 docs of its vectors,
+    /// returning the k vectors ranked highest once the two rankings are fused
+    fn hybrid_search_bm25<K>(
+        self,
+        label: &str,
+        query: &str,
+        query_vector: &[f64],
+        k: K,
+        fusion: HybridFusion,
+    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        K: TryInto<usize>,
+        K::Error: std::fmt::Debug;
+
+    /// Searches both the sparse vectors of the label, given as the query's non-zero
+    /// dimensions and their weights, and the bm25 docs of those vectors
+    #[allow(clippy::too_many_arguments)]
+    fn hybrid_search_bm25_sparse<K>(
         self,
         label: &str,
         query: &str,
-        query_vector: Vec<f64>,
-        k: usize,
-    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>;
+        indices: &[u32],
+        weights: &[f64],
+        k: K,
+        fusion: HybridFusion,
+    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        K: TryInto<usize>,
+        K::Error: std::fmt::Debug;
 }
 
 impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>>> HybridSearchBM25Adapter<'a>
     for RoTraversalIterator<'a, I>
 {
-    fn hybrid_search_bm25(
+    fn hybrid_search_bm25<K>(
         self,
         label: &str,
         query: &str,
-        query_vector: &Vec<f64>,
-        k: usize,
-    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>> {
-        // check bm25 enabled
-
-        let results = self.storage.hybrid_search(
-            query,
-            query_vector,
-            0.5,
-            k,
+        query_vector: &[f64],
+        k: K,
+        fusion: HybridFusion,
+    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        K: TryInto<usize>,
+        K::Error: std::fmt::Debug,
+    {
+        let k = k.try_into().unwrap();
+        let results = bm25_search_label(&self.storage, self.txn, query, label, k * 2).and_then(
+            |bm25_results| {
+                let vector_results =
+                    vector_search_label(&self.storage, self.txn, query_vector, label, k * 2)?;
+                Ok(fusion.fuse(bm25_results, vector_results, k))
+            },
         );
 
-        let iter = HybridSearchBM25 {
+        RoTraversalIterator {
+            inner: hybrid_results(&self.storage, self.txn, results),
+            storage: self.storage,
             txn: self.txn,
-            iter: results,
-            storage: Arc::clone(&self.storage),
-            label,
-        };
+        }
+    }
+
+    fn hybrid_search_bm25_sparse<K>(
+        self,
+        label: &str,
+        query: &str,
+        indices: &[u32],
+        weights: &[f64],
+        k: K,
+        fusion: HybridFusion,
+    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        K: TryInto<usize>,
+        K::Error: std::fmt::Debug,
+    {
+        let k = k.try_into().unwrap();
+        let results = bm25_search_label(&self.storage, self.txn, query, label, k * 2).and_then(
+            |bm25_results| {
+                let query_vector = SparseVector::new(indices, weights)?;
+                let sparse_results =
+                    sparse_search_label(&self.storage, self.txn, &query_vector, label, k * 2)?;
+                Ok(fusion.fuse(bm25_results, sparse_results, k))
+            },
+        );
+
         RoTraversalIterator {
-            inner: iter,
+            inner: hybrid_results(&self.storage, self.txn, results),
             storage: self.storage,
             txn: self.txn,
         }
     }
 }
-*/
 
+fn hybrid_results(
+    storage: &HelixGraphStorage,
+    txn: &RoTxn,
+    results: Result<Vec<(u128, f32)>, GraphError>,
+) -> std::vec::IntoIter<Result<TraversalValue, GraphError>> {
+    match results {
+        Ok(results) => results
+            .into_iter()
+            .map(|(id, _)| storage.get_vector(txn, &id).map(TraversalValue::Vector))
+            .collect::<Vec<_>>()
+            .into_iter(),
+        Err(e) => vec![Err(e)].into_iter(),
+    }
+}
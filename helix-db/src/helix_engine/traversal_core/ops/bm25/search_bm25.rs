@@ -1,7 +1,7 @@
 use heed3::RoTxn;
 
 use crate::helix_engine::{
-    bm25::bm25::BM25,
+    bm25::query::TermExpansion,
     traversal_core::{traversal_iter::RoTraversalIterator, traversal_value::TraversalValue},
     storage_core::{HelixGraphStorage, storage_methods::StorageMethods},
     types::GraphError,
@@ -44,6 +44,19 @@ pub trait SearchBM25Adapter<'a>: Iterator<Item = Result<TraversalValue, GraphErr
         RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>,
         GraphError,
     >;
+
+    /// Searches like `search_bm25`, expanding each word of the query by `expansion`
+    /// unless it sets its own, e.g. `SearchBM25<Doc>(text, 10, fuzzy: 1)` in HQL
+    fn search_bm25_expanded(
+        self,
+        label: &str,
+        query: &str,
+        k: usize,
+        expansion: TermExpansion,
+    ) -> Result<
+        RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>,
+        GraphError,
+    >;
 }
 
 impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>>> SearchBM25Adapter<'a>
@@ -58,22 +71,54 @@ impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>>> SearchBM25Adapt
         RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>,
         GraphError,
     > {
-        let results = match self.storage.bm25.as_ref() {
-            Some(s) => s.search(self.txn, query, k)?,
-            None => return Err(GraphError::from("BM25 not enabled!")),
-        };
+        search_nodes(self, label, query, k, None)
+    }
 
-        let iter = SearchBM25 {
-            txn: self.txn,
-            iter: results.into_iter(),
-            storage: Arc::clone(&self.storage),
-            label,
-        };
-        Ok(RoTraversalIterator {
-            inner: iter,
-            storage: self.storage,
-            txn: self.txn,
-        })
+    fn search_bm25_expanded(
+        self,
+        label: &str,
+        query: &str,
+        k: usize,
+        expansion: TermExpansion,
+    ) -> Result<
+        RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>,
+        GraphError,
+    > {
+        search_nodes(self, label, query, k, Some(expansion))
     }
 }
 
+fn search_nodes<'a, I: Iterator<Item = Result<TraversalValue, GraphError>>>(
+    traversal: RoTraversalIterator<'a, I>,
+    label: &str,
+    query: &str,
+    k: usize,
+    expansion: Option<TermExpansion>,
+) -> Result<
+    RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>,
+    GraphError,
+> {
+    let RoTraversalIterator { storage, txn, .. } = traversal;
+    // the index also holds the docs of vectors and other node labels
+    let results = match storage.bm25.as_ref() {
+        Some(s) => s.search_expanded_query(txn, label, query, expansion, k, |id| {
+            storage
+                .get_node(txn, &id)
+                .is_ok_and(|node| node.label == label)
+        })?,
+        None => return Err(GraphError::from("BM25 not enabled!")),
+    };
+
+    let iter = SearchBM25 {
+        txn,
+        iter: results.into_iter(),
+        storage: Arc::clone(&storage),
+        label,
+    };
+    Ok(RoTraversalIterator {
+        inner: iter,
+        storage,
+        txn,
+    })
+}
+
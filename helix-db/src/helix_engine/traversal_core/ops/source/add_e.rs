@@ -3,7 +3,8 @@ use std::fmt::Display;
 use crate::{
     helix_engine::{
         traversal_core::traversal_iter::RwTraversalIterator,
-        traversal_core::traversal_value::TraversalValue, storage_core::HelixGraphStorage,
+        traversal_core::traversal_value::TraversalValue,
+        storage_core::{HelixGraphStorage, index_key::encode_item_index_key},
         types::GraphError, vector_core::hnsw::HNSW,
     },
     protocol::value::Value,
@@ -132,6 +133,14 @@ impl<'a, 'b, I: Iterator<Item = Result<TraversalValue, GraphError>>> AddEAdapter
 
         let label_hash = hash_label(edge.label.as_str(), None);
 
+        if let Err(e) = self
+            .storage
+            .edge_labels_db
+            .put(self.txn, &label_hash, &edge.id)
+        {
+            result = Err(GraphError::from(e));
+        }
+
         match self.storage.out_edges_db.put_with_flags(
             self.txn,
             PutFlags::APPEND_DUP,
@@ -162,6 +171,17 @@ impl<'a, 'b, I: Iterator<Item = Result<TraversalValue, GraphError>>> AddEAdapter
             }
         }
 
+        if let Some(indices) = self.storage.edge_secondary_indices.get(label) {
+            for (field, db) in indices {
+                // edges without the property are left out of its index
+                if let Ok(key) = encode_item_index_key(&edge, field)
+                    && let Err(e) = db.put(self.txn, &key, &edge.id)
+                {
+                    result = Err(GraphError::from(e));
+                }
+            }
+        }
+
         let result = match result {
             Ok(_) => Ok(TraversalValue::Edge(edge)),
             Err(_) => Err(GraphError::EdgeNotFound),
@@ -181,11 +201,17 @@ impl<'a, 'b, I: Iterator<Item = Result<TraversalValue, GraphError>>> AddEAdapter
                 .nodes_db
                 .get(self.txn, HelixGraphStorage::node_key(node_vec_id))
                 .is_ok_and(|node| node.is_some()),
-            EdgeType::Vec => self
-                .storage
-                .vectors
-                .get_vector(self.txn, *node_vec_id, 0, false)
-                .is_ok(),
+            EdgeType::Vec => {
+                self.storage
+                    .vectors
+                    .get_vector(self.txn, *node_vec_id, 0, false)
+                    .is_ok()
+                    || self
+                        .storage
+                        .sparse
+                        .contains(self.txn, *node_vec_id)
+                        .unwrap_or(false)
+            }
         };
 
         if !exists {
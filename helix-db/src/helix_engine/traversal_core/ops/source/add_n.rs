@@ -1,10 +1,13 @@
 
 use crate::{
     helix_engine::{
-        bm25::bm25::{BM25Flatten, BM25}, traversal_core::{traversal_iter::RwTraversalIterator, traversal_value::TraversalValue}, types::GraphError
+        bm25::bm25::BM25,
+        storage_core::index_key::encode_item_index_key,
+        traversal_core::{traversal_iter::RwTraversalIterator, traversal_value::TraversalValue},
+        types::GraphError,
     },
     protocol::value::Value,
-    utils::{filterable::Filterable, id::v6_uuid, items::Node},
+    utils::{id::v6_uuid, items::Node, label_hash::hash_label},
 };
 use heed3::PutFlags;
 
@@ -47,6 +50,21 @@ impl<'a, 'b, I: Iterator<Item = Result<TraversalValue, GraphError>>> AddNAdapter
         let secondary_indices = secondary_indices.unwrap_or(&[]).to_vec();
         let mut result: Result<TraversalValue, GraphError> = Ok(TraversalValue::Empty);
 
+        // reject the node before anything is written if it would break a unique index
+        for index in &secondary_indices {
+            if let Ok(key) = encode_item_index_key(&node, index)
+                && let Err(e) = self
+                    .storage
+                    .check_unique(self.txn, label, index, &key, &node.id)
+            {
+                return RwTraversalIterator {
+                    inner: std::iter::once(Err(e)),
+                    storage: self.storage,
+                    txn: self.txn,
+                };
+            }
+        }
+
         match node.encode_node() {
             Ok(bytes) => {
                 if let Err(e) = self.storage.nodes_db.put_with_flags(
@@ -61,56 +79,50 @@ impl<'a, 'b, I: Iterator<Item = Result<TraversalValue, GraphError>>> AddNAdapter
             Err(e) => result = Err(e),
         }
 
+        if let Err(e) = self.storage.node_labels_db.put(
+            self.txn,
+            &hash_label(&node.label, None),
+            &node.id,
+        ) {
+            result = Err(GraphError::from(e));
+        }
+
         for index in secondary_indices {
-            match self.storage.secondary_indices.get(index) {
+            match self.storage.get_secondary_index(label, index) {
                 Some(db) => {
-                    let key = match node.check_property(index) {
-                        Ok(value) => value,
+                    let key = match encode_item_index_key(&node, index) {
+                        Ok(key) => key,
                         Err(e) => {
                             result = Err(e);
                             continue;
                         }
                     };
-                    // look into if there is a way to serialize to a slice
-                    match bincode::serialize(&key) {
-                        Ok(serialized) => {
-                            // possibly append dup
-
-                            if let Err(e) = db.put(self.txn, &serialized, &node.id) {
-                                println!(
-                                    "{} Error adding node to secondary index: {:?}",
-                                    line!(),
-                                    e
-                                );
-                                result = Err(GraphError::from(e));
-                            }
-                        }
-                        Err(e) => result = Err(GraphError::from(e)),
+                    if let Err(e) = db.put(self.txn, &key, &node.id) {
+                        println!(
+                            "{} Error adding node to secondary index: {:?}",
+                            line!(),
+                            e
+                        );
+                        result = Err(GraphError::from(e));
                     }
                 }
                 None => {
                     result = Err(GraphError::New(format!(
-                        "Secondary Index {index} not found"
+                        "Secondary Index {label}.{index} not found"
                     )));
                 }
             }
         }
 
         if let Some(bm25) = &self.storage.bm25
-            && let Some(props) = node.properties.as_ref() {
-                let mut data = props.flatten_bm25();
-                data.push_str(&node.label);
-                if let Err(e) = bm25.insert_doc(self.txn, node.id, &data) {
-                    result = Err(e);
-                }
+            && let Some(props) = node.properties.as_ref()
+            && let Some(terms) = bm25.doc_fields(&node.label, props)
+            && let Err(e) = bm25.insert_fields(self.txn, node.id, terms) {
+                result = Err(e);
         }
 
         if result.is_ok() {
             result = Ok(TraversalValue::Node(node.clone()));
-        } else {
-            result = Err(GraphError::New(
-                "Failed to add node to secondary indices".to_string(),
-            ));
         }
 
         RwTraversalIterator {
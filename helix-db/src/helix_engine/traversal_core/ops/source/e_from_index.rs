@@ -0,0 +1,109 @@
+use crate::{
+    helix_engine::{
+        storage_core::{
+            HelixGraphStorage, index_key::encode_index_key, storage_methods::StorageMethods,
+        },
+        traversal_core::{traversal_iter::RoTraversalIterator, traversal_value::TraversalValue},
+        types::GraphError,
+    },
+    protocol::value::Value,
+};
+use heed3::{RoTxn, byteorder::BE};
+use helix_macros::debug_trace;
+use serde::Serialize;
+use std::sync::Arc;
+
+pub struct EFromIndex<'a> {
+    iter: Option<
+        heed3::RoPrefix<'a, heed3::types::Bytes, heed3::types::LazyDecode<heed3::types::U128<BE>>>,
+    >,
+    error: Option<GraphError>,
+    txn: &'a RoTxn<'a>,
+    storage: Arc<HelixGraphStorage>,
+    label: &'a str,
+}
+
+impl<'a> Iterator for EFromIndex<'a> {
+    type Item = Result<TraversalValue, GraphError>;
+
+    #[debug_trace("E_FROM_INDEX")]
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+        for value in self.iter.as_mut()?.by_ref() {
+            let (_, value) = match value {
+                Ok(value) => value,
+                Err(e) => return Some(Err(GraphError::from(e))),
+            };
+            match value.decode() {
+                Ok(id) => match self.storage.get_edge(self.txn, &id) {
+                    Ok(edge) if edge.label == self.label => {
+                        return Some(Ok(TraversalValue::Edge(edge)));
+                    }
+                    Ok(_) => continue,
+                    Err(e) => return Some(Err(e)),
+                },
+                Err(e) => return Some(Err(GraphError::ConversionError(e.to_string()))),
+            }
+        }
+        None
+    }
+}
+
+pub trait EFromIndexAdapter<'a, K: Into<Value> + Serialize>:
+    Iterator<Item = Result<TraversalValue, GraphError>>
+{
+    type OutputIter: Iterator<Item = Result<TraversalValue, GraphError>>;
+
+    /// Returns a new iterator that will return the edges from the secondary index.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label of the edges to return.
+    /// * `index` - The name of the indexed property.
+    /// * `key` - The key to search for in the secondary index.
+    ///
+    /// The index must be declared with `INDEX` in the `Properties` of the edge type `label`.
+    fn e_from_index(self, label: &'a str, index: &'a str, key: &'a K) -> Self::OutputIter
+    where
+        K: Into<Value> + Serialize + Clone;
+}
+
+impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>>, K: Into<Value> + Serialize + 'a>
+    EFromIndexAdapter<'a, K> for RoTraversalIterator<'a, I>
+{
+    type OutputIter = RoTraversalIterator<'a, EFromIndex<'a>>;
+
+    #[inline]
+    fn e_from_index(self, label: &'a str, index: &'a str, key: &'a K) -> Self::OutputIter
+    where
+        K: Into<Value> + Serialize + Clone,
+    {
+        let iter = match self.storage.get_edge_secondary_index(label, index) {
+            Some(db) => db
+                .lazily_decode_data()
+                .prefix_iter(self.txn, &encode_index_key(&Value::from(key)))
+                .map_err(GraphError::from),
+            None => Err(GraphError::New(format!(
+                "Edge Secondary Index {label}.{index} not found"
+            ))),
+        };
+        let (iter, error) = match iter {
+            Ok(iter) => (Some(iter), None),
+            Err(e) => (None, Some(e)),
+        };
+
+        RoTraversalIterator {
+            inner: EFromIndex {
+                iter,
+                error,
+                txn: self.txn,
+                storage: Arc::clone(&self.storage),
+                label,
+            },
+            storage: self.storage,
+            txn: self.txn,
+        }
+    }
+}
@@ -1,35 +1,65 @@
 use crate::{
     helix_engine::{
+        storage_core::{HelixGraphStorage, storage_methods::StorageMethods},
         traversal_core::{traversal_value::TraversalValue, traversal_iter::RoTraversalIterator},
         types::GraphError,
     },
-    utils::items::Edge,
+    utils::label_hash::hash_label,
 };
 use heed3::{
+    RoTxn,
     byteorder::BE,
-    types::{Bytes, U128},
+    types::{Bytes, LazyDecode, U128},
 };
 use helix_macros::debug_trace;
+use itertools::Either;
+use std::sync::Arc;
 
 pub struct EFromType<'a> {
-    pub iter: heed3::RoIter<'a, U128<BE>, heed3::types::LazyDecode<Bytes>>,
+    pub iter: heed3::RoPrefix<'a, Bytes, LazyDecode<U128<BE>>>,
+    pub txn: &'a RoTxn<'a>,
+    pub storage: Arc<HelixGraphStorage>,
     pub label: &'a str,
 }
 
+impl<'a> EFromType<'a> {
+    /// Creates an iterator over the edge label index for the given label.
+    pub fn new(
+        storage: Arc<HelixGraphStorage>,
+        txn: &'a RoTxn<'a>,
+        label: &'a str,
+    ) -> Result<Self, GraphError> {
+        let iter = storage
+            .edge_labels_db
+            .lazily_decode_data()
+            .prefix_iter(txn, &hash_label(label, None))?;
+        Ok(EFromType {
+            iter,
+            txn,
+            storage,
+            label,
+        })
+    }
+}
+
 impl<'a> Iterator for EFromType<'a> {
     type Item = Result<TraversalValue, GraphError>;
 
     #[debug_trace("E_FROM_TYPE")]
     fn next(&mut self) -> Option<Self::Item> {
         for value in self.iter.by_ref() {
-            let (key, value) = value.unwrap();
+            let (_, value) = match value {
+                Ok(value) => value,
+                Err(e) => return Some(Err(GraphError::from(e))),
+            };
             match value.decode() {
-                Ok(value) => match Edge::decode_edge(value, key) {
+                Ok(id) => match self.storage.get_edge(self.txn, &id) {
+                    // label hashes can collide so the label is still checked
                     Ok(edge) => match &edge.label {
                         label if label == self.label => return Some(Ok(TraversalValue::Edge(edge))),
                         _ => continue,
                     },
-                    Err(e) => return Some(Err(GraphError::ConversionError(e.to_string()))),
+                    Err(e) => return Some(Err(e)),
                 },
                 Err(e) => return Some(Err(GraphError::ConversionError(e.to_string()))),
             }
@@ -51,14 +81,13 @@ impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>>> EFromTypeAdapte
         self,
         label: &'a str,
     ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>> {
-        let iter = self
-            .storage
-            .edges_db
-            .lazily_decode_data()
-            .iter(self.txn)
-            .unwrap();
+        // an error opening the label index is the only item
+        let iter = match EFromType::new(Arc::clone(&self.storage), self.txn, label) {
+            Ok(iter) => Either::Left(iter),
+            Err(e) => Either::Right(std::iter::once(Err(e))),
+        };
         RoTraversalIterator {
-            inner: EFromType { iter, label },
+            inner: iter,
             storage: self.storage,
             txn: self.txn,
         }
@@ -1,7 +1,9 @@
 use crate::{
     helix_engine::{
         traversal_core::{traversal_value::TraversalValue, traversal_iter::RoTraversalIterator},
-        storage_core::{HelixGraphStorage, storage_methods::StorageMethods},
+        storage_core::{
+            HelixGraphStorage, index_key::encode_index_key, storage_methods::StorageMethods,
+        },
         types::GraphError,
     },
     protocol::value::Value,
@@ -12,8 +14,10 @@ use serde::Serialize;
 use std::sync::Arc;
 
 pub struct NFromIndex<'a> {
-    iter:
+    iter: Option<
         heed3::RoPrefix<'a, heed3::types::Bytes, heed3::types::LazyDecode<heed3::types::U128<BE>>>,
+    >,
+    error: Option<GraphError>,
     txn: &'a RoTxn<'a>,
     storage: Arc<HelixGraphStorage>,
     label: &'a str,
@@ -24,8 +28,14 @@ impl<'a> Iterator for NFromIndex<'a> {
 
     #[debug_trace("N_FROM_INDEX")]
     fn next(&mut self) -> Option<Self::Item> {
-        for value in self.iter.by_ref() {
-            let (_, value) = value.unwrap();
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+        for value in self.iter.as_mut()?.by_ref() {
+            let (_, value) = match value {
+                Ok(value) => value,
+                Err(e) => return Some(Err(GraphError::from(e))),
+            };
             match value.decode() {
                 Ok(value) => match self.storage.get_node(self.txn, &value) {
                     Ok(node) => {
@@ -57,11 +67,13 @@ pub trait NFromIndexAdapter<'a, K: Into<Value> + Serialize>:
     ///
     /// # Arguments
     ///
-    /// * `index` - The name of the secondary index.
+    /// * `label` - The label of the nodes to return.
+    /// * `index` - The name of the indexed field.
     /// * `key` - The key to search for in the secondary index.
     ///
-    /// Note that both the `index` and `key` must be provided.
-    /// The index must be a valid and existing secondary index and the key should match the type of the index.
+    /// Note that the `label`, `index` and `key` must all be provided.
+    /// The index must be a valid and existing secondary index on `label` and the key should match the type of the index.
+    /// Secondary indices are scoped by label so only nodes of `label` are returned.
     fn n_from_index(self, label: &'a str, index: &'a str, key: &'a K) -> Self::OutputIter
     where
         K: Into<Value> + Serialize + Clone;
@@ -77,22 +89,23 @@ impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>>, K: Into<Value>
     where
         K: Into<Value> + Serialize + Clone,
     {
-        let db = self
-            .storage
-            .secondary_indices
-            .get(index)
-            // TODO: this
-            .ok_or(GraphError::New(format!(
-                "Secondary Index {index} not found"
-            )))
-            .unwrap();
-        let res = db
-            .lazily_decode_data()
-            .prefix_iter(self.txn, &bincode::serialize(&Value::from(key)).unwrap())
-            .unwrap();
+        let iter = match self.storage.get_secondary_index(label, index) {
+            Some(db) => db
+                .lazily_decode_data()
+                .prefix_iter(self.txn, &encode_index_key(&Value::from(key)))
+                .map_err(GraphError::from),
+            None => Err(GraphError::New(format!(
+                "Secondary Index {label}.{index} not found"
+            ))),
+        };
+        let (iter, error) = match iter {
+            Ok(iter) => (Some(iter), None),
+            Err(e) => (None, Some(e)),
+        };
 
         let n_from_index = NFromIndex {
-            iter: res,
+            iter,
+            error,
             txn: self.txn,
             storage: Arc::clone(&self.storage),
             label,
@@ -0,0 +1,204 @@
+use crate::{
+    helix_engine::{
+        storage_core::{
+            HelixGraphStorage,
+            index_key::{
+                encode_composite_key, encode_range, encode_string_prefix, prefix_successor,
+            },
+            storage_methods::StorageMethods,
+        },
+        traversal_core::{traversal_iter::RoTraversalIterator, traversal_value::TraversalValue},
+        types::GraphError,
+    },
+    protocol::value::Value,
+};
+use heed3::{RoTxn, byteorder::BE};
+use helix_macros::debug_trace;
+use serde::Serialize;
+use std::{ops::Bound, sync::Arc};
+
+pub struct NFromIndexRange<'a> {
+    iter: Option<
+        heed3::RoRange<'a, heed3::types::Bytes, heed3::types::LazyDecode<heed3::types::U128<BE>>>,
+    >,
+    error: Option<GraphError>,
+    txn: &'a RoTxn<'a>,
+    storage: Arc<HelixGraphStorage>,
+    label: &'a str,
+}
+
+impl<'a> NFromIndexRange<'a> {
+    fn new(
+        storage: Arc<HelixGraphStorage>,
+        txn: &'a RoTxn<'a>,
+        label: &'a str,
+        index: &str,
+        range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+    ) -> Self {
+        let iter = match storage.get_secondary_index(label, index) {
+            Some(db) => db
+                .lazily_decode_data()
+                .range(
+                    txn,
+                    &(
+                        range.0.as_ref().map(Vec::as_slice),
+                        range.1.as_ref().map(Vec::as_slice),
+                    ),
+                )
+                .map_err(GraphError::from),
+            None => Err(GraphError::New(format!(
+                "Secondary Index {label}.{index} not found"
+            ))),
+        };
+        let (iter, error) = match iter {
+            Ok(iter) => (Some(iter), None),
+            Err(e) => (None, Some(e)),
+        };
+        Self {
+            iter,
+            error,
+            txn,
+            storage,
+            label,
+        }
+    }
+}
+
+impl<'a> Iterator for NFromIndexRange<'a> {
+    type Item = Result<TraversalValue, GraphError>;
+
+    #[debug_trace("N_FROM_INDEX_RANGE")]
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+        for value in self.iter.as_mut()?.by_ref() {
+            let (_, value) = match value {
+                Ok(value) => value,
+                Err(e) => return Some(Err(GraphError::from(e))),
+            };
+            match value.decode() {
+                Ok(id) => match self.storage.get_node(self.txn, &id) {
+                    Ok(node) if node.label == self.label => {
+                        return Some(Ok(TraversalValue::Node(node)));
+                    }
+                    Ok(_) => continue,
+                    Err(e) => return Some(Err(e)),
+                },
+                Err(e) => return Some(Err(GraphError::ConversionError(e.to_string()))),
+            }
+        }
+        None
+    }
+}
+
+pub trait NFromIndexRangeAdapter<'a>: Iterator<Item = Result<TraversalValue, GraphError>> {
+    type OutputIter: Iterator<Item = Result<TraversalValue, GraphError>>;
+
+    /// Returns a new iterator that will return the nodes whose indexed field lies within a range.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label of the nodes to return.
+    /// * `index` - The name of the indexed field.
+    /// * `start` - The lower bound of the range.
+    /// * `end` - The upper bound of the range.
+    ///
+    /// Nodes are returned in the order of the indexed field.
+    /// An unbounded side only extends over values of the same kind as the other bound,
+    /// e.g. `(Bound::Excluded(&5), Bound::Unbounded)` returns every number greater than 5 but no strings.
+    fn n_from_index_range<K>(
+        self,
+        label: &'a str,
+        index: &'a str,
+        start: Bound<&K>,
+        end: Bound<&K>,
+    ) -> Self::OutputIter
+    where
+        K: Into<Value> + Serialize + Clone;
+
+    /// Returns a new iterator that will return the nodes whose indexed string field starts with `prefix`.
+    ///
+    /// Nodes are returned in the order of the indexed field.
+    fn n_from_index_prefix(self, label: &'a str, index: &'a str, prefix: &str) -> Self::OutputIter;
+
+    /// Returns a new iterator that will return the nodes matching `keys` in a composite index.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label of the nodes to return.
+    /// * `index` - The name of the composite index, its fields separated by commas.
+    /// * `keys` - The values of all or a leading subset of the index's fields, in the index's order.
+    ///
+    /// Nodes are returned in the order of the fields not covered by `keys`.
+    fn n_from_composite_index(
+        self,
+        label: &'a str,
+        index: &'a str,
+        keys: &[Value],
+    ) -> Self::OutputIter;
+}
+
+impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>>> NFromIndexRangeAdapter<'a>
+    for RoTraversalIterator<'a, I>
+{
+    type OutputIter = RoTraversalIterator<'a, NFromIndexRange<'a>>;
+
+    #[inline]
+    fn n_from_index_range<K>(
+        self,
+        label: &'a str,
+        index: &'a str,
+        start: Bound<&K>,
+        end: Bound<&K>,
+    ) -> Self::OutputIter
+    where
+        K: Into<Value> + Serialize + Clone,
+    {
+        let start = start.map(Value::from);
+        let end = end.map(Value::from);
+        let range = encode_range(start.as_ref(), end.as_ref());
+
+        RoTraversalIterator {
+            inner: NFromIndexRange::new(Arc::clone(&self.storage), self.txn, label, index, range),
+            storage: self.storage,
+            txn: self.txn,
+        }
+    }
+
+    #[inline]
+    fn n_from_index_prefix(self, label: &'a str, index: &'a str, prefix: &str) -> Self::OutputIter {
+        let range = prefix_range(encode_string_prefix(prefix));
+
+        RoTraversalIterator {
+            inner: NFromIndexRange::new(Arc::clone(&self.storage), self.txn, label, index, range),
+            storage: self.storage,
+            txn: self.txn,
+        }
+    }
+
+    #[inline]
+    fn n_from_composite_index(
+        self,
+        label: &'a str,
+        index: &'a str,
+        keys: &[Value],
+    ) -> Self::OutputIter {
+        let range = prefix_range(encode_composite_key(keys));
+
+        RoTraversalIterator {
+            inner: NFromIndexRange::new(Arc::clone(&self.storage), self.txn, label, index, range),
+            storage: self.storage,
+            txn: self.txn,
+        }
+    }
+}
+
+/// Range of every key starting with `prefix`.
+fn prefix_range(prefix: Vec<u8>) -> (Bound<Vec<u8>>, Bound<Vec<u8>>) {
+    let end = match prefix_successor(&prefix) {
+        Some(end) => Bound::Excluded(end),
+        None => Bound::Unbounded,
+    };
+    (Bound::Included(prefix), end)
+}
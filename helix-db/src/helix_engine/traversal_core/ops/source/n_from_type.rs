@@ -1,38 +1,65 @@
 use crate::{
     helix_engine::{
+        storage_core::{HelixGraphStorage, storage_methods::StorageMethods},
         traversal_core::{traversal_value::TraversalValue, traversal_iter::RoTraversalIterator},
         types::GraphError,
     },
-    utils::items::Node,
+    utils::label_hash::hash_label,
 };
 use helix_macros::debug_trace;
+use itertools::Either;
 use heed3::{
+    RoTxn,
     byteorder::BE,
-    types::{Bytes, U128},
+    types::{Bytes, LazyDecode, U128},
 };
+use std::sync::Arc;
 
 pub struct NFromType<'a> {
-    pub iter: heed3::RoIter<'a, U128<BE>, heed3::types::LazyDecode<Bytes>>,
+    pub iter: heed3::RoPrefix<'a, Bytes, LazyDecode<U128<BE>>>,
+    pub txn: &'a RoTxn<'a>,
+    pub storage: Arc<HelixGraphStorage>,
     pub label: &'a str,
 }
 
+impl<'a> NFromType<'a> {
+    /// Creates an iterator over the node label index for the given label.
+    pub fn new(
+        storage: Arc<HelixGraphStorage>,
+        txn: &'a RoTxn<'a>,
+        label: &'a str,
+    ) -> Result<Self, GraphError> {
+        let iter = storage
+            .node_labels_db
+            .lazily_decode_data()
+            .prefix_iter(txn, &hash_label(label, None))?;
+        Ok(NFromType {
+            iter,
+            txn,
+            storage,
+            label,
+        })
+    }
+}
+
 impl<'a> Iterator for NFromType<'a> {
     type Item = Result<TraversalValue, GraphError>;
 
     #[debug_trace("N_FROM_TYPE")]
     fn next(&mut self) -> Option<Self::Item> {
         for value in self.iter.by_ref() {
-            let (key_, value) = value.unwrap();
+            let (_, value) = match value {
+                Ok(value) => value,
+                Err(e) => return Some(Err(GraphError::from(e))),
+            };
             match value.decode() {
-                Ok(value) => match Node::decode_node(value, key_) {
+                Ok(id) => match self.storage.get_node(self.txn, &id) {
+                    // label hashes can collide so the label is still checked
                     Ok(node) => match &node.label {
                         label if label == self.label => return Some(Ok(TraversalValue::Node(node))),
                         _ => continue,
                     },
-                    Err(e) => {
-                        println!("{} Error decoding node: {:?}", line!(), e);
-                        return Some(Err(GraphError::ConversionError(e.to_string())));
-                    }
+                    Err(e) => return Some(Err(e)),
                 },
                 Err(e) => return Some(Err(GraphError::ConversionError(e.to_string()))),
             }
@@ -57,14 +84,13 @@ impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>>> NFromTypeAdapte
         self,
         label: &'a str,
     ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>> {
-        let iter = self
-            .storage
-            .nodes_db
-            .lazily_decode_data()
-            .iter(self.txn)
-            .unwrap();
+        // an error opening the label index is the only item
+        let iter = match NFromType::new(Arc::clone(&self.storage), self.txn, label) {
+            Ok(iter) => Either::Left(iter),
+            Err(e) => Either::Right(std::iter::once(Err(e))),
+        };
         RoTraversalIterator {
-            inner: NFromType { iter, label },
+            inner: iter,
             storage: self.storage,
             txn: self.txn,
         }
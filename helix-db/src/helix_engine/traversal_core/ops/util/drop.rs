@@ -39,7 +39,10 @@ where
                         Err(e) => Err(e),
                     },
                     TraversalValue::Vector(vector) => match storage.drop_vector(txn, &vector.id) {
-                        Ok(_) => Ok(()),
+                        Ok(_) => match &storage.bm25 {
+                            Some(bm25) => bm25.delete_doc(txn, vector.id),
+                            None => Ok(()),
+                        },
                         Err(e) => Err(e),
                     },
                     _ => Err(GraphError::ConversionError(format!(
@@ -1,9 +1,12 @@
-use heed3::PutFlags;
-
 use crate::{
     helix_engine::{
+        bm25::bm25::BM25,
         traversal_core::{traversal_iter::RwTraversalIterator, traversal_value::TraversalValue},
-        storage_core::{HelixGraphStorage, storage_methods::StorageMethods},
+        storage_core::{
+            HelixGraphStorage,
+            index_key::{COMPOSITE_INDEX_SEPARATOR, encode_item_index_key},
+            storage_methods::StorageMethods,
+        },
         types::GraphError,
     },
     protocol::value::Value,
@@ -52,27 +55,24 @@ impl<'scope, 'env, I: Iterator<Item = Result<TraversalValue, GraphError>>> Updat
             match item {
                 Ok(TraversalValue::Node(node)) => match storage.get_node(self.txn, &node.id) {
                     Ok(mut old_node) => {
-                        let mut properties = old_node.properties.unwrap_or_default();
+                        // indices over any of the updated fields, with the node's key before the update
+                        let indices = match &props {
+                            Some(props) => storage
+                                .node_indices(&old_node.label)
+                                .into_iter()
+                                .filter(|(index, _)| {
+                                    index
+                                        .split(COMPOSITE_INDEX_SEPARATOR)
+                                        .any(|field| props.iter().any(|(key, _)| key == field))
+                                })
+                                .map(|(index, db)| {
+                                    (index, db, encode_item_index_key(&old_node, index).ok())
+                                })
+                                .collect::<Vec<_>>(),
+                            _ => Vec::new(),
+                        };
 
-                        if let Some(ref props) = props {
-                            for (key, _new_value) in props.iter() {
-                                if let Some(db) = storage.secondary_indices.get(key)
-                                    && let Some(old_value) = properties.get(key) {
-                                        match bincode::serialize(old_value) {
-                                            Ok(old_serialized) => {
-                                                if let Err(e) = db.delete_one_duplicate(
-                                                    self.txn,
-                                                    &old_serialized,
-                                                    &node.id,
-                                                ) {
-                                                    vec.push(Err(GraphError::from(e)));
-                                                }
-                                            }
-                                            Err(e) => vec.push(Err(GraphError::from(e))),
-                                        }
-                                    }
-                            }
-                        }
+                        let mut properties = old_node.properties.unwrap_or_default();
 
                         if let Some(ref props) = props {
                             for (k, v) in props.iter() {
@@ -80,32 +80,63 @@ impl<'scope, 'env, I: Iterator<Item = Result<TraversalValue, GraphError>>> Updat
                             }
                         }
 
-                        if let Some(ref props) = props {
-                            for (key, new_value) in props.iter() {
-                                if let Some(db) = storage.secondary_indices.get(key) {
-                                    match bincode::serialize(new_value) {
-                                        Ok(new_serialized) => {
-                                            if let Err(e) = db.put_with_flags(
-                                                self.txn,
-                                                PutFlags::APPEND_DUP,
-                                                &new_serialized,
-                                                &node.id,
-                                            ) {
-                                                vec.push(Err(GraphError::from(e)));
-                                            }
-                                        }
-                                        Err(e) => vec.push(Err(GraphError::from(e))),
-                                    }
-                                }
-                            }
-                        }
-
                         if properties.is_empty() {
                             old_node.properties = None;
                         } else {
                             old_node.properties = Some(properties);
                         }
 
+                        let indices = indices
+                            .into_iter()
+                            .map(|(index, db, old_key)| {
+                                let new_key = encode_item_index_key(&old_node, index).ok();
+                                (index, db, old_key, new_key)
+                            })
+                            .collect::<Vec<_>>();
+
+                        if let Some(e) = indices.iter().find_map(|(index, _, _, new_key)| {
+                            let new_key = new_key.as_ref()?;
+                            storage
+                                .check_unique(self.txn, &old_node.label, index, new_key, &node.id)
+                                .err()
+                        }) {
+                            vec.push(Err(e));
+                            continue;
+                        }
+
+                        for (_, db, old_key, new_key) in indices {
+                            if let Some(old_key) = old_key
+                                && let Err(e) = db.delete_one_duplicate(self.txn, &old_key, &node.id)
+                            {
+                                vec.push(Err(GraphError::from(e)));
+                            }
+                            if let Some(new_key) = new_key
+                                && let Err(e) = db.put(self.txn, &new_key, &node.id)
+                            {
+                                vec.push(Err(GraphError::from(e)));
+                            }
+                        }
+
+                        // reindexes the node's text when any of its text fields is updated
+                        if let (Some(bm25), Some(props)) = (&storage.bm25, &props)
+                            && bm25.indexes_any(
+                                &old_node.label,
+                                props.iter().map(|(key, _)| key.as_str()),
+                            )
+                        {
+                            let fields = old_node.properties.as_ref().and_then(|properties| {
+                                bm25.doc_fields(&old_node.label, properties)
+                            });
+                            let result =
+                                bm25.delete_doc(self.txn, node.id).and_then(|_| match fields {
+                                    Some(fields) => bm25.insert_fields(self.txn, node.id, fields),
+                                    None => Ok(()),
+                                });
+                            if let Err(e) = result {
+                                vec.push(Err(e));
+                            }
+                        }
+
                         match old_node.encode_node() {
                             Ok(serialized) => {
                                 match storage.nodes_db.put(
@@ -125,6 +156,18 @@ impl<'scope, 'env, I: Iterator<Item = Result<TraversalValue, GraphError>>> Updat
                 Ok(TraversalValue::Edge(edge)) => match storage.get_edge(self.txn, &edge.id) {
                     Ok(old_edge) => {
                         let mut old_edge = old_edge.clone();
+                        // indices over any of the updated fields, with the edge's key before the update
+                        let indices =
+                            match (&props, storage.edge_secondary_indices.get(&old_edge.label)) {
+                                (Some(props), Some(indices)) => indices
+                                    .iter()
+                                    .filter(|(field, _)| props.iter().any(|(key, _)| key == *field))
+                                    .map(|(field, db)| {
+                                        (field, db, encode_item_index_key(&old_edge, field).ok())
+                                    })
+                                    .collect::<Vec<_>>(),
+                                _ => Vec::new(),
+                            };
                         if let Some(mut properties) = old_edge.properties.clone()
                             && let Some(ref props) = props {
                                 for (k, v) in props.iter() {
@@ -132,9 +175,21 @@ impl<'scope, 'env, I: Iterator<Item = Result<TraversalValue, GraphError>>> Updat
                                 }
                                 old_edge.properties = Some(properties);
                         }
+                        for (field, db, old_key) in indices {
+                            if let Some(old_key) = old_key
+                                && let Err(e) = db.delete_one_duplicate(self.txn, &old_key, &edge.id)
+                            {
+                                vec.push(Err(GraphError::from(e)));
+                            }
+                            if let Ok(new_key) = encode_item_index_key(&old_edge, field)
+                                && let Err(e) = db.put(self.txn, &new_key, &edge.id)
+                            {
+                                vec.push(Err(GraphError::from(e)));
+                            }
+                        }
                         match old_edge.encode_edge() {
                             Ok(serialized) => {
-                                match storage.nodes_db.put(
+                                match storage.edges_db.put(
                                     self.txn,
                                     HelixGraphStorage::edge_key(&edge.id),
                                     &serialized,
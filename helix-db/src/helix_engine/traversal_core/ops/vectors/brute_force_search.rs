@@ -5,7 +5,6 @@ use crate::{
     helix_engine::{
         traversal_core::{traversal_iter::RoTraversalIterator, traversal_value::TraversalValue},
         types::GraphError,
-        vector_core::vector_distance::cosine_similarity,
     },
     protocol::value::Value,
     utils::filterable::Filterable,
@@ -38,6 +37,13 @@ pub trait BruteForceSearchVAdapter<'a>:
     where
         K: TryInto<usize>,
         K::Error: std::fmt::Debug;
+
+    /// Keeps the vectors within `radius` of the query, closest first
+    fn brute_force_search_v_radius(
+        self,
+        query: &[f64],
+        radius: f64,
+    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>;
 }
 
 impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>> + 'a> BruteForceSearchVAdapter<'a>
@@ -59,13 +65,14 @@ impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>> + 'a> BruteForce
             .inner
             .filter_map(|v| match v {
                 Ok(TraversalValue::Vector(mut v)) => {
-                    let d = cosine_similarity(v.get_data(), query).unwrap();
+                    let metric = storage.vectors.metric(v.label());
+                    let d = metric.distance(v.get_data(), query).unwrap();
                     v.set_distance(d);
                     Some(v)
                 }
                 _ => None,
             })
-            .sorted_by(|v1, v2| v1.partial_cmp(v2).unwrap())
+            .sorted_by(|v1, v2| v1.get_distance().total_cmp(&v2.get_distance()))
             .take(k.try_into().unwrap())
             .filter_map(move |mut item| {
                 item.properties = match storage
@@ -105,4 +112,78 @@ impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>> + 'a> BruteForce
             txn: self.txn,
         }
     }
+
+    fn brute_force_search_v_radius(
+        self,
+        query: &[f64],
+        radius: f64,
+    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>> {
+        let storage = Arc::clone(&self.storage);
+        let txn = self.txn;
+
+        // a query the vectors can't be compared with, or a vector whose data can't be read,
+        // fails the step rather than being skipped
+        let vectors = self
+            .inner
+            .filter_map(|v| match v {
+                Ok(TraversalValue::Vector(mut v)) => {
+                    let metric = storage.vectors.metric(v.label());
+                    Some(
+                        metric
+                            .distance(v.get_data(), query)
+                            .map(|d| {
+                                v.set_distance(d);
+                                v
+                            })
+                            .map_err(GraphError::from),
+                    )
+                }
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            })
+            .filter_ok(|v| v.get_distance() <= radius)
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(|vectors| {
+                vectors
+                    .into_iter()
+                    .sorted_by(|v1, v2| v1.get_distance().total_cmp(&v2.get_distance()))
+                    .filter_map(|mut item| {
+                        item.properties = match storage
+                            .vectors
+                            .vector_data_db
+                            .get(txn, &item.get_id().to_be_bytes())
+                        {
+                            Ok(Some(bytes)) => match bincode::deserialize(bytes) {
+                                Ok(properties) => Some(properties),
+                                Err(e) => return Some(Err(GraphError::from(e))),
+                            },
+                            Ok(None) => None,
+                            Err(e) => return Some(Err(GraphError::from(e))),
+                        };
+
+                        match item.check_property("is_deleted") {
+                            Ok(is_deleted) if *is_deleted.as_ref() == Value::Boolean(false) => {
+                                Some(Ok(item))
+                            }
+                            _ => None,
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            });
+
+        let iter = match vectors {
+            Ok(vectors) => vectors
+                .into_iter()
+                .map(|v| Ok(TraversalValue::Vector(v)))
+                .collect::<Vec<_>>()
+                .into_iter(),
+            Err(e) => vec![Err(e)].into_iter(),
+        };
+
+        RoTraversalIterator {
+            inner: iter.into_iter(),
+            storage: self.storage,
+            txn: self.txn,
+        }
+    }
 }
@@ -1,10 +1,12 @@
-use heed3::RoTxn;
+use heed3::{RoTxn, RwTxn};
 
 use crate::{
     helix_engine::{
+        bm25::bm25::BM25,
+        storage_core::HelixGraphStorage,
         traversal_core::{traversal_iter::RwTraversalIterator, traversal_value::TraversalValue},
         types::GraphError,
-        vector_core::{hnsw::HNSW, vector::HVector},
+        vector_core::{hnsw::HNSW, sparse_vector::SparseVector, vector::HVector},
     },
     protocol::value::Value,
 };
@@ -39,6 +41,27 @@ pub trait InsertVAdapter<'a, 'b>: Iterator<Item = Result<TraversalValue, GraphEr
     ) -> RwTraversalIterator<'a, 'b, impl Iterator<Item = Result<TraversalValue, GraphError>>>
     where
         F: Fn(&HVector, &RoTxn) -> bool;
+
+    /// Inserts a multi-vector document of the label, each of its vectors indexed on its own
+    fn insert_multi_v<F>(
+        self,
+        vectors: &[Vec<f64>],
+        label: &str,
+        fields: Option<Vec<(String, Value)>>,
+    ) -> RwTraversalIterator<'a, 'b, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool;
+
+    /// Inserts a sparse vector of the label from its non-zero dimensions and their weights
+    fn insert_sparse_v<F>(
+        self,
+        indices: &[u32],
+        weights: &[f64],
+        label: &str,
+        fields: Option<Vec<(String, Value)>>,
+    ) -> RwTraversalIterator<'a, 'b, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool;
 }
 
 impl<'a, 'b, I: Iterator<Item = Result<TraversalValue, GraphError>>> InsertVAdapter<'a, 'b>
@@ -53,21 +76,15 @@ impl<'a, 'b, I: Iterator<Item = Result<TraversalValue, GraphError>>> InsertVAdap
     where
         F: Fn(&HVector, &RoTxn) -> bool,
     {
-        let fields = match fields {
-            Some(mut fields) => {
-                fields.push((String::from("label"), Value::String(label.to_string())));
-                fields.push((String::from("is_deleted"), Value::Boolean(false)));
-                Some(fields)
-            }
-            None => Some(vec![
-                (String::from("label"), Value::String(label.to_string())),
-                (String::from("is_deleted"), Value::Boolean(false)),
-            ]),
-        };
-        let vector = self.storage.vectors.insert::<F>(self.txn, query, fields);
+        let terms = bm25_terms(&self.storage, label, fields.as_deref());
+        let vector = self
+            .storage
+            .vectors
+            .insert::<F>(self.txn, query, with_label(label, fields));
 
         let result = match vector {
-            Ok(vector) => Ok(TraversalValue::Vector(vector)),
+            Ok(vector) => index_bm25(&self.storage, self.txn, &vector, terms)
+                .map(|_| TraversalValue::Vector(vector)),
             Err(e) => Err(GraphError::from(e)),
         };
 
@@ -105,4 +122,101 @@ impl<'a, 'b, I: Iterator<Item = Result<TraversalValue, GraphError>>> InsertVAdap
             txn,
         }
     }
+
+    fn insert_multi_v<F>(
+        self,
+        vectors: &[Vec<f64>],
+        label: &str,
+        fields: Option<Vec<(String, Value)>>,
+    ) -> RwTraversalIterator<'a, 'b, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+    {
+        let terms = bm25_terms(&self.storage, label, fields.as_deref());
+        let document =
+            self.storage
+                .vectors
+                .insert_multi::<F>(self.txn, vectors, with_label(label, fields));
+
+        let result = match document {
+            Ok(document) => index_bm25(&self.storage, self.txn, &document, terms)
+                .map(|_| TraversalValue::Vector(document)),
+            Err(e) => Err(GraphError::from(e)),
+        };
+
+        RwTraversalIterator {
+            inner: std::iter::once(result),
+            storage: self.storage,
+            txn: self.txn,
+        }
+    }
+
+    fn insert_sparse_v<F>(
+        self,
+        indices: &[u32],
+        weights: &[f64],
+        label: &str,
+        fields: Option<Vec<(String, Value)>>,
+    ) -> RwTraversalIterator<'a, 'b, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+    {
+        let terms = bm25_terms(&self.storage, label, fields.as_deref());
+        let vector = SparseVector::new(indices, weights).and_then(|vector| {
+            self.storage
+                .sparse
+                .insert(self.txn, &vector, with_label(label, fields))
+        });
+
+        let result = match vector {
+            Ok(vector) => index_bm25(&self.storage, self.txn, &vector, terms)
+                .map(|_| TraversalValue::Vector(vector)),
+            Err(e) => Err(GraphError::from(e)),
+        };
+
+        RwTraversalIterator {
+            inner: std::iter::once(result),
+            storage: self.storage,
+            txn: self.txn,
+        }
+    }
+}
+
+fn with_label(label: &str, fields: Option<Vec<(String, Value)>>) -> Option<Vec<(String, Value)>> {
+    match fields {
+        Some(mut fields) => {
+            fields.push((String::from("label"), Value::String(label.to_string())));
+            fields.push((String::from("is_deleted"), Value::Boolean(false)));
+            Some(fields)
+        }
+        None => Some(vec![
+            (String::from("label"), Value::String(label.to_string())),
+            (String::from("is_deleted"), Value::Boolean(false)),
+        ]),
+    }
+}
+
+/// The terms a vector is indexed under in bm25, like a node those of its text fields
+fn bm25_terms(
+    storage: &HelixGraphStorage,
+    label: &str,
+    fields: Option<&[(String, Value)]>,
+) -> Option<Vec<Vec<String>>> {
+    storage
+        .bm25
+        .as_ref()
+        .zip(fields)
+        .and_then(|(bm25, fields)| bm25.doc_fields(label, fields))
+}
+
+fn index_bm25(
+    storage: &HelixGraphStorage,
+    txn: &mut RwTxn,
+    vector: &HVector,
+    terms: Option<Vec<Vec<String>>>,
+) -> Result<(), GraphError> {
+    match (&storage.bm25, terms) {
+        (Some(bm25), Some(terms)) => bm25.insert_fields(txn, vector.id, terms),
+        _ => Ok(()),
+    }
 }
@@ -3,10 +3,12 @@ use heed3::RoTxn;
 use crate::helix_engine::{
     traversal_core::{traversal_iter::RoTraversalIterator, traversal_value::TraversalValue},
     types::{GraphError, VectorError},
-    vector_core::{hnsw::HNSW, vector::HVector},
+    vector_core::{
+        hnsw::HNSW, sparse_vector::SparseVector, vector::HVector, vector_core::SearchParams,
+    },
 };
 use helix_macros::debug_trace;
-use std::iter::once;
+use std::{iter::once, thread};
 
 pub struct SearchV<I: Iterator<Item = Result<TraversalValue, GraphError>>> {
     iter: I,
@@ -29,11 +31,76 @@ pub trait SearchVAdapter<'a>: Iterator<Item = Result<TraversalValue, GraphError>
         k: K,
         label: &str,
         filter: Option<&[F]>,
+        params: SearchParams,
     ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>
     where
         F: Fn(&HVector, &RoTxn) -> bool,
         K: TryInto<usize>,
         K::Error: std::fmt::Debug;
+
+    /// Searches for every vector of the label within `radius` of the query, closest first
+    fn search_v_radius<F>(
+        self,
+        query: &[f64],
+        radius: f64,
+        label: &str,
+        filter: Option<&[F]>,
+        params: SearchParams,
+    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool;
+
+    /// Searches for the k multi-vector documents of the label closest to the query vectors
+    /// under MaxSim, returning the documents rather than their individual vectors
+    fn search_v_multi<F, K>(
+        self,
+        queries: &[Vec<f64>],
+        k: K,
+        label: &str,
+        filter: Option<&[F]>,
+        params: SearchParams,
+    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+        K: TryInto<usize>,
+        K::Error: std::fmt::Debug;
+
+    /// Searches for the k sparse vectors of the label with the largest dot product with the
+    /// query, given as its non-zero dimensions and their weights
+    fn search_sparse_v<F, K>(
+        self,
+        indices: &[u32],
+        weights: &[f64],
+        k: K,
+        label: &str,
+        filter: Option<&[F]>,
+    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+        K: TryInto<usize>,
+        K::Error: std::fmt::Debug;
+
+    /// Searches for the k nearest vectors of each query, returning the results of each query
+    /// in the order of the queries.
+    ///
+    /// The queries are searched through the traversal's read transaction, or split across
+    /// `threads` threads which each open their own. A thread only keeps its results if its
+    /// transaction reads the same snapshot, otherwise its queries are searched again through
+    /// the traversal's, so every query sees the same data either way.
+    #[allow(clippy::too_many_arguments)]
+    fn search_v_batch<F, K>(
+        self,
+        queries: &[Vec<f64>],
+        k: K,
+        label: &str,
+        filter: Option<&[F]>,
+        params: SearchParams,
+        threads: usize,
+    ) -> Result<Vec<Vec<TraversalValue>>, GraphError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool + Sync,
+        K: TryInto<usize>,
+        K::Error: std::fmt::Debug;
 }
 
 impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>> + 'a> SearchVAdapter<'a>
@@ -45,6 +112,7 @@ impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>> + 'a> SearchVAda
         k: K,
         label: &str,
         filter: Option<&[F]>,
+        params: SearchParams,
     ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>
     where
         F: Fn(&HVector, &RoTxn) -> bool,
@@ -54,48 +122,101 @@ impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>> + 'a> SearchVAda
         let vectors =
             self.storage
                 .vectors
-                .search(self.txn, query, k.try_into().unwrap(), label, filter, false);
+                .search(self.txn, query, k.try_into().unwrap(), label, filter, true, params);
 
-        let iter = match vectors {
-            Ok(vectors) => vectors
-                .into_iter()
-                .map(|vector| Ok::<TraversalValue, GraphError>(TraversalValue::Vector(vector)))
-                .collect::<Vec<_>>()
-                .into_iter(),
-            Err(VectorError::VectorNotFound(id)) => {
-                let error = GraphError::VectorError(format!("vector not found for id {id}"));
-                once(Err(error)).collect::<Vec<_>>().into_iter()
-            }
-            Err(VectorError::InvalidVectorData) => {
-                let error = GraphError::VectorError("invalid vector data".to_string());
-                once(Err(error)).collect::<Vec<_>>().into_iter()
-            }
-            Err(VectorError::EntryPointNotFound) => {
-                let error =
-                    GraphError::VectorError("no entry point found for hnsw index".to_string());
-                once(Err(error)).collect::<Vec<_>>().into_iter()
-            }
-            Err(VectorError::ConversionError(e)) => {
-                let error = GraphError::VectorError(format!("conversion error: {e}"));
-                once(Err(error)).collect::<Vec<_>>().into_iter()
-            }
-            Err(VectorError::VectorCoreError(e)) => {
-                let error = GraphError::VectorError(format!("vector core error: {e}"));
-                once(Err(error)).collect::<Vec<_>>().into_iter()
-            }
-            Err(VectorError::InvalidVectorLength) => {
-                let error = GraphError::VectorError("invalid vector dimensions!".to_string());
-                once(Err(error)).collect::<Vec<_>>().into_iter()
-            }
-            Err(id) => {
-                let error = GraphError::VectorError(format!("vector already deleted for id {id}"));
-                once(Err(error)).collect::<Vec<_>>().into_iter()
-            }
-            .collect::<Vec<_>>()
-            .into_iter(),
+        let iter = SearchV {
+            iter: search_results(vectors),
+        };
+
+        RoTraversalIterator {
+            inner: iter,
+            storage: self.storage,
+            txn: self.txn,
+        }
+    }
+
+    fn search_v_radius<F>(
+        self,
+        query: &[f64],
+        radius: f64,
+        label: &str,
+        filter: Option<&[F]>,
+        params: SearchParams,
+    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+    {
+        let vectors = self
+            .storage
+            .vectors
+            .search_radius(self.txn, query, radius, label, filter, params);
+
+        let iter = SearchV {
+            iter: search_results(vectors),
+        };
+
+        RoTraversalIterator {
+            inner: iter,
+            storage: self.storage,
+            txn: self.txn,
+        }
+    }
+
+    fn search_v_multi<F, K>(
+        self,
+        queries: &[Vec<f64>],
+        k: K,
+        label: &str,
+        filter: Option<&[F]>,
+        params: SearchParams,
+    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+        K: TryInto<usize>,
+        K::Error: std::fmt::Debug,
+    {
+        let documents = self.storage.vectors.search_multi(
+            self.txn,
+            queries,
+            k.try_into().unwrap(),
+            label,
+            filter,
+            params,
+        );
+
+        let iter = SearchV {
+            iter: search_results(documents),
         };
 
-        let iter = SearchV { iter };
+        RoTraversalIterator {
+            inner: iter,
+            storage: self.storage,
+            txn: self.txn,
+        }
+    }
+
+    fn search_sparse_v<F, K>(
+        self,
+        indices: &[u32],
+        weights: &[f64],
+        k: K,
+        label: &str,
+        filter: Option<&[F]>,
+    ) -> RoTraversalIterator<'a, impl Iterator<Item = Result<TraversalValue, GraphError>>>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+        K: TryInto<usize>,
+        K::Error: std::fmt::Debug,
+    {
+        let vectors = SparseVector::new(indices, weights).and_then(|query| {
+            self.storage
+                .sparse
+                .search(self.txn, &query, k.try_into().unwrap(), label, filter)
+        });
+
+        let iter = SearchV {
+            iter: search_results(vectors),
+        };
 
         RoTraversalIterator {
             inner: iter,
@@ -103,5 +224,109 @@ impl<'a, I: Iterator<Item = Result<TraversalValue, GraphError>> + 'a> SearchVAda
             txn: self.txn,
         }
     }
+
+    fn search_v_batch<F, K>(
+        self,
+        queries: &[Vec<f64>],
+        k: K,
+        label: &str,
+        filter: Option<&[F]>,
+        params: SearchParams,
+        threads: usize,
+    ) -> Result<Vec<Vec<TraversalValue>>, GraphError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool + Sync,
+        K: TryInto<usize>,
+        K::Error: std::fmt::Debug,
+    {
+        let k = k.try_into().unwrap();
+        let storage = &self.storage;
+        let search = |txn: &RoTxn, queries: &[Vec<f64>]| {
+            queries
+                .iter()
+                .map(|query| {
+                    storage
+                        .vectors
+                        .search(txn, query, k, label, filter, true, params)
+                        .map(|vectors| vectors.into_iter().map(TraversalValue::Vector).collect())
+                        .map_err(search_error)
+                })
+                .collect::<Result<Vec<Vec<TraversalValue>>, GraphError>>()
+        };
+
+        let threads = threads.clamp(1, queries.len().max(1));
+        if threads == 1 {
+            return search(self.txn, queries);
+        }
+
+        let snapshot = self.txn.id();
+        let chunk_size = queries.len().div_ceil(threads);
+        let found = thread::scope(|scope| {
+            let handles = queries
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(|| {
+                        let txn = storage.graph_env.read_txn()?;
+                        match txn.id() == snapshot {
+                            true => search(&txn, chunk).map(Some),
+                            // a write was committed since the traversal's transaction was opened
+                            false => Ok(None),
+                        }
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<Result<Option<_>, GraphError>>>()
+        });
+
+        let mut results = Vec::with_capacity(queries.len());
+        for (chunk, found) in queries.chunks(chunk_size).zip(found) {
+            match found? {
+                Some(found) => results.extend(found),
+                None => results.extend(search(self.txn, chunk)?),
+            }
+        }
+        Ok(results)
+    }
+}
+
+/// Maps the vectors a search found, or its error, to traversal values
+fn search_results(
+    vectors: Result<Vec<HVector>, VectorError>,
+) -> impl Iterator<Item = Result<TraversalValue, GraphError>> {
+    match vectors {
+        Ok(vectors) => vectors
+            .into_iter()
+            .map(|vector| Ok::<TraversalValue, GraphError>(TraversalValue::Vector(vector)))
+            .collect::<Vec<_>>()
+            .into_iter(),
+        Err(e) => once(Err(search_error(e))).collect::<Vec<_>>().into_iter(),
+    }
 }
 
+fn search_error(error: VectorError) -> GraphError {
+    match error {
+        VectorError::VectorNotFound(id) => {
+            GraphError::VectorError(format!("vector not found for id {id}"))
+        }
+        VectorError::InvalidVectorData => {
+            GraphError::VectorError("invalid vector data".to_string())
+        }
+        VectorError::EntryPointNotFound => {
+            GraphError::VectorError("no entry point found for hnsw index".to_string())
+        }
+        VectorError::ConversionError(e) => {
+            GraphError::VectorError(format!("conversion error: {e}"))
+        }
+        VectorError::VectorCoreError(e) => {
+            GraphError::VectorError(format!("vector core error: {e}"))
+        }
+        VectorError::InvalidVectorLength => {
+            GraphError::VectorError("invalid vector dimensions!".to_string())
+        }
+        e @ VectorError::InvalidVectorDimension { .. } => GraphError::VectorError(e.to_string()),
+        id => GraphError::VectorError(format!("vector already deleted for id {id}")),
+    }
+}
@@ -135,6 +135,24 @@ impl<'scope, 'env, I: Iterator<Item = Result<TraversalValue, GraphError>>>
             None => TraversalValue::Empty,
         }
     }
+
+    /// Like `collect_to` but returns the first error instead of skipping it.
+    pub fn try_collect_to<B: FromIterator<TraversalValue>>(self) -> Result<B, GraphError> {
+        self.inner.collect()
+    }
+
+    /// Like `collect_to_obj` but returns the first error instead of skipping it,
+    /// so a failed write (e.g. a unique constraint violation) aborts the transaction.
+    pub fn try_collect_to_obj(self) -> Result<TraversalValue, GraphError> {
+        let mut first = None;
+        for item in self.inner {
+            let val = item?;
+            if first.is_none() {
+                first = Some(val);
+            }
+        }
+        Ok(first.unwrap_or(TraversalValue::Empty))
+    }
 }
 // pub trait TraversalIteratorMut<'a> {
 //     type Inner: Iterator<Item = Result<TraversalValue, GraphError>>;
@@ -29,6 +29,12 @@ pub enum GraphError {
     EmbeddingError(String),
     ParamNotFound(&'static str),
     IoNeeded(IoContFn),
+    /// A write would give a second node of `label` the value of `field` held by node `id`.
+    UniqueConstraintViolation {
+        label: String,
+        field: String,
+        id: u128,
+    },
 }
 
 impl std::error::Error for GraphError {}
@@ -65,6 +71,11 @@ impl fmt::Display for GraphError {
             GraphError::IoNeeded(_) => {
                 write!(f, "Asyncronous IO is needed to complete the DB operation")
             }
+            GraphError::UniqueConstraintViolation { label, field, id } => write!(
+                f,
+                "Unique constraint violation: {label}.{field} is already held by node {}",
+                uuid::Uuid::from_u128(*id)
+            ),
         }
     }
 }
@@ -150,6 +161,11 @@ pub enum VectorError {
     ConversionError(String),
     VectorCoreError(String),
     VectorAlreadyDeleted(String),
+    InvalidVectorDimension {
+        label: String,
+        expected: usize,
+        found: usize,
+    },
 }
 
 impl std::error::Error for VectorError {}
@@ -164,6 +180,14 @@ impl fmt::Display for VectorError {
             VectorError::ConversionError(msg) => write!(f, "Conversion error: {msg}"),
             VectorError::VectorCoreError(msg) => write!(f, "Vector core error: {msg}"),
             VectorError::VectorAlreadyDeleted(id) => write!(f, "Vector already deleted: {id}"),
+            VectorError::InvalidVectorDimension {
+                label,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Vectors of type {label} have {expected} dimensions, but this one has {found}"
+            ),
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::{helix_engine::types::VectorError, protocol::value::Value};
-use crate::helix_engine::vector_core::vector::HVector;
+use crate::helix_engine::vector_core::{vector::HVector, vector_core::SearchParams};
 use heed3::{RoTxn, RwTxn};
 
 pub trait HNSW
@@ -11,10 +11,12 @@ pub trait HNSW
     /// * `txn` - The transaction to use
     /// * `query` - The query vector
     /// * `k` - The number of nearest neighbors to search for
+    /// * `params` - Overrides of the vector type's search config for this search
     ///
     /// # Returns
     ///
     /// A vector of tuples containing the id and distance of the nearest neighbors
+    #[allow(clippy::too_many_arguments)]
     fn search<F>(
         &self,
         txn: &RoTxn,
@@ -23,6 +25,57 @@ pub trait HNSW
         label: &str,
         filter: Option<&[F]>,
         should_trickle: bool,
+        params: SearchParams,
+    ) -> Result<Vec<HVector>, VectorError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool;
+
+    /// Search for all the vectors within a distance of a query vector
+    ///
+    /// # Arguments
+    ///
+    /// * `txn` - The transaction to use
+    /// * `query` - The query vector
+    /// * `radius` - The largest distance to the query, under the vector type's metric
+    /// * `params` - Overrides of the vector type's search config for this search
+    ///
+    /// # Returns
+    ///
+    /// The vectors within the radius, closest first
+    fn search_radius<F>(
+        &self,
+        txn: &RoTxn,
+        query: &[f64],
+        radius: f64,
+        label: &str,
+        filter: Option<&[F]>,
+        params: SearchParams,
+    ) -> Result<Vec<HVector>, VectorError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool;
+
+    /// Search for the k multi-vector documents closest to a set of query vectors under MaxSim
+    ///
+    /// # Arguments
+    ///
+    /// * `txn` - The transaction to use
+    /// * `queries` - The query vectors, e.g. the token embeddings of a query
+    /// * `k` - The number of documents to search for
+    /// * `params` - Overrides of the vector type's search config, `candidates` being
+    ///   the number of vectors gathered per query vector
+    ///
+    /// # Returns
+    ///
+    /// The documents, closest first, with the sum of the distances of each query vector
+    /// to its closest vector of the document as their distance
+    fn search_multi<F>(
+        &self,
+        txn: &RoTxn,
+        queries: &[Vec<f64>],
+        k: usize,
+        label: &str,
+        filter: Option<&[F]>,
+        params: SearchParams,
     ) -> Result<Vec<HVector>, VectorError>
     where
         F: Fn(&HVector, &RoTxn) -> bool;
@@ -46,6 +99,26 @@ pub trait HNSW
     where
         F: Fn(&HVector, &RoTxn) -> bool;
 
+    /// Insert a document of several vectors into the index, each vector linked on its own
+    ///
+    /// # Arguments
+    ///
+    /// * `txn` - The transaction to use
+    /// * `vectors` - The vectors of the document
+    /// * `fields` - The properties of the document
+    ///
+    /// # Returns
+    ///
+    /// An HVector of the document, without data of its own
+    fn insert_multi<F>(
+        &self,
+        txn: &mut RwTxn,
+        vectors: &[Vec<f64>],
+        fields: Option<Vec<(String, Value)>>,
+    ) -> Result<HVector, VectorError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool;
+
     /// Get all vectors from the index at a specific level
     ///
     /// # Arguments
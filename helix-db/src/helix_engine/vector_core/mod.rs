@@ -1,7 +1,10 @@
 pub mod hnsw;
+pub mod sparse_core;
+pub mod sparse_vector;
 pub mod utils;
 pub mod vector;
 pub mod vector_core;
 pub mod vector_distance;
+pub mod vector_encoding;
 
 
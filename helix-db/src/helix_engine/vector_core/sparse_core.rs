@@ -0,0 +1,220 @@
+use crate::{
+    debug_println,
+    helix_engine::{
+        types::VectorError,
+        vector_core::{sparse_vector::SparseVector, utils::VectorFilter, vector::HVector},
+    },
+    protocol::value::Value,
+    utils::{filterable::Filterable, id::v6_uuid},
+};
+use heed3::{
+    Database, DatabaseFlags, Env, RoTxn, RwTxn,
+    byteorder::BE,
+    types::{Bytes, U128},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap};
+
+const DB_SPARSE_INVERTED_INDEX: &str = "sparse_inverted_index"; // label and dimension -> list of (vector id, weight)
+const DB_SPARSE_VECTORS: &str = "sparse_vectors"; // vector id -> sparse vector
+const DB_SPARSE_VECTOR_DATA: &str = "sparse_vector_data"; // vector id -> properties
+const DEFAULT_LABEL: &str = "vector"; // label of vectors inserted without one, as in `HVector::label`
+
+/// For inverted index
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SparsePosting {
+    pub id: u128,
+    pub weight: f64,
+}
+
+/// Store of the sparse vector types, searched by dot product through an inverted index
+/// from each dimension of a label to the vectors with a weight on it.
+///
+/// Sparse vectors are read as `HVector`s without data, with `1 - dot product` as their distance
+/// like the `inner_product` metric of dense vectors, so smaller distances are still closer.
+pub struct SparseCore {
+    pub inverted_index_db: Database<Bytes, Bytes>,
+    pub vectors_db: Database<U128<BE>, Bytes>,
+    pub vector_data_db: Database<Bytes, Bytes>,
+}
+
+impl SparseCore {
+    /// Names of the lmdb dbs (tables) the sparse vectors are stored in
+    pub const DB_NAMES: [&str; 3] = [
+        DB_SPARSE_INVERTED_INDEX,
+        DB_SPARSE_VECTORS,
+        DB_SPARSE_VECTOR_DATA,
+    ];
+
+    pub fn new(env: &Env, txn: &mut RwTxn) -> Result<Self, VectorError> {
+        let inverted_index_db = env
+            .database_options()
+            .types::<Bytes, Bytes>()
+            .flags(DatabaseFlags::DUP_SORT)
+            .name(DB_SPARSE_INVERTED_INDEX)
+            .create(txn)?;
+        let vectors_db = env.create_database(txn, Some(DB_SPARSE_VECTORS))?;
+        let vector_data_db = env.create_database(txn, Some(DB_SPARSE_VECTOR_DATA))?;
+
+        Ok(Self {
+            inverted_index_db,
+            vectors_db,
+            vector_data_db,
+        })
+    }
+
+    #[inline(always)]
+    fn posting_key(label: &str, dimension: u32) -> Vec<u8> {
+        [label.as_bytes(), &[0], &dimension.to_be_bytes()].concat()
+    }
+
+    fn get_properties(
+        &self,
+        txn: &RoTxn,
+        id: u128,
+    ) -> Result<Option<HashMap<String, Value>>, VectorError> {
+        match self.vector_data_db.get(txn, &id.to_be_bytes())? {
+            Some(bytes) => Ok(Some(bincode::deserialize(bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether `id` is a sparse vector
+    pub fn contains(&self, txn: &RoTxn, id: u128) -> Result<bool, VectorError> {
+        Ok(self.vectors_db.get(txn, &id)?.is_some())
+    }
+
+    /// Reads the weights of a sparse vector
+    pub fn get_sparse(&self, txn: &RoTxn, id: u128) -> Result<SparseVector, VectorError> {
+        match self.vectors_db.get(txn, &id)? {
+            Some(bytes) => Ok(bincode::deserialize(bytes)?),
+            None => Err(VectorError::VectorNotFound(id.to_string())),
+        }
+    }
+
+    /// Reads a sparse vector as an `HVector`, with its properties if `with_data`
+    pub fn get(&self, txn: &RoTxn, id: u128, with_data: bool) -> Result<HVector, VectorError> {
+        if !self.contains(txn, id)? {
+            return Err(VectorError::VectorNotFound(id.to_string()));
+        }
+        Ok(HVector {
+            id,
+            level: 0,
+            distance: None,
+            data: Vec::new(),
+            properties: match with_data {
+                true => self.get_properties(txn, id)?,
+                false => None,
+            },
+            encoded: None,
+        })
+    }
+
+    /// Inserts a sparse vector under the label in its fields, adding it to the posting list
+    /// of each of its dimensions.
+    ///
+    /// The label is always stored with the vector, as its postings are found by it,
+    /// `DEFAULT_LABEL` when the fields have none.
+    pub fn insert(
+        &self,
+        txn: &mut RwTxn,
+        vector: &SparseVector,
+        fields: Option<Vec<(String, Value)>>,
+    ) -> Result<HVector, VectorError> {
+        let mut fields = fields.unwrap_or_default();
+        if !fields.iter().any(|(key, _)| key == "label") {
+            fields.push(("label".to_string(), Value::String(DEFAULT_LABEL.to_string())));
+        }
+        let label = match fields
+            .iter()
+            .find_map(|(key, value)| (key == "label").then_some(value))
+        {
+            Some(Value::String(label)) => label.as_str(),
+            _ => DEFAULT_LABEL,
+        };
+
+        let id = v6_uuid();
+        for &(dimension, weight) in &vector.entries {
+            let posting = bincode::serialize(&SparsePosting { id, weight })?;
+            self.inverted_index_db
+                .put(txn, &Self::posting_key(label, dimension), &posting)?;
+        }
+        self.vectors_db
+            .put(txn, &id, &bincode::serialize(vector)?)?;
+        self.vector_data_db
+            .put(txn, &id.to_be_bytes(), &bincode::serialize(&fields)?)?;
+
+        debug_println!(
+            "sparse vector inserted with id {} and {} dimensions",
+            id,
+            vector.len()
+        );
+        self.get(txn, id, false)
+    }
+
+    /// Deletes a sparse vector and its postings
+    pub fn delete(&self, txn: &mut RwTxn, id: u128) -> Result<(), VectorError> {
+        let vector = self.get_sparse(txn, id)?;
+        let label = self.get(txn, id, true)?.label().to_string();
+
+        for (dimension, weight) in vector.entries {
+            let posting = bincode::serialize(&SparsePosting { id, weight })?;
+            self.inverted_index_db.delete_one_duplicate(
+                txn,
+                &Self::posting_key(&label, dimension),
+                &posting,
+            )?;
+        }
+        self.vectors_db.delete(txn, &id)?;
+        self.vector_data_db.delete(txn, &id.to_be_bytes())?;
+
+        debug_println!("sparse vector deleted with id {}", id);
+        Ok(())
+    }
+
+    /// Search for the k vectors of the label with the largest dot product with the query,
+    /// reading only the posting lists of the query's dimensions
+    pub fn search<F>(
+        &self,
+        txn: &RoTxn,
+        query: &SparseVector,
+        k: usize,
+        label: &str,
+        filter: Option<&[F]>,
+    ) -> Result<Vec<HVector>, VectorError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+    {
+        let mut scores: HashMap<u128, f64> = HashMap::new();
+        for &(dimension, weight) in &query.entries {
+            let Some(postings) = self
+                .inverted_index_db
+                .get_duplicates(txn, &Self::posting_key(label, dimension))?
+            else {
+                continue;
+            };
+            for result in postings {
+                let (_, bytes) = result?;
+                let posting: SparsePosting = bincode::deserialize(bytes)?;
+                *scores.entry(posting.id).or_insert(0.0) += weight * posting.weight;
+            }
+        }
+
+        let mut candidates: BinaryHeap<HVector> = scores
+            .into_iter()
+            .map(|(id, score)| HVector {
+                id,
+                level: 0,
+                distance: Some(1.0 - score),
+                data: Vec::new(),
+                properties: None,
+                encoded: None,
+            })
+            .collect();
+        let results =
+            candidates.to_vec_with_filter::<F, true>(k, filter, label, txn, self.vector_data_db)?;
+
+        debug_println!("sparse vector search found {} results", results.len());
+        Ok(results)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::helix_engine::types::VectorError;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// A sparse vector, e.g. a SPLADE or BM42 embedding, as the weights of its non-zero dimensions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SparseVector {
+    /// (dimension, weight) pairs, sorted by dimension
+    pub entries: Vec<(u32, f64)>,
+}
+
+impl SparseVector {
+    /// Builds a sparse vector from its dimensions and their weights, given in the same order.
+    ///
+    /// Dimensions with a weight of zero are left out, and no dimension can be given twice.
+    pub fn new(indices: &[u32], weights: &[f64]) -> Result<Self, VectorError> {
+        if indices.len() != weights.len() {
+            return Err(VectorError::VectorCoreError(format!(
+                "sparse vector has {} indices but {} weights",
+                indices.len(),
+                weights.len()
+            )));
+        }
+        let mut entries: Vec<(u32, f64)> = indices
+            .iter()
+            .copied()
+            .zip(weights.iter().copied())
+            .filter(|&(_, weight)| weight != 0.0)
+            .collect();
+        entries.sort_unstable_by_key(|&(index, _)| index);
+        if let Some(pair) = entries.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(VectorError::VectorCoreError(format!(
+                "sparse vector has index {} more than once",
+                pair[0].0
+            )));
+        }
+        Ok(Self { entries })
+    }
+
+    /// Number of non-zero dimensions
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Dot product with another sparse vector, over the dimensions both have
+    pub fn dot(&self, other: &SparseVector) -> f64 {
+        let (mut i, mut j) = (0, 0);
+        let mut sum = 0.0;
+        while i < self.entries.len() && j < other.entries.len() {
+            let ((a, x), (b, y)) = (self.entries[i], other.entries[j]);
+            match a.cmp(&b) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    sum += x * y;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        sum
+    }
+}
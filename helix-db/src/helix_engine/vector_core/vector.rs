@@ -1,7 +1,10 @@
 use crate::{
     helix_engine::{
         types::{GraphError, VectorError},
-        vector_core::vector_distance::DistanceCalc,
+        vector_core::{
+            vector_distance::{DistanceCalc, DistanceMetric},
+            vector_encoding::{EncodedVector, VectorEncoding},
+        },
     },
     protocol::{return_values::ReturnValue, value::Value},
     utils::{
@@ -13,7 +16,6 @@ use core::fmt;
 use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, cmp::Ordering, collections::HashMap, fmt::Debug};
 
-// TODO: make this generic over the type of encoding (f32, f64, etc)
 // TODO: use const param to set dimension
 // TODO: set level as u8
 
@@ -32,6 +34,10 @@ pub struct HVector {
     pub data: Vec<f64>,
     /// The properties of the HVector
     pub properties: Option<HashMap<String, Value>>,
+    /// The vector in the encoding of its vector type, when that is not f64.
+    /// Vectors read while traversing the index only have this and no `data`.
+    #[serde(skip)]
+    pub encoded: Option<EncodedVector>,
 }
 
 impl Eq for HVector {}
@@ -75,6 +81,7 @@ impl HVector {
             data,
             distance: None,
             properties: None,
+            encoded: None,
         }
     }
 
@@ -88,6 +95,7 @@ impl HVector {
             data,
             distance: None,
             properties: None,
+            encoded: None,
         }
     }
 
@@ -109,9 +117,36 @@ impl HVector {
         self.level
     }
 
-    /// Converts the HVector to an vec of bytes by accessing the data field directly
-    /// and converting each f64 to a byte slice
+    /// Builds an HVector from its stored encoding, f64 vectors are kept in `data`
+    pub fn from_encoded(id: u128, level: usize, encoded: EncodedVector) -> Self {
+        let (data, encoded) = match encoded {
+            EncodedVector::F64(data) => (data, None),
+            encoded => (Vec::new(), Some(encoded)),
+        };
+        HVector {
+            id,
+            level,
+            distance: None,
+            data,
+            properties: None,
+            encoded,
+        }
+    }
+
+    /// Encodes the data of the HVector, distances are then computed on the encoded values
+    pub fn encode(&mut self, encoding: VectorEncoding) {
+        self.encoded = match encoding {
+            VectorEncoding::F64 => None,
+            encoding => Some(encoding.encode(&self.data)),
+        };
+    }
+
+    /// Converts the HVector to an vec of bytes, in its encoding if it has one,
+    /// or by converting each f64 of the data to a byte slice
     pub fn to_bytes(&self) -> Vec<u8> {
+        if let Some(encoded) = &self.encoded {
+            return encoded.to_bytes();
+        }
         let size = self.data.len() * std::mem::size_of::<f64>();
         let mut bytes = Vec::with_capacity(size);
         for &value in &self.data {
@@ -142,22 +177,26 @@ impl HVector {
             data,
             distance: None,
             properties: None,
+            encoded: None,
         })
     }
 
     #[inline(always)]
     pub fn len(&self) -> usize {
-        self.data.len()
+        match &self.encoded {
+            Some(encoded) => encoded.len(),
+            None => self.data.len(),
+        }
     }
 
     #[inline(always)]
     pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
+        self.len() == 0
     }
 
     #[inline(always)]
-    pub fn distance_to(&self, other: &HVector) -> Result<f64, VectorError> {
-        HVector::distance(self, other)
+    pub fn distance_to(&self, other: &HVector, metric: DistanceMetric) -> Result<f64, VectorError> {
+        HVector::distance(self, other, metric)
     }
 
     #[inline(always)]
@@ -1,31 +1,58 @@
 use crate::{
     debug_println,
     helix_engine::{
+        traversal_core::config::VectorIndex,
         types::VectorError,
         vector_core::{
             hnsw::HNSW,
             utils::{Candidate, HeapOps, VectorFilter},
             vector::HVector,
+            vector_distance::DistanceMetric,
+            vector_encoding::{EncodedVector, VectorEncoding},
         },
     },
     protocol::value::Value,
+    utils::filterable::Filterable,
 };
 use heed3::{
     Database, Env, RoTxn, RwTxn,
     types::{Bytes, Unit},
 };
 use itertools::Itertools;
-use rand::prelude::Rng;
-use serde::{Deserialize, Serialize};
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use rand::{prelude::Rng, seq::IteratorRandom};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 const DB_VECTORS: &str = "vectors"; // for vector data (v:)
 const DB_VECTOR_DATA: &str = "vector_data"; // for vector data (v:)
 const DB_HNSW_EDGES: &str = "hnsw_out_nodes"; // for hnsw out node data
 const VECTOR_PREFIX: &[u8] = b"v:";
-const ENTRY_POINT_KEY: &str = "entry_point";
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+const ENTRY_POINT_KEY: &str = "entry_point"; // shared by all labels before each had its own graph
+const ENTRY_POINT_PREFIX: &[u8] = b"entry_point:"; // for the entry point of each label's graph
+const METRIC_PREFIX: &[u8] = b"metric:"; // for the distance metric of each vector type
+const ENCODING_PREFIX: &[u8] = b"encoding:"; // for the storage encoding of each vector type
+const RESCORE_PREFIX: &[u8] = b"r:"; // for the int8 copy binary vectors are rescored with
+const GENERATION_PREFIX: &[u8] = b"generation:"; // for the generation of each label's graph
+const CONFIG_PREFIX: &[u8] = b"hnsw:"; // for the HNSW params a graph was rebuilt with, by namespace
+const REBUILD_PREFIX: &[u8] = b"rebuild:"; // for the claim of the rebuild in progress of each label
+const REBUILD_LEASE: Duration = Duration::from_secs(600); // claims not renewed for this long are stale
+const DEFAULT_LABEL: &str = "vector"; // label of vectors inserted without one, as in `HVector::label`
+const PREFILTER_SAMPLE_SIZE: usize = 128; // vectors sampled to estimate how selective a filter is
+const PREFILTER_BRUTE_FORCE_SELECTIVITY: f64 = 0.02; // below this share of matches, filtered searches scan the label
+const REBUILD_BATCH_SIZE: usize = 1_000; // vectors linked per write transaction when rebuilding a graph
+const DOCUMENT_PREFIX: &[u8] = b"d:"; // for the vectors of each multi-vector document (d:{document}{vector})
+const PARENT_PREFIX: &[u8] = b"p:"; // for the document each vector of a multi-vector document belongs to
+const MAXSIM_CANDIDATES: usize = 100; // vectors gathered per query vector when searching multi-vector documents
+const LEVEL_PREFIX: &[u8] = b"level:"; // for the top level of each vector by label (level:{label}\0{level}{id})
+const LEVEL_INDEX_KEY: &str = "level_index"; // set once the level of every stored vector is indexed
+
+type Filter = fn(&HVector, &RoTxn) -> bool;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct HNSWConfig {
     pub m: usize,             // max num of bi-directional links per element
     pub m_max_0: usize,       // max num of links for lower layers
@@ -59,36 +86,748 @@ impl HNSWConfig {
     }
 }
 
+/// Tuning of a single search, declared with `SearchV<Doc>(vec, k, ef: 200, candidates: 50)`.
+///
+/// Each value left out falls back to the vector type's `HNSWConfig`.
+/// Unlike the configured `ef`, which is capped at 512 when the database starts,
+/// the `ef` of a search is not capped, so a query can trade latency for recall.
+///
+/// Radius searches, `SearchV<Doc>(vec, radius: 0.2)`, have no k: `ef` is how many candidates
+/// beyond the radius are still expanded, and `candidates` caps how many vectors are returned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchParams {
+    /// Size of the candidate list while walking level 0, `HNSWConfig::ef` by default.
+    /// Raised to `k`, as fewer candidates could never hold k results.
+    pub ef: Option<usize>,
+    /// How many of the closest candidates are post filtered, and rescored for binary vectors,
+    /// to pick the k results. All `ef` candidates by default, and at least `k` of them.
+    pub candidates: Option<usize>,
+}
+
+impl SearchParams {
+    pub fn new(ef: Option<usize>, candidates: Option<usize>) -> Self {
+        Self { ef, candidates }
+    }
+}
+
+/// Recall and latency of the HNSW searches of a vector type and the shape of its graph,
+/// as measured by `VectorCore::evaluate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEvaluation {
+    pub label: String,
+    pub k: usize,
+    /// Number of query vectors searched
+    pub queries: usize,
+    /// Share of the exact k nearest neighbors the searches returned, averaged over the queries
+    pub recall: f64,
+    /// Search latency percentiles, in microseconds
+    pub latency_us: LatencyPercentiles,
+    /// Number of vectors of the vector type
+    pub vectors: usize,
+    /// Number of vectors whose top level is each level
+    pub levels: BTreeMap<usize, usize>,
+    /// Average number of links of a vector at level 0
+    pub average_degree: f64,
+    /// Vectors that cannot be reached from the entry point through the links of level 0
+    pub unreachable: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyPercentiles {
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+impl LatencyPercentiles {
+    /// Nearest rank percentiles of latencies sorted from fastest to slowest
+    fn from_sorted(latencies: &[Duration]) -> Self {
+        let percentile = |p: f64| match latencies.len() {
+            0 => 0,
+            n => {
+                let rank = ((p * n as f64).ceil() as usize).clamp(1, n);
+                latencies[rank - 1].as_micros() as u64
+            }
+        };
+        Self {
+            p50: percentile(0.50),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            max: percentile(1.0),
+        }
+    }
+}
+
 pub struct VectorCore {
     pub vectors_db: Database<Bytes, Bytes>,
     pub vector_data_db: Database<Bytes, Bytes>,
     pub edges_db: Database<Bytes, Unit>,
     pub config: HNSWConfig,
+    metrics: HashMap<String, DistanceMetric>,
+    encodings: HashMap<String, VectorEncoding>,
+    configs: HashMap<String, HNSWConfig>,
+    dimensions: HashMap<String, usize>,
+    multi: HashSet<String>,
+}
+
+/// The HNSW graph of a single vector label.
+///
+/// Each label has its own entry point and its edges are keyed under the label,
+/// so inserts and searches on one label never reach vectors of another.
+/// Rebuilding the graph links its vectors under the next generation,
+/// which searches move to once it is complete.
+#[derive(Clone, Copy)]
+struct LabelGraph<'a> {
+    label: &'a str,
+    generation: u32,
+    metric: DistanceMetric,
+    config: HNSWConfig,
+    dimension: Option<usize>,
+    encoding: VectorEncoding,
+}
+
+impl<'a> LabelGraph<'a> {
+    /// Prefix of the graph's edges and entry point keys, the label for the first generation
+    #[inline]
+    fn namespace(&self) -> Cow<'a, str> {
+        match self.generation {
+            0 => Cow::Borrowed(self.label),
+            generation => Cow::Owned(format!("{}#{generation}", self.label)),
+        }
+    }
+
+    /// Rejects vectors whose length differs from the dimension declared for the label
+    #[inline]
+    fn check_dimension(&self, data: &[f64]) -> Result<(), VectorError> {
+        match self.dimension {
+            Some(expected) if expected != data.len() => Err(VectorError::InvalidVectorDimension {
+                label: self.label.to_string(),
+                expected,
+                found: data.len(),
+            }),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl VectorCore {
+    /// Names of the lmdb dbs (tables) the vectors and their graphs are stored in
+    pub const DB_NAMES: [&str; 3] = [DB_VECTORS, DB_VECTOR_DATA, DB_HNSW_EDGES];
+
     pub fn new(env: &Env, txn: &mut RwTxn, config: HNSWConfig) -> Result<Self, VectorError> {
         let vectors_db = env.create_database(txn, Some(DB_VECTORS))?;
         let vector_data_db = env.create_database(txn, Some(DB_VECTOR_DATA))?;
         let edges_db = env.create_database(txn, Some(DB_HNSW_EDGES))?;
 
-        Ok(Self {
+        let metrics = Self::get_settings(&vectors_db, txn, METRIC_PREFIX)?;
+        let encodings = Self::get_settings(&vectors_db, txn, ENCODING_PREFIX)?;
+
+        let core = Self {
             vectors_db,
             vector_data_db,
             edges_db,
             config,
+            metrics,
+            encodings,
+            configs: HashMap::new(),
+            dimensions: HashMap::new(),
+            multi: HashSet::new(),
+        };
+
+        Ok(core)
+    }
+
+    /// Databases written before each label had its own graph keep one entry point
+    /// and one edges namespace for all labels; relinks their vectors into a graph per label.
+    ///
+    /// Vectors soft deleted by those versions are purged rather than relinked.
+    /// The others are linked `REBUILD_BATCH_SIZE` per write transaction, and the shared
+    /// entry point is only removed once all of them are, so an interrupted split starts over
+    /// on the next open.
+    pub fn split_shared_graph(&self, env: &Env) -> Result<(), VectorError> {
+        let txn = env.read_txn()?;
+        if self
+            .vectors_db
+            .get(&txn, ENTRY_POINT_KEY.as_bytes())?
+            .is_none()
+        {
+            return Ok(());
+        }
+        let mut levels = Vec::new();
+        let mut deleted = Vec::new();
+        // ids are time ordered, so vectors are relinked in the order they were inserted
+        for (id, level) in self.vector_levels(&txn)? {
+            let vector = self.get_vector(&txn, id, 0, true)?;
+            match vector.properties.as_ref().and_then(|props| props.get("is_deleted")) {
+                Some(Value::Boolean(true)) => deleted.push((id, level, vector.label().to_string())),
+                _ => levels.push((id, level, vector.label().to_string())),
+            }
+        }
+        drop(txn);
+
+        let mut txn = env.write_txn()?;
+        self.edges_db.clear(&mut txn)?;
+        // entry points left behind by a split that was interrupted
+        let keys: Vec<Vec<u8>> = self
+            .vectors_db
+            .prefix_iter(&txn, ENTRY_POINT_PREFIX)?
+            .map(|result| result.map(|(key, _)| key.to_vec()))
+            .collect::<Result<_, _>>()?;
+        for key in keys {
+            self.vectors_db.delete(&mut txn, &key)?;
+        }
+        for (id, top_level, label) in deleted {
+            for level in self.get_levels(&txn, id)? {
+                self.vectors_db.delete(&mut txn, &Self::vector_key(id, level))?;
+            }
+            self.vectors_db
+                .delete(&mut txn, &Self::level_key(&label, top_level, id))?;
+            self.vectors_db.delete(&mut txn, &Self::rescore_key(id))?;
+            self.vector_data_db.delete(&mut txn, &id.to_be_bytes())?;
+        }
+        txn.commit()?;
+
+        for batch in levels.chunks(REBUILD_BATCH_SIZE) {
+            let mut txn = env.write_txn()?;
+            for (id, level, label) in batch {
+                let mut vector = self.get_vector(&txn, *id, 0, false)?;
+                vector.level = *level;
+                let graph = self.graph(&txn, label)?;
+                self.link::<Filter>(&mut txn, graph, &mut vector)?;
+            }
+            txn.commit()?;
+        }
+
+        let mut txn = env.write_txn()?;
+        self.vectors_db
+            .delete(&mut txn, ENTRY_POINT_KEY.as_bytes())?;
+        txn.commit()?;
+
+        debug_println!("split the shared graph of {} vectors", levels.len());
+        Ok(())
+    }
+
+    /// Databases written before the top level of each vector was indexed by label
+    /// have to find the vectors of a label by scanning all of them; indexes their levels once.
+    ///
+    /// The levels are written `REBUILD_BATCH_SIZE` vectors per write transaction,
+    /// and the index is only marked as built once all of them are.
+    pub fn build_level_index(&self, env: &Env) -> Result<(), VectorError> {
+        let txn = env.read_txn()?;
+        if self
+            .vectors_db
+            .get(&txn, LEVEL_INDEX_KEY.as_bytes())?
+            .is_some()
+        {
+            return Ok(());
+        }
+        let levels: Vec<(u128, usize)> = self.vector_levels(&txn)?.into_iter().collect();
+        drop(txn);
+
+        for batch in levels.chunks(REBUILD_BATCH_SIZE) {
+            let mut txn = env.write_txn()?;
+            for &(id, level) in batch {
+                let label = self.get_vector(&txn, id, 0, true)?.label().to_string();
+                self.vectors_db
+                    .put(&mut txn, &Self::level_key(&label, level, id), &[])?;
+            }
+            txn.commit()?;
+        }
+
+        let mut txn = env.write_txn()?;
+        self.vectors_db
+            .put(&mut txn, LEVEL_INDEX_KEY.as_bytes(), &[])?;
+        txn.commit()?;
+
+        debug_println!("indexed the levels of {} vectors", levels.len());
+        Ok(())
+    }
+
+    /// Applies the settings declared for each vector type.
+    ///
+    /// The HNSW parameters a vector type leaves out fall back to the global config.
+    /// The metric and encoding of a vector type that already has vectors cannot change,
+    /// as its graph was built with the old ones, and neither can its dimension.
+    pub fn set_indices(
+        &mut self,
+        txn: &mut RwTxn,
+        indices: &[VectorIndex],
+    ) -> Result<(), VectorError> {
+        for index in indices {
+            if index.multi {
+                self.multi.insert(index.label.clone());
+            }
+
+            if index.m.is_some() || index.ef_construction.is_some() || index.ef_search.is_some() {
+                let config = HNSWConfig::new(
+                    index.m.or(Some(self.config.m)),
+                    index.ef_construction.or(Some(self.config.ef_construct)),
+                    index.ef_search.or(Some(self.config.ef)),
+                );
+                self.configs.insert(index.label.clone(), config);
+            }
+
+            if let Some(dimension) = index.dimension {
+                if let Ok(entry_point) = self.get_entry_point(txn, self.graph(txn, &index.label)?)
+                    && entry_point.len() != dimension
+                {
+                    return Err(VectorError::VectorCoreError(format!(
+                        "vector type {} has {} dimensional vectors and cannot be declared with {}",
+                        index.label,
+                        entry_point.len(),
+                        dimension
+                    )));
+                }
+                self.dimensions.insert(index.label.clone(), dimension);
+            }
+
+            let has_vectors = self
+                .get_entry_point_id(txn, self.graph(txn, &index.label)?)?
+                .is_some();
+
+            let current = self.metric(&index.label);
+            if current != index.metric && has_vectors {
+                return Err(VectorError::VectorCoreError(format!(
+                    "vector type {} is indexed with {} distance and cannot be changed to {}",
+                    index.label, current, index.metric
+                )));
+            }
+            if current != index.metric || !self.metrics.contains_key(&index.label) {
+                Self::put_setting(
+                    self.vectors_db,
+                    txn,
+                    METRIC_PREFIX,
+                    &index.label,
+                    &index.metric,
+                )?;
+                self.metrics.insert(index.label.clone(), index.metric);
+            }
+
+            let current = self.encoding(&index.label);
+            if current != index.encoding && has_vectors {
+                return Err(VectorError::VectorCoreError(format!(
+                    "vector type {} is stored as {} and cannot be changed to {}",
+                    index.label, current, index.encoding
+                )));
+            }
+            if current != index.encoding || !self.encodings.contains_key(&index.label) {
+                Self::put_setting(
+                    self.vectors_db,
+                    txn,
+                    ENCODING_PREFIX,
+                    &index.label,
+                    &index.encoding,
+                )?;
+                self.encodings.insert(index.label.clone(), index.encoding);
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the HNSW graph of the vector type `label` with its current config,
+    /// so changes to `m` or `ef_construction` apply to the vectors inserted before them.
+    ///
+    /// The HNSW params given override the declared ones for the rebuilt graph and the vectors
+    /// inserted into it, until the next rebuild; a rebuild without any goes back to them.
+    ///
+    /// The vectors are linked into a shadow graph, `REBUILD_BATCH_SIZE` per write transaction,
+    /// while searches and writes keep going through the current graph.
+    /// Vectors inserted or deleted in the meantime are caught up in the last transaction,
+    /// which swaps the shadow graph in and drops the old one.
+    ///
+    /// The rebuild is claimed in the database, so no other rebuild of the label runs alongside
+    /// it in this or another process, and the claim is renewed with every batch,
+    /// so one left by a rebuild that crashed lapses after `REBUILD_LEASE`.
+    ///
+    /// Returns the number of vectors in the rebuilt graph.
+    pub fn rebuild(
+        &self,
+        env: &Env,
+        label: &str,
+        m: Option<usize>,
+        ef_construction: Option<usize>,
+        ef_search: Option<usize>,
+    ) -> Result<usize, VectorError> {
+        let claim: u128 = rand::rng().random();
+        let mut txn = env.write_txn()?;
+        self.claim_rebuild(&mut txn, label, claim)?;
+        txn.commit()?;
+
+        let config = (m.is_some() || ef_construction.is_some() || ef_search.is_some()).then(|| {
+            let declared = self.config(label);
+            HNSWConfig::new(
+                m.or(Some(declared.m)),
+                ef_construction.or(Some(declared.ef_construct)),
+                ef_search.or(Some(declared.ef)),
+            )
+        });
+        let result = self.rebuild_graph(env, label, config, claim);
+        if result.is_err() {
+            let mut txn = env.write_txn()?;
+            if self.rebuild_claim(&txn, label)?.is_some_and(|(holder, _)| holder == claim) {
+                self.vectors_db
+                    .delete(&mut txn, &[REBUILD_PREFIX, label.as_bytes()].concat())?;
+            }
+            txn.commit()?;
+        }
+        result
+    }
+
+    /// The holder of the claim on the rebuild of the label and when it last renewed it,
+    /// in seconds since the unix epoch
+    fn rebuild_claim(&self, txn: &RoTxn, label: &str) -> Result<Option<(u128, u64)>, VectorError> {
+        let key = [REBUILD_PREFIX, label.as_bytes()].concat();
+        match self.vectors_db.get(txn, &key)? {
+            Some(bytes) => Ok(Some(bincode::deserialize(bytes)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Claims or renews the claim on the rebuild of the label,
+    /// unless another rebuild holds a claim it renewed within `REBUILD_LEASE`
+    fn claim_rebuild(&self, txn: &mut RwTxn, label: &str, claim: u128) -> Result<(), VectorError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if let Some((holder, renewed)) = self.rebuild_claim(txn, label)?
+            && holder != claim
+            && now.saturating_sub(renewed) < REBUILD_LEASE.as_secs()
+        {
+            return Err(VectorError::VectorCoreError(format!(
+                "vector type {label} is already being rebuilt"
+            )));
+        }
+        let key = [REBUILD_PREFIX, label.as_bytes()].concat();
+        self.vectors_db
+            .put(txn, &key, &bincode::serialize(&(claim, now))?)?;
+        Ok(())
+    }
+
+    fn rebuild_graph(
+        &self,
+        env: &Env,
+        label: &str,
+        config: Option<HNSWConfig>,
+        claim: u128,
+    ) -> Result<usize, VectorError> {
+        let txn = env.read_txn()?;
+        let mut shadow = self.graph(&txn, label)?;
+        let live = shadow.generation;
+        shadow.generation += 1;
+        shadow.config = config.unwrap_or(*self.config(label));
+        let levels = self.label_levels(&txn, label)?;
+        drop(txn);
+
+        // leftovers of a rebuild that was interrupted
+        let mut txn = env.write_txn()?;
+        self.clear_graph(&mut txn, shadow)?;
+        if let Some(config) = &config {
+            let key = [CONFIG_PREFIX, shadow.namespace().as_bytes()].concat();
+            self.vectors_db
+                .put(&mut txn, &key, &bincode::serialize(config)?)?;
+        }
+        txn.commit()?;
+
+        let mut built: BTreeMap<u128, usize> = BTreeMap::new();
+        let levels: Vec<(u128, usize)> = levels.into_iter().collect();
+        for batch in levels.chunks(REBUILD_BATCH_SIZE) {
+            let mut txn = env.write_txn()?;
+            self.claim_rebuild(&mut txn, label, claim)?;
+            for &(id, level) in batch {
+                let mut vector = match self.get_graph_vector(&txn, shadow, id, 0) {
+                    Ok(vector) => vector,
+                    // deleted since the rebuild started
+                    Err(VectorError::VectorNotFound(_)) => continue,
+                    Err(e) => return Err(e),
+                };
+                vector.level = level;
+                self.link::<Filter>(&mut txn, shadow, &mut vector)?;
+                built.insert(id, level);
+            }
+            txn.commit()?;
+        }
+
+        let mut txn = env.write_txn()?;
+        let current = self.graph(&txn, label)?;
+        if current.generation != live {
+            return Err(VectorError::VectorCoreError(format!(
+                "vector type {label} was rebuilt by another rebuild"
+            )));
+        }
+
+        let levels = self.label_levels(&txn, label)?;
+        for (&id, &level) in &levels {
+            if !built.contains_key(&id) {
+                let mut vector = self.get_graph_vector(&txn, shadow, id, 0)?;
+                vector.level = level;
+                self.link::<Filter>(&mut txn, shadow, &mut vector)?;
+            }
+        }
+        for (&id, &level) in &built {
+            if !levels.contains_key(&id) {
+                for level in 0..=level {
+                    self.unlink(&mut txn, shadow, id, level)?;
+                }
+            }
+        }
+        if self
+            .get_entry_point_id(&txn, shadow)?
+            .is_some_and(|id| !levels.contains_key(&id))
+        {
+            self.replace_entry_point(&mut txn, shadow)?;
+        }
+
+        Self::put_setting(
+            self.vectors_db,
+            &mut txn,
+            GENERATION_PREFIX,
+            label,
+            &shadow.generation,
+        )?;
+        self.clear_graph(&mut txn, current)?;
+        self.vectors_db
+            .delete(&mut txn, &[REBUILD_PREFIX, label.as_bytes()].concat())?;
+        txn.commit()?;
+
+        debug_println!("rebuilt the graph of {label} with {} vectors", levels.len());
+        Ok(levels.len())
+    }
+
+    /// Measures the recall@k of the HNSW searches of the vector type `label` against
+    /// an exact search, along with their latency and statistics of the label's graph.
+    ///
+    /// The queries are `sample` vectors of the label picked at random, unless query vectors
+    /// are given. The exact neighbors of all queries are found in a single pass over the label.
+    pub fn evaluate(
+        &self,
+        txn: &RoTxn,
+        label: &str,
+        k: usize,
+        queries: Option<Vec<Vec<f64>>>,
+        sample: usize,
+        params: SearchParams,
+    ) -> Result<IndexEvaluation, VectorError> {
+        let graph = self.graph(txn, label)?;
+        if self.label_vectors(txn, label)?.next().is_none() {
+            return Err(VectorError::VectorCoreError(format!(
+                "vector type {label} has no vectors to evaluate"
+            )));
+        }
+
+        let queries = match queries {
+            Some(queries) => {
+                for query in &queries {
+                    graph.check_dimension(query)?;
+                }
+                queries
+            }
+            None => self
+                .label_vectors(txn, label)?
+                .process_results(|vectors| {
+                    vectors
+                        .map(|(id, _)| id)
+                        .choose_multiple(&mut rand::rng(), sample)
+                })?
+                .into_iter()
+                .map(|id| {
+                    let mut vector = self.get_graph_vector(txn, graph, id, 0)?;
+                    self.decode(txn, graph, &mut vector)?;
+                    Ok(vector.data)
+                })
+                .collect::<Result<_, VectorError>>()?,
+        };
+
+        // the vectors are streamed from the level index, so only the exact k nearest neighbors
+        // of each query, closest first, and the shape of the graph are kept
+        let mut exact: Vec<Vec<(f64, u128)>> = vec![Vec::with_capacity(k + 1); queries.len()];
+        let mut vectors = 0;
+        let mut level_counts = BTreeMap::new();
+        let mut links = 0;
+        for result in self.label_vectors(txn, label)? {
+            let (id, level) = result?;
+            vectors += 1;
+            *level_counts.entry(level).or_insert(0) += 1;
+            links += self.neighbor_ids(txn, graph, id, 0)?.len();
+
+            let mut vector = self.get_graph_vector(txn, graph, id, 0)?;
+            self.decode(txn, graph, &mut vector)?;
+            for (query, nearest) in queries.iter().zip(exact.iter_mut()) {
+                let distance = graph.metric.distance(query, &vector.data)?;
+                if nearest.len() < k || nearest.last().is_some_and(|&(d, _)| distance < d) {
+                    let at = nearest.partition_point(|&(d, _)| d <= distance);
+                    nearest.insert(at, (distance, id));
+                    nearest.truncate(k);
+                }
+            }
+        }
+
+        let mut recall = 0.0;
+        let mut latencies = Vec::with_capacity(queries.len());
+        for (query, nearest) in queries.iter().zip(&exact) {
+            let start = Instant::now();
+            let results = self.search::<Filter>(txn, query, k, label, None, false, params)?;
+            latencies.push(start.elapsed());
+
+            let found: HashSet<u128> = results.iter().map(|vector| vector.id).collect();
+            recall += match nearest.len() {
+                0 => 1.0,
+                n => nearest.iter().filter(|(_, id)| found.contains(id)).count() as f64 / n as f64,
+            };
+        }
+        latencies.sort_unstable();
+
+        // links to deleted vectors are left until the vectors linking to them are relinked
+        let mut reachable = HashSet::new();
+        if let Some(entry_point) = self.get_entry_point_id(txn, graph)? {
+            reachable.insert(entry_point);
+            let mut queue = VecDeque::from([entry_point]);
+            while let Some(id) = queue.pop_front() {
+                for neighbor in self.neighbor_ids(txn, graph, id, 0)? {
+                    if !reachable.contains(&neighbor)
+                        && self
+                            .vectors_db
+                            .get(txn, &Self::vector_key(neighbor, 0))?
+                            .is_some()
+                    {
+                        reachable.insert(neighbor);
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+
+        Ok(IndexEvaluation {
+            label: label.to_string(),
+            k,
+            queries: queries.len(),
+            recall: match queries.len() {
+                0 => 0.0,
+                n => recall / n as f64,
+            },
+            latency_us: LatencyPercentiles::from_sorted(&latencies),
+            vectors,
+            levels: level_counts,
+            average_degree: links as f64 / vectors as f64,
+            unreachable: vectors - reachable.len(),
         })
     }
 
+    /// Distance metric of the vector type `label`, cosine if it was never declared
+    #[inline]
+    pub fn metric(&self, label: &str) -> DistanceMetric {
+        self.metrics.get(label).copied().unwrap_or_default()
+    }
+
+    /// Storage encoding of the vector type `label`, f64 if it was never declared
+    #[inline]
+    pub fn encoding(&self, label: &str) -> VectorEncoding {
+        self.encodings.get(label).copied().unwrap_or_default()
+    }
+
+    /// Whether the items of the vector type `label` are documents of several vectors
+    #[inline]
+    pub fn is_multi(&self, label: &str) -> bool {
+        self.multi.contains(label)
+    }
+
+    /// HNSW config of the vector type `label`
+    #[inline]
+    pub fn config(&self, label: &str) -> &HNSWConfig {
+        self.configs.get(label).unwrap_or(&self.config)
+    }
+
+    /// The graph of the vector type `label` searches currently go through,
+    /// with the HNSW params it was rebuilt with if they were given
+    #[inline]
+    fn graph<'a>(&'a self, txn: &RoTxn, label: &'a str) -> Result<LabelGraph<'a>, VectorError> {
+        let key = [GENERATION_PREFIX, label.as_bytes()].concat();
+        let generation = match self.vectors_db.get(txn, &key)? {
+            Some(bytes) => bincode::deserialize(bytes)?,
+            None => 0,
+        };
+        let mut graph = LabelGraph {
+            label,
+            generation,
+            metric: self.metric(label),
+            config: *self.config(label),
+            dimension: self.dimensions.get(label).copied(),
+            encoding: self.encoding(label),
+        };
+        let key = [CONFIG_PREFIX, graph.namespace().as_bytes()].concat();
+        if let Some(bytes) = self.vectors_db.get(txn, &key)? {
+            graph.config = bincode::deserialize(bytes)?;
+        }
+        Ok(graph)
+    }
+
+    /// Reads the setting stored under `prefix` for each vector type
+    fn get_settings<T: DeserializeOwned>(
+        db: &Database<Bytes, Bytes>,
+        txn: &RoTxn,
+        prefix: &[u8],
+    ) -> Result<HashMap<String, T>, VectorError> {
+        db.prefix_iter(txn, prefix)?
+            .map(|result| -> Result<(String, T), VectorError> {
+                let (key, value): (&[u8], &[u8]) = result?;
+                let label = String::from_utf8_lossy(&key[prefix.len()..]).to_string();
+                Ok((label, bincode::deserialize(value)?))
+            })
+            .collect()
+    }
+
+    fn put_setting<T: Serialize>(
+        db: Database<Bytes, Bytes>,
+        txn: &mut RwTxn,
+        prefix: &[u8],
+        label: &str,
+        value: &T,
+    ) -> Result<(), VectorError> {
+        let key = [prefix, label.as_bytes()].concat();
+        db.put(txn, &key, &bincode::serialize(value)?)?;
+        Ok(())
+    }
+
     #[inline(always)]
     fn vector_key(id: u128, level: usize) -> Vec<u8> {
         [VECTOR_PREFIX, &id.to_be_bytes(), &level.to_be_bytes()].concat()
     }
 
     #[inline(always)]
-    fn out_edges_key(source_id: u128, level: usize, sink_id: Option<u128>) -> Vec<u8> {
+    fn rescore_key(id: u128) -> Vec<u8> {
+        [RESCORE_PREFIX, &id.to_be_bytes()].concat()
+    }
+
+    #[inline(always)]
+    fn document_key(document: u128, vector: Option<u128>) -> Vec<u8> {
+        match vector {
+            Some(vector) => [
+                DOCUMENT_PREFIX,
+                &document.to_be_bytes(),
+                &vector.to_be_bytes(),
+            ]
+            .concat(),
+            None => [DOCUMENT_PREFIX, &document.to_be_bytes()].concat(),
+        }
+    }
+
+    #[inline(always)]
+    fn parent_key(vector: u128) -> Vec<u8> {
+        [PARENT_PREFIX, &vector.to_be_bytes()].concat()
+    }
+
+    #[inline(always)]
+    fn out_edges_key(
+        namespace: &str,
+        source_id: u128,
+        level: usize,
+        sink_id: Option<u128>,
+    ) -> Vec<u8> {
         match sink_id {
             Some(sink_id) => [
+                namespace.as_bytes(),
+                &[0],
                 source_id.to_be_bytes().as_slice(),
                 level.to_be_bytes().as_slice(),
                 sink_id.to_be_bytes().as_slice(),
@@ -96,6 +835,8 @@ impl VectorCore {
             .concat()
             .to_vec(),
             None => [
+                namespace.as_bytes(),
+                &[0],
                 source_id.to_be_bytes().as_slice(),
                 level.to_be_bytes().as_slice(),
             ]
@@ -104,37 +845,83 @@ impl VectorCore {
         }
     }
 
+    #[inline(always)]
+    fn level_key(label: &str, level: usize, id: u128) -> Vec<u8> {
+        [
+            Self::level_prefix(label).as_slice(),
+            &level.to_be_bytes(),
+            &id.to_be_bytes(),
+        ]
+        .concat()
+    }
+
+    #[inline(always)]
+    fn level_prefix(label: &str) -> Vec<u8> {
+        [LEVEL_PREFIX, label.as_bytes(), &[0]].concat()
+    }
+
+    /// Id and level of a vector from its key in the level index, without the label's prefix
+    #[inline(always)]
+    fn from_level_key(key: &[u8]) -> Result<(u128, usize), VectorError> {
+        let (level, id) = key.split_at(std::mem::size_of::<usize>());
+        Ok((
+            u128::from_be_bytes(id.try_into().map_err(|_| VectorError::InvalidVectorData)?),
+            usize::from_be_bytes(level.try_into().map_err(|_| VectorError::InvalidVectorData)?),
+        ))
+    }
+
+    #[inline(always)]
+    fn entry_point_key(namespace: &str) -> Vec<u8> {
+        [ENTRY_POINT_PREFIX, namespace.as_bytes()].concat()
+    }
+
     #[inline]
-    fn get_new_level(&self) -> usize {
+    fn get_new_level(&self, graph: LabelGraph) -> usize {
         // TODO: look at using the XOR shift algorithm for random number generation
         // Should instead using an atomic mutable seed and the XOR shift algorithm
         let mut rng = rand::rng();
         let r: f64 = rng.random::<f64>();
-        (-r.ln() * self.config.m_l).floor() as usize
+        (-r.ln() * graph.config.m_l).floor() as usize
     }
 
     #[inline]
-    fn get_entry_point(&self, txn: &RoTxn) -> Result<HVector, VectorError> {
-        let ep_id = self.vectors_db.get(txn, ENTRY_POINT_KEY.as_bytes())?;
-        if let Some(ep_id) = ep_id {
-            let mut arr = [0u8; 16];
-            let len = std::cmp::min(ep_id.len(), 16);
-            arr[..len].copy_from_slice(&ep_id[..len]);
-
-            let ep = self
-                .get_vector(txn, u128::from_be_bytes(arr), 0, true)
-                .map_err(|_| VectorError::EntryPointNotFound)?;
-            Ok(ep)
-        } else {
-            Err(VectorError::EntryPointNotFound)
+    fn get_entry_point(&self, txn: &RoTxn, graph: LabelGraph) -> Result<HVector, VectorError> {
+        match self.get_entry_point_id(txn, graph)? {
+            Some(ep_id) => {
+                let ep = self
+                    .get_graph_vector(txn, graph, ep_id, 0)
+                    .map_err(|_| VectorError::EntryPointNotFound)?;
+                Ok(ep)
+            }
+            None => Err(VectorError::EntryPointNotFound),
         }
     }
 
+    fn get_entry_point_id(
+        &self,
+        txn: &RoTxn,
+        graph: LabelGraph,
+    ) -> Result<Option<u128>, VectorError> {
+        Ok(self
+            .vectors_db
+            .get(txn, &Self::entry_point_key(&graph.namespace()))?
+            .and_then(|ep_id| ep_id.try_into().ok())
+            .map(u128::from_be_bytes))
+    }
+
     #[inline]
-    fn set_entry_point(&self, txn: &mut RwTxn, entry: &HVector) -> Result<(), VectorError> {
-        let entry_key = ENTRY_POINT_KEY.as_bytes().to_vec();
+    fn set_entry_point(
+        &self,
+        txn: &mut RwTxn,
+        graph: LabelGraph,
+        entry: &HVector,
+    ) -> Result<(), VectorError> {
         self.vectors_db
-            .put(txn, &entry_key, &entry.get_id().to_be_bytes())
+            .put(
+                txn,
+                &Self::entry_point_key(&graph.namespace()),
+                &entry.get_id().to_be_bytes(),
+            )
             .map_err(VectorError::from)?;
         Ok(())
     }
@@ -152,19 +939,16 @@ impl VectorCore {
     }
 
     #[inline(always)]
-    fn get_neighbors<F>(
+    fn get_neighbors(
         &self,
         txn: &RoTxn,
+        graph: LabelGraph,
         id: u128,
         level: usize,
-        filter: Option<&[F]>,
-    ) -> Result<Vec<HVector>, VectorError>
-    where
-        F: Fn(&HVector, &RoTxn) -> bool,
-    {
-        let out_key = Self::out_edges_key(id, level, None);
-        let mut neighbors = Vec::with_capacity(self.config.m_max_0.min(self.config.min_neighbors));
-
+    ) -> Result<Vec<HVector>, VectorError> {
+        let out_key = Self::out_edges_key(&graph.namespace(), id, level, None);
+        let mut neighbors =
+            Vec::with_capacity(graph.config.m_max_0.min(graph.config.min_neighbors));
         let iter = self
             .edges_db
             .lazily_decode_data()
@@ -185,38 +969,54 @@ impl VectorCore {
                 continue;
             }
 
-            let vector = self.get_vector(txn, neighbor_id, level, false)?;
-
-            let passes_filters = match filter {
-                // TODO: look at implementing a macro that actually just runs each function rather than iterating through
-                Some(filter_slice) => filter_slice.iter().all(|f| f(&vector, txn)),
-                None => true,
-            };
-
-            if passes_filters {
-                neighbors.push(vector);
+            // links left behind by a deleted vector
+            match self.get_graph_vector(txn, graph, neighbor_id, level) {
+                Ok(vector) => neighbors.push(vector),
+                Err(VectorError::VectorNotFound(_)) => continue,
+                Err(e) => return Err(e),
             }
-
-            //if let Ok(vector) = self.get_vector(txn, neighbor_id, level, true) {
-            //    if filter.is_none() || filter.unwrap().iter().all(|f| f(&vector, txn)) {
-            //        neighbors.push(vector);
-            //    }
-            //}
         }
         neighbors.shrink_to_fit();
 
         Ok(neighbors)
     }
 
+    /// Ids the vector links to at `level`, without reading the linked vectors
+    fn neighbor_ids(
+        &self,
+        txn: &RoTxn,
+        graph: LabelGraph,
+        id: u128,
+        level: usize,
+    ) -> Result<Vec<u128>, VectorError> {
+        let out_key = Self::out_edges_key(&graph.namespace(), id, level, None);
+        let prefix_len = out_key.len();
+        self.edges_db
+            .lazily_decode_data()
+            .prefix_iter(txn, &out_key)?
+            .map(|result| {
+                let (key, _) = result?;
+                let sink: [u8; 16] = key
+                    .get(prefix_len..prefix_len + 16)
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or(VectorError::InvalidVectorData)?;
+                Ok(u128::from_be_bytes(sink))
+            })
+            .filter_ok(|&sink| sink != id)
+            .collect()
+    }
+
     #[inline(always)]
     fn set_neighbours(
         &self,
         txn: &mut RwTxn,
+        graph: LabelGraph,
         id: u128,
         neighbors: &BinaryHeap<HVector>,
         level: usize,
     ) -> Result<(), VectorError> {
-        let prefix = Self::out_edges_key(id, level, None);
+        let namespace = graph.namespace();
+        let prefix = Self::out_edges_key(&namespace, id, level, None);
 
         let mut keys_to_delete: HashSet<Vec<u8>> = self
             .edges_db
@@ -232,11 +1032,11 @@ impl VectorCore {
                     return Ok(());
                 }
 
-                let out_key = Self::out_edges_key(id, level, Some(neighbor_id));
+                let out_key = Self::out_edges_key(&namespace, id, level, Some(neighbor_id));
                 keys_to_delete.remove(&out_key);
                 self.edges_db.put(txn, &out_key, &())?;
 
-                let in_key = Self::out_edges_key(neighbor_id, level, Some(id));
+                let in_key = Self::out_edges_key(&namespace, neighbor_id, level, Some(id));
                 keys_to_delete.remove(&in_key);
                 self.edges_db.put(txn, &in_key, &())?;
 
@@ -250,9 +1050,11 @@ impl VectorCore {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn select_neighbors<'a, F>(
         &'a self,
         txn: &RoTxn,
+        graph: LabelGraph,
         query: &'a HVector,
         mut cands: BinaryHeap<HVector>,
         level: usize,
@@ -262,7 +1064,7 @@ impl VectorCore {
     where
         F: Fn(&HVector, &RoTxn) -> bool,
     {
-        let m = self.config.m;
+        let m = graph.config.m;
 
         if !should_extend {
             return Ok(cands.take_inord(m));
@@ -271,12 +1073,12 @@ impl VectorCore {
         let mut visited: HashSet<u128> = HashSet::new();
         let mut result = BinaryHeap::with_capacity(m * cands.len());
         for candidate in cands.iter() {
-            for mut neighbor in self.get_neighbors(txn, candidate.get_id(), level, filter)? {
+            for mut neighbor in self.get_neighbors(txn, graph, candidate.get_id(), level)? {
                 if !visited.insert(neighbor.get_id()) {
                     continue;
                 }
 
-                neighbor.set_distance(neighbor.distance_to(query)?);
+                neighbor.set_distance(neighbor.distance_to(query, graph.metric)?);
 
                 /*
                 let passes_filters = match filter {
@@ -299,9 +1101,11 @@ impl VectorCore {
         Ok(result.take_inord(m))
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn search_level<'a, F>(
         &'a self,
         txn: &RoTxn,
+        graph: LabelGraph,
         query: &'a HVector,
         entry_point: &'a mut HVector,
         ef: usize,
@@ -315,12 +1119,14 @@ impl VectorCore {
         let mut candidates: BinaryHeap<Candidate> = BinaryHeap::new();
         let mut results: BinaryHeap<HVector> = BinaryHeap::new();
 
-        entry_point.set_distance(entry_point.distance_to(query)?);
+        entry_point.set_distance(entry_point.distance_to(query, graph.metric)?);
         candidates.push(Candidate {
             id: entry_point.get_id(),
             distance: entry_point.get_distance(),
         });
-        results.push(entry_point.clone());
+        if self.passes_filter(txn, entry_point, filter)? {
+            results.push(entry_point.clone());
+        }
         visited.insert(entry_point.get_id());
 
         while let Some(curr_cand) = candidates.pop() {
@@ -338,168 +1144,335 @@ impl VectorCore {
                 None
             };
 
-            self.get_neighbors(txn, curr_cand.id, level, filter)?
-                .into_iter()
-                .filter(|neighbor| visited.insert(neighbor.get_id()))
-                .filter_map(|mut neighbor| {
-                    let distance = neighbor.distance_to(query).ok()?;
-
-                    if max_distance.is_none_or(|max| distance < max) {
-                        neighbor.set_distance(distance);
-                        Some((neighbor, distance))
-                    } else {
-                        None
-                    }
-                })
-                .for_each(|(neighbor, distance)| {
-                    candidates.push(Candidate {
-                        id: neighbor.get_id(),
-                        distance,
-                    });
+            for mut neighbor in self.get_neighbors(txn, graph, curr_cand.id, level)? {
+                if !visited.insert(neighbor.get_id()) {
+                    continue;
+                }
+                let Ok(distance) = neighbor.distance_to(query, graph.metric) else {
+                    continue;
+                };
+                if max_distance.is_some_and(|max| distance >= max) {
+                    continue;
+                }
+                neighbor.set_distance(distance);
 
-                    results.push(neighbor);
+                // vectors filtered out are still walked through, so the graph stays connected
+                candidates.push(Candidate {
+                    id: neighbor.get_id(),
+                    distance,
+                });
 
+                if self.passes_filter(txn, &mut neighbor, filter)? {
+                    results.push(neighbor);
                     if results.len() > ef {
                         results = results.take_inord(ef);
                     }
-                });
+                }
+            }
         }
         Ok(results)
     }
 
-    pub fn num_inserted_vectors(&self, txn: &RoTxn) -> Result<u64, VectorError> {
-        Ok(self.vectors_db.len(txn)?)
-    }
-}
-
-impl HNSW for VectorCore {
-    #[inline(always)]
-    fn get_vector(
+    /// Vectors of level 0 within `radius` of the query, found by expanding the closest candidate
+    /// until the closest left is beyond the radius.
+    ///
+    /// `slack` more candidates beyond the radius are then expanded,
+    /// as a vector within the radius can be linked only through vectors outside it.
+    fn search_level_radius(
         &self,
         txn: &RoTxn,
-        id: u128,
-        level: usize,
-        with_data: bool,
-    ) -> Result<HVector, VectorError> {
-        let key = Self::vector_key(id, level);
-        match self.vectors_db.get(txn, key.as_ref())? {
-            Some(bytes) => {
-                let mut vector = HVector::from_bytes(id, level, bytes)?;
-                match with_data {
-                    true => {
-                        let properties: Option<HashMap<String, Value>> =
-                            match self.vector_data_db.get(txn, &id.to_be_bytes())? {
-                                Some(bytes) => {
-                                    Some(bincode::deserialize(bytes).map_err(VectorError::from)?)
-                                }
-                                None => None,
-                            };
-                        vector.properties = properties;
-                        Ok(vector)
-                    }
-                    false => Ok(vector),
+        graph: LabelGraph,
+        query: &[f64],
+        mut entry_point: HVector,
+        radius: f64,
+        slack: usize,
+    ) -> Result<BinaryHeap<HVector>, VectorError> {
+        let mut visited: HashSet<u128> = HashSet::from([entry_point.get_id()]);
+        let mut candidates: BinaryHeap<Candidate> = BinaryHeap::new();
+        let mut results: BinaryHeap<HVector> = BinaryHeap::new();
+
+        let distance = self.exact_distance(txn, graph, query, &mut entry_point)?;
+        entry_point.set_distance(distance);
+        candidates.push(Candidate {
+            id: entry_point.get_id(),
+            distance,
+        });
+        if distance <= radius {
+            results.push(entry_point);
+        }
+
+        let mut expanded_beyond = 0;
+        while let Some(curr_cand) = candidates.pop() {
+            if curr_cand.distance > radius {
+                if expanded_beyond >= slack {
+                    break;
+                }
+                expanded_beyond += 1;
+            }
+
+            for mut neighbor in self.get_neighbors(txn, graph, curr_cand.id, 0)? {
+                if !visited.insert(neighbor.get_id()) {
+                    continue;
+                }
+                let Ok(distance) = self.exact_distance(txn, graph, query, &mut neighbor) else {
+                    continue;
+                };
+                neighbor.set_distance(distance);
+                candidates.push(Candidate {
+                    id: neighbor.get_id(),
+                    distance,
+                });
+                if distance <= radius {
+                    results.push(neighbor);
                 }
             }
-            None if level > 0 => self.get_vector(txn, id, 0, with_data),
-            None => Err(VectorError::VectorNotFound(id.to_string())),
         }
+        Ok(results)
     }
 
-    fn search<F>(
+    /// Distance from the query to a vector of the graph on its decoded data,
+    /// so radius searches compare the same distances whatever the encoding
+    #[inline]
+    fn exact_distance(
         &self,
         txn: &RoTxn,
+        graph: LabelGraph,
         query: &[f64],
-        k: usize,
-        label: &str,
+        vector: &mut HVector,
+    ) -> Result<f64, VectorError> {
+        self.decode(txn, graph, vector)?;
+        graph.metric.distance(query, &vector.data)
+    }
+
+    /// Whether the vector passes every filter, its properties are loaded for the filters to read
+    fn passes_filter<F>(
+        &self,
+        txn: &RoTxn,
+        vector: &mut HVector,
         filter: Option<&[F]>,
-        should_trickle: bool,
-    ) -> Result<Vec<HVector>, VectorError>
+    ) -> Result<bool, VectorError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+    {
+        let Some(filter) = filter else {
+            return Ok(true);
+        };
+        if vector.properties.is_none() {
+            vector.properties = match self.vector_data_db.get(txn, &vector.id.to_be_bytes())? {
+                Some(bytes) => Some(bincode::deserialize(bytes).map_err(VectorError::from)?),
+                None => None,
+            };
+        }
+        Ok(filter.iter().all(|f| f(vector, txn)))
+    }
+
+    /// Share of the vectors around `entry_point` at level 0 that pass the filter,
+    /// estimated from the first `PREFILTER_SAMPLE_SIZE` vectors reached from it
+    fn filter_selectivity<F>(
+        &self,
+        txn: &RoTxn,
+        graph: LabelGraph,
+        entry_point: &HVector,
+        filter: &[F],
+    ) -> Result<f64, VectorError>
     where
         F: Fn(&HVector, &RoTxn) -> bool,
     {
-        let query = HVector::from_slice(0, query.to_vec());
+        let mut visited = HashSet::from([entry_point.get_id()]);
+        let mut queue = VecDeque::from([entry_point.get_id()]);
+        let (mut sampled, mut passed) = (0usize, 0usize);
+        while let Some(id) = queue.pop_front() {
+            for mut neighbor in self.get_neighbors(txn, graph, id, 0)? {
+                if sampled >= PREFILTER_SAMPLE_SIZE {
+                    return Ok(passed as f64 / sampled as f64);
+                }
+                if !visited.insert(neighbor.get_id()) {
+                    continue;
+                }
+                sampled += 1;
+                if self.passes_filter(txn, &mut neighbor, Some(filter))? {
+                    passed += 1;
+                }
+                queue.push_back(neighbor.get_id());
+            }
+        }
+        match sampled {
+            0 => Ok(1.0),
+            _ => Ok(passed as f64 / sampled as f64),
+        }
+    }
 
-        let mut entry_point = self.get_entry_point(txn)?;
+    /// Compares the query to every vector of the label that passes the filter,
+    /// for filters too selective for the graph to find enough vectors through
+    fn brute_force_search<F>(
+        &self,
+        txn: &RoTxn,
+        graph: LabelGraph,
+        query: &HVector,
+        k: usize,
+        filter: &[F],
+    ) -> Result<BinaryHeap<HVector>, VectorError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+    {
+        let mut results = BinaryHeap::new();
+        for result in self.label_vectors(txn, graph.label)? {
+            let (id, _) = result?;
+            let properties: Option<HashMap<String, Value>> =
+                match self.vector_data_db.get(txn, &id.to_be_bytes())? {
+                    Some(bytes) => Some(bincode::deserialize(bytes).map_err(VectorError::from)?),
+                    None => None,
+                };
+            if let Some(Value::Boolean(true)) =
+                properties.as_ref().and_then(|props| props.get("is_deleted"))
+            {
+                continue;
+            }
+            let mut vector = match self.get_graph_vector(txn, graph, id, 0) {
+                Ok(vector) => vector,
+                Err(VectorError::VectorNotFound(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            vector.properties = properties;
+            if !filter.iter().all(|f| f(&vector, txn)) {
+                continue;
+            }
+            vector.set_distance(vector.distance_to(query, graph.metric)?);
+            results.push(vector);
+            if results.len() > k {
+                results = results.take_inord(k);
+            }
+        }
+        Ok(results)
+    }
 
-        let ef = self.config.ef;
-        let curr_level = entry_point.get_level();
+    /// Levels the vector is stored at, i.e. level 0 and its top level
+    fn get_levels(&self, txn: &RoTxn, id: u128) -> Result<Vec<usize>, VectorError> {
+        let prefix = [VECTOR_PREFIX, &id.to_be_bytes()].concat();
+        self.vectors_db
+            .prefix_iter(txn, &prefix)?
+            .map(|result| {
+                let (key, _) = result?;
+                Ok(Self::level_from_key(key))
+            })
+            .collect()
+    }
 
-        for level in (1..=curr_level).rev() {
-            let mut nearest = self.search_level(
-                txn,
-                &query,
-                &mut entry_point,
-                1,
-                level,
-                match should_trickle {
-                    true => filter,
-                    false => None,
-                },
-            )?;
+    #[inline(always)]
+    fn level_from_key(key: &[u8]) -> usize {
+        let mut arr = [0u8; std::mem::size_of::<usize>()];
+        arr.copy_from_slice(&key[VECTOR_PREFIX.len() + 16..]);
+        usize::from_be_bytes(arr)
+    }
 
-            if let Some(closest) = nearest.pop() {
-                entry_point = closest;
-            }
+    /// Top level of every stored vector, by id
+    fn vector_levels(&self, txn: &RoTxn) -> Result<BTreeMap<u128, usize>, VectorError> {
+        let mut levels: BTreeMap<u128, usize> = BTreeMap::new();
+        for result in self.vectors_db.prefix_iter(txn, VECTOR_PREFIX)? {
+            let (key, _) = result?;
+            let mut arr = [0u8; 16];
+            arr.copy_from_slice(&key[VECTOR_PREFIX.len()..VECTOR_PREFIX.len() + 16]);
+            let level = levels.entry(u128::from_be_bytes(arr)).or_default();
+            *level = (*level).max(Self::level_from_key(key));
         }
+        Ok(levels)
+    }
 
-        let mut candidates = self.search_level(
-            txn,
-            &query,
-            &mut entry_point,
-            ef,
-            0,
-            match should_trickle {
-                true => filter,
-                false => None,
-            },
-        )?;
+    /// Id and top level of each vector of the vector type `label`, read from the level index
+    /// as the iterator advances, in the order of their levels
+    fn label_vectors<'t>(
+        &self,
+        txn: &'t RoTxn,
+        label: &str,
+    ) -> Result<impl Iterator<Item = Result<(u128, usize), VectorError>> + 't, VectorError> {
+        let prefix = Self::level_prefix(label);
+        let prefix_len = prefix.len();
+        Ok(self
+            .vectors_db
+            .lazily_decode_data()
+            .prefix_iter(txn, &prefix)?
+            .map(move |result| {
+                let (key, _) = result?;
+                Self::from_level_key(&key[prefix_len..])
+            }))
+    }
 
-        let results =
-            candidates.to_vec_with_filter::<F, true>(k, filter, label, txn, self.vector_data_db)?;
+    /// Top level of every vector of the vector type `label`, by id
+    fn label_levels(&self, txn: &RoTxn, label: &str) -> Result<BTreeMap<u128, usize>, VectorError> {
+        self.label_vectors(txn, label)?.collect()
+    }
 
-        debug_println!("vector search found {} results", results.len());
-        Ok(results)
+    /// Removes the edges, entry point and HNSW params of the graph
+    fn clear_graph(&self, txn: &mut RwTxn, graph: LabelGraph) -> Result<(), VectorError> {
+        let prefix = [graph.namespace().as_bytes(), &[0]].concat();
+        let keys: Vec<Vec<u8>> = self
+            .edges_db
+            .prefix_iter(txn, &prefix)?
+            .map(|result| result.map(|(key, _)| key.to_vec()))
+            .collect::<Result<_, _>>()?;
+        for key in keys {
+            self.edges_db.delete(txn, &key)?;
+        }
+        self.vectors_db
+            .delete(txn, &Self::entry_point_key(&graph.namespace()))?;
+        self.vectors_db
+            .delete(txn, &[CONFIG_PREFIX, graph.namespace().as_bytes()].concat())?;
+        Ok(())
     }
 
-    fn insert<F>(
+    /// Makes a vector of the label on the highest remaining level the entry point,
+    /// or removes the entry point if the label has no vectors left
+    fn replace_entry_point(&self, txn: &mut RwTxn, graph: LabelGraph) -> Result<(), VectorError> {
+        // the level index is ordered by level, so its last key is on the highest one
+        let prefix = Self::level_prefix(graph.label);
+        let entry = match self
+            .vectors_db
+            .lazily_decode_data()
+            .rev_prefix_iter(txn, &prefix)?
+            .next()
+        {
+            Some(result) => Some(Self::from_level_key(&result?.0[prefix.len()..])?),
+            None => None,
+        };
+
+        match entry {
+            Some((id, level)) => {
+                let entry = self.get_graph_vector(txn, graph, id, level)?;
+                self.set_entry_point(txn, graph, &entry)
+            }
+            None => {
+                self.vectors_db
+                    .delete(txn, &Self::entry_point_key(&graph.namespace()))?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Links a stored vector into the graph of its label.
+    fn link<F>(
         &self,
         txn: &mut RwTxn,
-        data: &[f64],
-        fields: Option<Vec<(String, Value)>>,
-    ) -> Result<HVector, VectorError>
+        graph: LabelGraph,
+        query: &mut HVector,
+    ) -> Result<(), VectorError>
     where
         F: Fn(&HVector, &RoTxn) -> bool,
     {
-        let new_level = self.get_new_level();
-
-        let mut query = HVector::from_slice(0, data.to_vec());
-        self.put_vector(txn, &query)?;
-        query.level = new_level;
-        if new_level > 0 {
-            self.put_vector(txn, &query)?;
-        }
-
-        let entry_point = match self.get_entry_point(txn) {
+        let new_level = query.get_level();
+        let entry_point = match self.get_entry_point(txn, graph) {
             Ok(ep) => ep,
             Err(_) => {
-                self.set_entry_point(txn, &query)?;
+                self.set_entry_point(txn, graph, query)?;
                 query.set_distance(0.0);
-
-                if let Some(fields) = fields {
-                    self.vector_data_db.put(
-                        txn,
-                        &query.get_id().to_be_bytes(),
-                        &bincode::serialize(&fields)?,
-                    )?;
-                }
-                return Ok(query);
+                return Ok(());
             }
         };
 
         let l = entry_point.get_level();
         let mut curr_ep = entry_point;
         for level in (new_level + 1..=l).rev() {
-            let nearest = self.search_level::<F>(txn, &query, &mut curr_ep, 1, level, None)?;
+            let nearest =
+                self.search_level::<F>(txn, graph, query, &mut curr_ep, 1, level, None)?;
             curr_ep = nearest
                 .peek()
                 .ok_or(VectorError::VectorCoreError(
@@ -511,35 +1484,178 @@ impl HNSW for VectorCore {
         for level in (0..=l.min(new_level)).rev() {
             let nearest = self.search_level::<F>(
                 txn,
-                &query,
+                graph,
+                query,
                 &mut curr_ep,
-                self.config.ef_construct,
+                graph.config.ef_construct,
                 level,
                 None,
             )?;
             curr_ep = nearest.peek().unwrap().clone();
 
-            let neighbors = self.select_neighbors::<F>(txn, &query, nearest, level, true, None)?;
-            self.set_neighbours(txn, query.get_id(), &neighbors, level)?;
+            let neighbors =
+                self.select_neighbors::<F>(txn, graph, query, nearest, level, true, None)?;
+            self.set_neighbours(txn, graph, query.get_id(), &neighbors, level)?;
 
             for e in neighbors {
                 let id = e.get_id();
-                let e_conns = BinaryHeap::from(self.get_neighbors::<F>(txn, id, level, None)?);
+                let e_conns = BinaryHeap::from(self.get_neighbors(txn, graph, id, level)?);
                 let e_new_conn =
-                    self.select_neighbors::<F>(txn, &query, e_conns, level, true, None)?;
-                self.set_neighbours(txn, id, &e_new_conn, level)?;
+                    self.select_neighbors::<F>(txn, graph, query, e_conns, level, true, None)?;
+                self.set_neighbours(txn, graph, id, &e_new_conn, level)?;
             }
         }
 
         if new_level > l {
-            self.set_entry_point(txn, &query)?;
+            self.set_entry_point(txn, graph, query)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the vector's links at `level` and reconnects each of its neighbors,
+    /// choosing their new links from their remaining neighbors and the removed vector's neighbors.
+    ///
+    /// Links pointing to the vector from vectors it had itself dropped are not tracked;
+    /// they are skipped by `get_neighbors` and removed when those vectors' links are next set.
+    fn unlink(
+        &self,
+        txn: &mut RwTxn,
+        graph: LabelGraph,
+        id: u128,
+        level: usize,
+    ) -> Result<(), VectorError> {
+        let neighbors = self.get_neighbors(txn, graph, id, level)?;
+
+        let namespace = graph.namespace();
+        let prefix = Self::out_edges_key(&namespace, id, level, None);
+        let out_keys: Vec<Vec<u8>> = self
+            .edges_db
+            .prefix_iter(txn, &prefix)?
+            .map(|result| result.map(|(key, _)| key.to_vec()))
+            .collect::<Result<_, _>>()?;
+        for key in out_keys {
+            self.edges_db.delete(txn, &key)?;
+        }
+
+        for neighbor in &neighbors {
+            let neighbor_id = neighbor.get_id();
+            self.edges_db.delete(
+                txn,
+                &Self::out_edges_key(&namespace, neighbor_id, level, Some(id)),
+            )?;
+
+            let mut seen: HashSet<u128> = HashSet::from([id, neighbor_id]);
+            let cands = self
+                .get_neighbors(txn, graph, neighbor_id, level)?
+                .into_iter()
+                .chain(neighbors.iter().cloned())
+                .filter(|cand| seen.insert(cand.get_id()))
+                .map(|mut cand| -> Result<HVector, VectorError> {
+                    cand.set_distance(cand.distance_to(neighbor, graph.metric)?);
+                    Ok(cand)
+                })
+                .collect::<Result<BinaryHeap<_>, _>>()?;
+
+            let new_conns =
+                self.select_neighbors::<Filter>(txn, graph, neighbor, cands, level, false, None)?;
+            self.set_neighbours(txn, graph, neighbor_id, &new_conns, level)?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a vector of the label's graph in the label's encoding, without its properties
+    fn get_graph_vector(
+        &self,
+        txn: &RoTxn,
+        graph: LabelGraph,
+        id: u128,
+        level: usize,
+    ) -> Result<HVector, VectorError> {
+        match self.vectors_db.get(txn, &Self::vector_key(id, level))? {
+            Some(bytes) => Ok(HVector::from_encoded(
+                id,
+                level,
+                EncodedVector::from_bytes(graph.encoding, bytes)?,
+            )),
+            None if level > 0 => self.get_graph_vector(txn, graph, id, 0),
+            None => Err(VectorError::VectorNotFound(id.to_string())),
+        }
+    }
+
+    /// Fills the data of an encoded vector, from the int8 copy for binary vectors
+    fn decode(
+        &self,
+        txn: &RoTxn,
+        graph: LabelGraph,
+        vector: &mut HVector,
+    ) -> Result<(), VectorError> {
+        let Some(encoded) = &vector.encoded else {
+            return Ok(());
+        };
+        vector.data = match graph.encoding {
+            VectorEncoding::Binary => {
+                match self.vectors_db.get(txn, &Self::rescore_key(vector.id))? {
+                    Some(bytes) => EncodedVector::from_bytes(VectorEncoding::Int8, bytes)?.decode(),
+                    None => encoded.decode(),
+                }
+            }
+            _ => encoded.decode(),
+        };
+        Ok(())
+    }
+
+    /// Label of a vector from its fields, as in `HVector::label`
+    fn label_of(fields: &Option<Vec<(String, Value)>>) -> &str {
+        match fields
+            .iter()
+            .flatten()
+            .find_map(|(key, value)| (key == "label").then_some(value))
+        {
+            Some(Value::String(label)) => label.as_str(),
+            _ => DEFAULT_LABEL,
+        }
+    }
+
+    /// Inserts a vector into the label's graph and stores its fields
+    fn insert_vector<F>(
+        &self,
+        txn: &mut RwTxn,
+        graph: LabelGraph,
+        data: &[f64],
+        fields: Option<Vec<(String, Value)>>,
+    ) -> Result<HVector, VectorError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+    {
+        graph.check_dimension(data)?;
+        let new_level = self.get_new_level(graph);
+
+        let mut query = HVector::from_slice(0, data.to_vec());
+        query.encode(graph.encoding);
+        self.put_vector(txn, &query)?;
+        if graph.encoding == VectorEncoding::Binary {
+            let copy = VectorEncoding::Int8.encode(data);
+            self.vectors_db
+                .put(txn, &Self::rescore_key(query.get_id()), &copy.to_bytes())?;
+        }
+        query.level = new_level;
+        if new_level > 0 {
+            self.put_vector(txn, &query)?;
         }
 
-        if let Some(fields) = fields {
+        self.link::<F>(txn, graph, &mut query)?;
+        self.vectors_db.put(
+            txn,
+            &Self::level_key(graph.label, new_level, query.get_id()),
+            &[],
+        )?;
+
+        if let Some(fields) = &fields {
             self.vector_data_db.put(
                 txn,
                 &query.get_id().to_be_bytes(),
-                &bincode::serialize(&fields)?,
+                &bincode::serialize(fields)?,
             )?;
         }
 
@@ -547,33 +1663,396 @@ impl HNSW for VectorCore {
         Ok(query)
     }
 
-    fn delete(&self, txn: &mut RwTxn, id: u128) -> Result<(), VectorError> {
-        let properties: Option<HashMap<String, Value>> =
-            match self.vector_data_db.get(txn, &id.to_be_bytes())? {
+    /// Ids of the vectors of a multi-vector document, empty if `id` is not a document
+    fn document_vectors(&self, txn: &RoTxn, id: u128) -> Result<Vec<u128>, VectorError> {
+        let prefix = Self::document_key(id, None);
+        self.vectors_db
+            .lazily_decode_data()
+            .prefix_iter(txn, &prefix)?
+            .map(|result| {
+                let (key, _) = result?;
+                let vector: [u8; 16] = key[prefix.len()..]
+                    .try_into()
+                    .map_err(|_| VectorError::InvalidVectorData)?;
+                Ok(u128::from_be_bytes(vector))
+            })
+            .collect()
+    }
+
+    /// Document the vector belongs to, if it is one of the vectors of a multi-vector document
+    fn parent(&self, txn: &RoTxn, id: u128) -> Result<Option<u128>, VectorError> {
+        match self.vectors_db.get(txn, &Self::parent_key(id))? {
+            Some(bytes) => Ok(Some(u128::from_be_bytes(
+                bytes
+                    .try_into()
+                    .map_err(|_| VectorError::InvalidVectorData)?,
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads a multi-vector document, which has properties but no data of its own
+    fn get_document(&self, txn: &RoTxn, id: u128, with_data: bool) -> Result<HVector, VectorError> {
+        let properties = match with_data {
+            true => match self.vector_data_db.get(txn, &id.to_be_bytes())? {
                 Some(bytes) => Some(bincode::deserialize(bytes).map_err(VectorError::from)?),
                 None => None,
-            };
+            },
+            false => None,
+        };
+        Ok(HVector {
+            id,
+            level: 0,
+            distance: None,
+            data: Vec::new(),
+            properties,
+            encoded: None,
+        })
+    }
+
+    pub fn num_inserted_vectors(&self, txn: &RoTxn) -> Result<u64, VectorError> {
+        Ok(self.vectors_db.len(txn)?)
+    }
+}
+
+impl HNSW for VectorCore {
+    #[inline(always)]
+    fn get_vector(
+        &self,
+        txn: &RoTxn,
+        id: u128,
+        level: usize,
+        with_data: bool,
+    ) -> Result<HVector, VectorError> {
+        let key = Self::vector_key(id, level);
+        match self.vectors_db.get(txn, key.as_ref())? {
+            Some(bytes) => {
+                // the encoding of the vector depends on its label
+                let properties: Option<HashMap<String, Value>> =
+                    match with_data || !self.encodings.is_empty() {
+                        true => match self.vector_data_db.get(txn, &id.to_be_bytes())? {
+                            Some(bytes) => {
+                                Some(bincode::deserialize(bytes).map_err(VectorError::from)?)
+                            }
+                            None => None,
+                        },
+                        false => None,
+                    };
+                let label = match properties.as_ref().and_then(|props| props.get("label")) {
+                    Some(Value::String(label)) => label.as_str(),
+                    _ => DEFAULT_LABEL,
+                };
+                let graph = self.graph(txn, label)?;
+                let encoded = EncodedVector::from_bytes(graph.encoding, bytes)?;
+                let mut vector = HVector::from_encoded(id, level, encoded);
+                self.decode(txn, graph, &mut vector)?;
+                if with_data {
+                    vector.properties = properties;
+                }
+                Ok(vector)
+            }
+            None if level > 0 => self.get_vector(txn, id, 0, with_data),
+            None if !self.document_vectors(txn, id)?.is_empty() => {
+                self.get_document(txn, id, with_data)
+            }
+            None => Err(VectorError::VectorNotFound(id.to_string())),
+        }
+    }
+
+    fn search<F>(
+        &self,
+        txn: &RoTxn,
+        query: &[f64],
+        k: usize,
+        label: &str,
+        filter: Option<&[F]>,
+        should_trickle: bool,
+        params: SearchParams,
+    ) -> Result<Vec<HVector>, VectorError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+    {
+        let graph = self.graph(txn, label)?;
+        graph.check_dimension(query)?;
+        let query_data = query;
+        let mut query = HVector::from_slice(0, query.to_vec());
+        query.encode(graph.encoding);
 
-        debug_println!("properties: {properties:?}");
-        if let Some(mut properties) = properties {
-            if let Some(Value::Boolean(is_deleted)) = properties.get("is_deleted")
-                && *is_deleted
+        let mut entry_point = self.get_entry_point(txn, graph)?;
+
+        let ef = params.ef.unwrap_or(graph.config.ef).max(k);
+        let curr_level = entry_point.get_level();
+
+        // the upper levels only lead to the closest entry point, whether it passes the filter or not
+        for level in (1..=curr_level).rev() {
+            let mut nearest =
+                self.search_level::<F>(txn, graph, &query, &mut entry_point, 1, level, None)?;
+
+            if let Some(closest) = nearest.pop() {
+                entry_point = closest;
+            }
+        }
+
+        let prefilter = filter.filter(|_| should_trickle);
+        let mut candidates = match prefilter {
+            Some(filter)
+                if self.filter_selectivity(txn, graph, &entry_point, filter)?
+                    < PREFILTER_BRUTE_FORCE_SELECTIVITY =>
             {
-                return Err(VectorError::VectorAlreadyDeleted(id.to_string()));
+                self.brute_force_search(txn, graph, &query, ef, filter)?
             }
+            _ => self.search_level(txn, graph, &query, &mut entry_point, ef, 0, prefilter)?,
+        };
+        if let Some(n) = params.candidates {
+            candidates = candidates.take_inord(n.max(k));
+        }
 
-            properties.insert("is_deleted".to_string(), Value::Boolean(true));
-            debug_println!("properties: {properties:?}");
+        if graph.encoding != VectorEncoding::F64 {
+            candidates = candidates
+                .into_iter()
+                .map(|mut candidate| {
+                    self.decode(txn, graph, &mut candidate)?;
+                    if graph.encoding == VectorEncoding::Binary {
+                        candidate.set_distance(graph.metric.distance(query_data, &candidate.data)?);
+                    }
+                    Ok(candidate)
+                })
+                .collect::<Result<_, VectorError>>()?;
+        }
 
-            self.vector_data_db
-                .put(txn, &id.to_be_bytes(), &bincode::serialize(&properties)?)?;
-        } else {
-            let mut n_properties: HashMap<String, Value> = HashMap::new();
-            n_properties.insert("is_deleted".to_string(), Value::Boolean(true));
-            debug_println!("properties: {n_properties:?}");
+        let results =
+            candidates.to_vec_with_filter::<F, true>(k, filter, label, txn, self.vector_data_db)?;
+
+        debug_println!("vector search found {} results", results.len());
+        Ok(results)
+    }
 
+    fn search_radius<F>(
+        &self,
+        txn: &RoTxn,
+        query: &[f64],
+        radius: f64,
+        label: &str,
+        filter: Option<&[F]>,
+        params: SearchParams,
+    ) -> Result<Vec<HVector>, VectorError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+    {
+        let graph = self.graph(txn, label)?;
+        graph.check_dimension(query)?;
+        let query_data = query;
+        let mut query = HVector::from_slice(0, query.to_vec());
+        query.encode(graph.encoding);
+
+        let mut entry_point = self.get_entry_point(txn, graph)?;
+        for level in (1..=entry_point.get_level()).rev() {
+            let mut nearest =
+                self.search_level::<F>(txn, graph, &query, &mut entry_point, 1, level, None)?;
+
+            if let Some(closest) = nearest.pop() {
+                entry_point = closest;
+            }
+        }
+
+        // every vector within the radius is kept, so filtering them afterwards loses none
+        let slack = params.ef.unwrap_or(graph.config.ef);
+        let mut candidates =
+            self.search_level_radius(txn, graph, query_data, entry_point, radius, slack)?;
+        if let Some(n) = params.candidates {
+            candidates = candidates.take_inord(n);
+        }
+
+        let found = candidates.len();
+        let results = candidates.to_vec_with_filter::<F, true>(
+            found,
+            filter,
+            label,
+            txn,
+            self.vector_data_db,
+        )?;
+
+        debug_println!("vector radius search found {} results", results.len());
+        Ok(results)
+    }
+
+    fn search_multi<F>(
+        &self,
+        txn: &RoTxn,
+        queries: &[Vec<f64>],
+        k: usize,
+        label: &str,
+        filter: Option<&[F]>,
+        params: SearchParams,
+    ) -> Result<Vec<HVector>, VectorError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+    {
+        let graph = self.graph(txn, label)?;
+        let candidates = params.candidates.unwrap_or(MAXSIM_CANDIDATES).max(k);
+        let token_params = SearchParams::new(params.ef, None);
+
+        // documents with a vector among the nearest of any query vector
+        let mut documents = HashSet::new();
+        for query in queries {
+            for vector in
+                self.search::<F>(txn, query, candidates, label, None, false, token_params)?
+            {
+                if let Some(document) = self.parent(txn, vector.get_id())? {
+                    documents.insert(document);
+                }
+            }
+        }
+
+        // MaxSim sums the similarity of each query vector to its closest vector of the document,
+        // so summing the distances to the closest vectors ranks the documents the same way
+        let mut scored = BinaryHeap::with_capacity(documents.len());
+        for id in documents {
+            let vectors = self
+                .document_vectors(txn, id)?
+                .into_iter()
+                .map(|vector| {
+                    let mut vector = self.get_graph_vector(txn, graph, vector, 0)?;
+                    self.decode(txn, graph, &mut vector)?;
+                    Ok(vector.data)
+                })
+                .collect::<Result<Vec<_>, VectorError>>()?;
+
+            let mut score = 0.0;
+            for query in queries {
+                let mut closest = f64::INFINITY;
+                for data in &vectors {
+                    closest = closest.min(graph.metric.distance(query, data)?);
+                }
+                score += closest;
+            }
+
+            let mut document = self.get_document(txn, id, false)?;
+            document.set_distance(score);
+            scored.push(document);
+        }
+
+        let results =
+            scored.to_vec_with_filter::<F, true>(k, filter, label, txn, self.vector_data_db)?;
+
+        debug_println!("multi-vector search found {} documents", results.len());
+        Ok(results)
+    }
+
+    fn insert<F>(
+        &self,
+        txn: &mut RwTxn,
+        data: &[f64],
+        fields: Option<Vec<(String, Value)>>,
+    ) -> Result<HVector, VectorError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+    {
+        let label = Self::label_of(&fields).to_string();
+        if self.is_multi(&label) {
+            return Err(VectorError::VectorCoreError(format!(
+                "vector type {label} holds multi-vector documents and cannot take a single vector"
+            )));
+        }
+        let graph = self.graph(txn, &label)?;
+        self.insert_vector::<F>(txn, graph, data, fields)
+    }
+
+    fn insert_multi<F>(
+        &self,
+        txn: &mut RwTxn,
+        vectors: &[Vec<f64>],
+        fields: Option<Vec<(String, Value)>>,
+    ) -> Result<HVector, VectorError>
+    where
+        F: Fn(&HVector, &RoTxn) -> bool,
+    {
+        let label = Self::label_of(&fields).to_string();
+        if !self.is_multi(&label) {
+            return Err(VectorError::VectorCoreError(format!(
+                "vector type {label} holds single vectors and cannot take a multi-vector document"
+            )));
+        }
+        if vectors.is_empty() {
+            return Err(VectorError::VectorCoreError(format!(
+                "a document of vector type {label} needs at least one vector"
+            )));
+        }
+        let graph = self.graph(txn, &label)?;
+        for data in vectors {
+            graph.check_dimension(data)?;
+        }
+
+        let document = HVector::from_slice(0, Vec::new());
+        let document_id = document.get_id().to_be_bytes();
+        // the vectors only carry what the graph needs, the document holds the properties
+        let vector_fields = vec![
+            ("label".to_string(), Value::String(label.clone())),
+            ("is_deleted".to_string(), Value::Boolean(false)),
+        ];
+        for data in vectors {
+            let vector = self.insert_vector::<F>(txn, graph, data, Some(vector_fields.clone()))?;
+            self.vectors_db.put(
+                txn,
+                &Self::document_key(document.get_id(), Some(vector.get_id())),
+                &[],
+            )?;
+            self.vectors_db
+                .put(txn, &Self::parent_key(vector.get_id()), &document_id)?;
+        }
+
+        if let Some(fields) = &fields {
             self.vector_data_db
-                .put(txn, &id.to_be_bytes(), &bincode::serialize(&n_properties)?)?;
+                .put(txn, &document_id, &bincode::serialize(fields)?)?;
+        }
+
+        debug_println!(
+            "document inserted with id {} and {} vectors",
+            document.get_id(),
+            vectors.len()
+        );
+        Ok(document)
+    }
+
+    fn delete(&self, txn: &mut RwTxn, id: u128) -> Result<(), VectorError> {
+        let levels = self.get_levels(txn, id)?;
+        let top_level = match levels.iter().max() {
+            Some(level) => *level,
+            None => {
+                let vectors = self.document_vectors(txn, id)?;
+                if vectors.is_empty() {
+                    return Err(VectorError::VectorNotFound(id.to_string()));
+                }
+                for vector in vectors {
+                    self.delete(txn, vector)?;
+                }
+                self.vector_data_db.delete(txn, &id.to_be_bytes())?;
+                debug_println!("document deleted with id {}", &id);
+                return Ok(());
+            }
+        };
+        let label = self.get_vector(txn, id, 0, true)?.label().to_string();
+        let graph = self.graph(txn, &label)?;
+
+        for level in 0..=top_level {
+            self.unlink(txn, graph, id, level)?;
+        }
+
+        for level in levels {
+            self.vectors_db.delete(txn, &Self::vector_key(id, level))?;
+        }
+        self.vectors_db
+            .delete(txn, &Self::level_key(&label, top_level, id))?;
+        self.vectors_db.delete(txn, &Self::rescore_key(id))?;
+        self.vector_data_db.delete(txn, &id.to_be_bytes())?;
+        if let Some(document) = self.parent(txn, id)? {
+            self.vectors_db.delete(txn, &Self::parent_key(id))?;
+            self.vectors_db
+                .delete(txn, &Self::document_key(document, Some(id)))?;
+        }
+
+        if self.get_entry_point_id(txn, graph)? == Some(id) {
+            self.replace_entry_point(txn, graph)?;
         }
 
         debug_println!("vector deleted with id {}", &id);
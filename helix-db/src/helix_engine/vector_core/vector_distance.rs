@@ -1,30 +1,121 @@
 use crate::helix_engine::{types::VectorError, vector_core::vector::HVector};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
 
 pub const MAX_DISTANCE: f64 = 2.0;
 pub const ORTHOGONAL: f64 = 1.0;
 pub const MIN_DISTANCE: f64 = 0.0;
 
+/// The distance metric of a vector type, declared with `V::Name(metric: ...)` in the schema.
+///
+/// Smaller distances are always closer, whichever the metric.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DistanceMetric {
+    /// `1 - cosine similarity`, between 0 and 2
+    #[default]
+    Cosine,
+    /// Euclidean (L2) distance
+    Euclidean,
+    /// `1 - dot product`, equal to the cosine distance for normalized vectors
+    InnerProduct,
+    /// Number of dimensions whose sign differs, for binary vectors
+    Hamming,
+}
+
+impl DistanceMetric {
+    pub const ALL: [DistanceMetric; 4] = [
+        DistanceMetric::Cosine,
+        DistanceMetric::Euclidean,
+        DistanceMetric::InnerProduct,
+        DistanceMetric::Hamming,
+    ];
+
+    /// Name of the metric in the schema
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DistanceMetric::Cosine => "cosine",
+            DistanceMetric::Euclidean => "euclidean",
+            DistanceMetric::InnerProduct => "inner_product",
+            DistanceMetric::Hamming => "hamming",
+        }
+    }
+
+    #[inline(always)]
+    pub fn distance(&self, from: &[f64], to: &[f64]) -> Result<f64, VectorError> {
+        if from.len() != to.len() {
+            return Err(VectorError::InvalidVectorLength);
+        }
+        match self {
+            DistanceMetric::Cosine => cosine_similarity(from, to).map(|sim| 1.0 - sim),
+            DistanceMetric::Euclidean => Ok(euclidean_distance(from, to)),
+            DistanceMetric::InnerProduct => Ok(1.0 - dot_product(from, to)),
+            DistanceMetric::Hamming => Ok(hamming_distance(from, to)),
+        }
+    }
+}
+
+impl fmt::Display for DistanceMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for DistanceMetric {
+    type Err = VectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DistanceMetric::ALL
+            .into_iter()
+            .find(|metric| metric.as_str() == s)
+            .ok_or_else(|| VectorError::VectorCoreError(format!("unknown distance metric {s}")))
+    }
+}
+
 pub trait DistanceCalc {
-    fn distance(from: &HVector, to: &HVector) -> Result<f64, VectorError>;
+    fn distance(from: &HVector, to: &HVector, metric: DistanceMetric) -> Result<f64, VectorError>;
 }
 impl DistanceCalc for HVector {
-    /// Calculates the distance between two vectors.
+    /// Calculates the distance between two vectors under `metric`,
+    /// on their encoded values when both are encoded.
     ///
-    /// It normalizes the distance to be between 0 and 2.
+    /// The cosine distance is normalized to be between 0 and 2.
     ///
     /// - 1.0 (most similar) → Distance 0.0 (closest)
     /// - 0.0 (orthogonal) → Distance 1.0
     /// - -1.0 (most dissimilar) → Distance 2.0 (furthest)
     #[inline(always)]
-    #[cfg(feature = "cosine")]
-    fn distance(from: &HVector, to: &HVector) -> Result<f64, VectorError> {
-        cosine_similarity(&from.data, &to.data).map(|sim| 1.0 - sim)
+    fn distance(from: &HVector, to: &HVector, metric: DistanceMetric) -> Result<f64, VectorError> {
+        match (&from.encoded, &to.encoded) {
+            (Some(from), Some(to)) => from.distance(to, metric),
+            _ => metric.distance(&from.data, &to.data),
+        }
     }
 }
 
+#[inline]
+pub fn dot_product(from: &[f64], to: &[f64]) -> f64 {
+    from.iter().zip(to).map(|(a, b)| a * b).sum()
+}
+
+#[inline]
+pub fn euclidean_distance(from: &[f64], to: &[f64]) -> f64 {
+    from.iter()
+        .zip(to)
+        .map(|(a, b)| (a - b) * (a - b))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Treats every positive value as a set bit.
+#[inline]
+pub fn hamming_distance(from: &[f64], to: &[f64]) -> f64 {
+    from.iter()
+        .zip(to)
+        .filter(|(a, b)| (**a > 0.0) != (**b > 0.0))
+        .count() as f64
+}
 
 #[inline]
-#[cfg(feature = "cosine")]
 pub fn cosine_similarity(from: &[f64], to: &[f64]) -> Result<f64, VectorError> {
     let len = from.len();
     let other_len = to.len();
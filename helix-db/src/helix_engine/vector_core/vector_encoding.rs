@@ -0,0 +1,314 @@
+use crate::helix_engine::{types::VectorError, vector_core::vector_distance::DistanceMetric};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// How the vectors of a vector type are stored, declared with `V::Name(encoding: ...)` in the schema.
+///
+/// The smaller encodings trade precision for size, and distances are computed
+/// on the encoded values without decoding them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VectorEncoding {
+    /// Eight bytes per dimension, exactly as inserted
+    #[default]
+    F64,
+    /// Four bytes per dimension, the precision embedding models produce
+    F32,
+    /// One byte per dimension, scaled by the largest absolute value of the vector
+    Int8,
+    /// One bit per dimension, the sign of the value.
+    ///
+    /// Searches compare bits and rescore the closest candidates with an int8 copy of each vector,
+    /// so a binary vector takes slightly more space than an int8 one, for faster graph walks.
+    Binary,
+}
+
+impl VectorEncoding {
+    pub const ALL: [VectorEncoding; 4] = [
+        VectorEncoding::F64,
+        VectorEncoding::F32,
+        VectorEncoding::Int8,
+        VectorEncoding::Binary,
+    ];
+
+    /// Name of the encoding in the schema
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VectorEncoding::F64 => "f64",
+            VectorEncoding::F32 => "f32",
+            VectorEncoding::Int8 => "int8",
+            VectorEncoding::Binary => "binary",
+        }
+    }
+
+    pub fn encode(&self, data: &[f64]) -> EncodedVector {
+        match self {
+            VectorEncoding::F64 => EncodedVector::F64(data.to_vec()),
+            VectorEncoding::F32 => EncodedVector::F32(data.iter().map(|v| *v as f32).collect()),
+            VectorEncoding::Int8 => {
+                let max = data.iter().fold(0.0f64, |max, v| max.max(v.abs()));
+                let scale = if max == 0.0 { 1.0 } else { max / 127.0 };
+                EncodedVector::Int8 {
+                    scale: scale as f32,
+                    codes: data
+                        .iter()
+                        .map(|v| (v / scale).round().clamp(-127.0, 127.0) as i8)
+                        .collect(),
+                }
+            }
+            VectorEncoding::Binary => {
+                let mut bits = vec![0u64; data.len().div_ceil(64)];
+                for (i, v) in data.iter().enumerate() {
+                    if *v > 0.0 {
+                        bits[i / 64] |= 1 << (i % 64);
+                    }
+                }
+                EncodedVector::Binary {
+                    len: data.len(),
+                    bits,
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for VectorEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for VectorEncoding {
+    type Err = VectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        VectorEncoding::ALL
+            .into_iter()
+            .find(|encoding| encoding.as_str() == s)
+            .ok_or_else(|| VectorError::VectorCoreError(format!("unknown vector encoding {s}")))
+    }
+}
+
+/// The values of a vector in the encoding of its vector type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncodedVector {
+    F64(Vec<f64>),
+    F32(Vec<f32>),
+    /// Each value is `scale * code`
+    Int8 {
+        scale: f32,
+        codes: Vec<i8>,
+    },
+    /// Bit `i` is set when value `i` is positive
+    Binary {
+        len: usize,
+        bits: Vec<u64>,
+    },
+}
+
+impl EncodedVector {
+    pub fn encoding(&self) -> VectorEncoding {
+        match self {
+            EncodedVector::F64(_) => VectorEncoding::F64,
+            EncodedVector::F32(_) => VectorEncoding::F32,
+            EncodedVector::Int8 { .. } => VectorEncoding::Int8,
+            EncodedVector::Binary { .. } => VectorEncoding::Binary,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            EncodedVector::F64(data) => data.len(),
+            EncodedVector::F32(data) => data.len(),
+            EncodedVector::Int8 { codes, .. } => codes.len(),
+            EncodedVector::Binary { len, .. } => *len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The values of the vector, binary vectors decode to `1.0` and `-1.0`
+    pub fn decode(&self) -> Vec<f64> {
+        match self {
+            EncodedVector::F64(data) => data.clone(),
+            EncodedVector::F32(data) => data.iter().map(|v| *v as f64).collect(),
+            EncodedVector::Int8 { scale, codes } => {
+                codes.iter().map(|c| *c as f64 * *scale as f64).collect()
+            }
+            EncodedVector::Binary { len, bits } => (0..*len)
+                .map(|i| match bits[i / 64] & (1 << (i % 64)) {
+                    0 => -1.0,
+                    _ => 1.0,
+                })
+                .collect(),
+        }
+    }
+
+    /// F64 vectors keep the layout vectors have always been stored with,
+    /// the other encodings are big endian values after a header:
+    /// the scale for int8 and the number of dimensions for binary.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            EncodedVector::F64(data) => data.iter().flat_map(|v| v.to_be_bytes()).collect(),
+            EncodedVector::F32(data) => data.iter().flat_map(|v| v.to_be_bytes()).collect(),
+            EncodedVector::Int8 { scale, codes } => scale
+                .to_be_bytes()
+                .into_iter()
+                .chain(codes.iter().map(|c| *c as u8))
+                .collect(),
+            EncodedVector::Binary { len, bits } => (*len as u32)
+                .to_be_bytes()
+                .into_iter()
+                .chain(bits.iter().flat_map(|b| b.to_be_bytes()))
+                .collect(),
+        }
+    }
+
+    pub fn from_bytes(encoding: VectorEncoding, bytes: &[u8]) -> Result<Self, VectorError> {
+        match encoding {
+            VectorEncoding::F64 => {
+                if !bytes.len().is_multiple_of(8) {
+                    return Err(VectorError::InvalidVectorData);
+                }
+                Ok(EncodedVector::F64(
+                    bytes
+                        .chunks_exact(8)
+                        .map(|chunk| f64::from_be_bytes(chunk.try_into().unwrap()))
+                        .collect(),
+                ))
+            }
+            VectorEncoding::F32 => {
+                if !bytes.len().is_multiple_of(4) {
+                    return Err(VectorError::InvalidVectorData);
+                }
+                Ok(EncodedVector::F32(
+                    bytes
+                        .chunks_exact(4)
+                        .map(|chunk| f32::from_be_bytes(chunk.try_into().unwrap()))
+                        .collect(),
+                ))
+            }
+            VectorEncoding::Int8 => {
+                let (scale, codes) = bytes
+                    .split_first_chunk::<4>()
+                    .ok_or(VectorError::InvalidVectorData)?;
+                Ok(EncodedVector::Int8 {
+                    scale: f32::from_be_bytes(*scale),
+                    codes: codes.iter().map(|c| *c as i8).collect(),
+                })
+            }
+            VectorEncoding::Binary => {
+                let (len, bits) = bytes
+                    .split_first_chunk::<4>()
+                    .ok_or(VectorError::InvalidVectorData)?;
+                let len = u32::from_be_bytes(*len) as usize;
+                if bits.len() != len.div_ceil(64) * 8 {
+                    return Err(VectorError::InvalidVectorData);
+                }
+                Ok(EncodedVector::Binary {
+                    len,
+                    bits: bits
+                        .chunks_exact(8)
+                        .map(|chunk| u64::from_be_bytes(chunk.try_into().unwrap()))
+                        .collect(),
+                })
+            }
+        }
+    }
+
+    /// Distance between two vectors of the same encoding, computed on the encoded values.
+    ///
+    /// Binary vectors are compared by the number of differing bits whatever the metric,
+    /// searches rescore them with the metric afterwards.
+    pub fn distance(
+        &self,
+        other: &EncodedVector,
+        metric: DistanceMetric,
+    ) -> Result<f64, VectorError> {
+        if self.len() != other.len() {
+            return Err(VectorError::InvalidVectorLength);
+        }
+        match (self, other) {
+            (EncodedVector::F64(from), EncodedVector::F64(to)) => metric.distance(from, to),
+            (EncodedVector::F32(from), EncodedVector::F32(to)) => {
+                Ok(f32_distance(from, to, metric))
+            }
+            (
+                EncodedVector::Int8 {
+                    scale: from_scale,
+                    codes: from,
+                },
+                EncodedVector::Int8 {
+                    scale: to_scale,
+                    codes: to,
+                },
+            ) => Ok(int8_distance(
+                (*from_scale as f64, from),
+                (*to_scale as f64, to),
+                metric,
+            )),
+            (EncodedVector::Binary { bits: from, .. }, EncodedVector::Binary { bits: to, .. }) => {
+                Ok(from
+                    .iter()
+                    .zip(to)
+                    .map(|(a, b)| (a ^ b).count_ones())
+                    .sum::<u32>() as f64)
+            }
+            _ => metric.distance(&self.decode(), &other.decode()),
+        }
+    }
+}
+
+#[inline]
+fn f32_distance(from: &[f32], to: &[f32], metric: DistanceMetric) -> f64 {
+    let (mut dot, mut mag_a, mut mag_b, mut l2) = (0.0f32, 0.0f32, 0.0f32, 0.0f32);
+    for (a, b) in from.iter().zip(to) {
+        dot += a * b;
+        mag_a += a * a;
+        mag_b += b * b;
+        l2 += (a - b) * (a - b);
+    }
+    match metric {
+        DistanceMetric::Cosine if mag_a == 0.0 || mag_b == 0.0 => 2.0,
+        DistanceMetric::Cosine => 1.0 - (dot / (mag_a.sqrt() * mag_b.sqrt())) as f64,
+        DistanceMetric::Euclidean => (l2 as f64).sqrt(),
+        DistanceMetric::InnerProduct => 1.0 - dot as f64,
+        DistanceMetric::Hamming => from
+            .iter()
+            .zip(to)
+            .filter(|(a, b)| (**a > 0.0) != (**b > 0.0))
+            .count() as f64,
+    }
+}
+
+/// Sums of products of the codes fit an `i32` for any realistic dimension
+#[inline]
+fn int8_distance(
+    (from_scale, from): (f64, &[i8]),
+    (to_scale, to): (f64, &[i8]),
+    metric: DistanceMetric,
+) -> f64 {
+    let (mut dot, mut mag_a, mut mag_b) = (0i32, 0i32, 0i32);
+    for (a, b) in from.iter().zip(to) {
+        let (a, b) = (*a as i32, *b as i32);
+        dot += a * b;
+        mag_a += a * a;
+        mag_b += b * b;
+    }
+    let dot = dot as f64 * from_scale * to_scale;
+    let mag_a = mag_a as f64 * from_scale * from_scale;
+    let mag_b = mag_b as f64 * to_scale * to_scale;
+    match metric {
+        DistanceMetric::Cosine if mag_a == 0.0 || mag_b == 0.0 => 2.0,
+        DistanceMetric::Cosine => 1.0 - dot / (mag_a.sqrt() * mag_b.sqrt()),
+        // |a - b|² = |a|² + |b|² - 2 a·b
+        DistanceMetric::Euclidean => (mag_a + mag_b - 2.0 * dot).max(0.0).sqrt(),
+        DistanceMetric::InnerProduct => 1.0 - dot,
+        DistanceMetric::Hamming => from
+            .iter()
+            .zip(to)
+            .filter(|(a, b)| (**a > 0) != (**b > 0))
+            .count() as f64,
+    }
+}
@@ -7,13 +7,14 @@ use serde::Deserialize;
 use sonic_rs::{JsonValueTrait, json};
 use tracing::info;
 
+use crate::helix_engine::traversal_core::ops::source::n_from_type::NFromType;
+use crate::helix_engine::traversal_core::traversal_value::TraversalValue;
 use crate::helix_engine::types::GraphError;
 use crate::helix_gateway::gateway::AppState;
 use crate::helix_gateway::router::router::{Handler, HandlerInput, HandlerSubmission};
 use crate::protocol::{self, request::RequestType};
 use crate::utils::filterable::Filterable;
 use crate::utils::id::ID;
-use crate::utils::items::Node;
 
 // get all nodes with a specific label
 // curl "http://localhost:PORT/nodes-by-label?label=YOUR_LABEL&limit=100"
@@ -82,38 +83,32 @@ pub fn nodes_by_label_inner(input: HandlerInput) -> Result<protocol::Response, G
     let mut nodes_json = Vec::new();
     let mut count = 0;
 
-    for result in db.nodes_db.iter(&txn)? {
-        let (id, node_data) = result?;
-        match Node::decode_node(node_data, id) {
-            Ok(node) => {
-                if node.label() == label {
-                    let id_str = ID::from(id).stringify();
-
-                    let mut node_json = json!({
-                        "id": id_str.clone(),
-                        "label": node.label(),
-                        "title": id_str
-                    });
-
-                    // Add node properties
-                    if let Some(properties) = &node.properties {
-                        for (key, value) in properties {
-                            node_json[key] = sonic_rs::to_value(&value.to_string())
-                                .unwrap_or_else(|_| sonic_rs::Value::from(""));
-                        }
-                    }
-
-                    nodes_json.push(node_json);
-                    count += 1;
-
-                    if let Some(limit_count) = limit {
-                        if count >= limit_count {
-                            break;
-                        }
-                    }
+    for result in NFromType::new(Arc::clone(&db), &txn, &label)? {
+        if let TraversalValue::Node(node) = result? {
+            let id_str = ID::from(node.id).stringify();
+
+            let mut node_json = json!({
+                "id": id_str.clone(),
+                "label": node.label(),
+                "title": id_str
+            });
+
+            // Add node properties
+            if let Some(properties) = &node.properties {
+                for (key, value) in properties {
+                    node_json[key] = sonic_rs::to_value(&value.to_string())
+                        .unwrap_or_else(|_| sonic_rs::Value::from(""));
+                }
+            }
+
+            nodes_json.push(node_json);
+            count += 1;
+
+            if let Some(limit_count) = limit {
+                if count >= limit_count {
+                    break;
                 }
             }
-            Err(_) => continue,
         }
     }
 
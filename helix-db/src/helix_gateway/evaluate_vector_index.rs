@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use axum::body::{Body, Bytes};
+use axum::extract::State;
+use axum::response::IntoResponse;
+use serde::Deserialize;
+use tracing::info;
+
+use crate::helix_engine::types::GraphError;
+use crate::helix_engine::vector_core::vector_core::SearchParams;
+use crate::helix_gateway::gateway::AppState;
+use crate::helix_gateway::router::router::{Handler, HandlerInput, HandlerSubmission};
+use crate::protocol::{self, request::RequestType};
+
+// measure the recall, latency and graph shape of the hnsw index of a vector type
+// curl -X POST http://localhost:PORT/evaluate-vector-index -d '{"label":"YOUR_VECTOR_TYPE","k":10}'
+// "queries": [[...], ...] searches the given vectors instead of "sample" stored vectors
+
+#[derive(Deserialize)]
+pub struct EvaluateVectorIndexParams {
+    label: String,
+    #[serde(default = "default_k")]
+    k: usize,
+    #[serde(default = "default_sample")]
+    sample: usize,
+    ef: Option<usize>,
+    queries: Option<Vec<Vec<f64>>>,
+}
+
+fn default_k() -> usize {
+    10
+}
+
+fn default_sample() -> usize {
+    100
+}
+
+pub async fn evaluate_vector_index_handler(
+    State(state): State<Arc<AppState>>,
+    body: Bytes,
+) -> axum::http::Response<Body> {
+    let req = protocol::request::Request {
+        name: "evaluate_vector_index".to_string(),
+        req_type: RequestType::Query,
+        body,
+        in_fmt: protocol::Format::default(),
+        out_fmt: protocol::Format::default(),
+    };
+
+    let res = state.worker_pool.process(req).await;
+
+    match res {
+        Ok(r) => r.into_response(),
+        Err(e) => {
+            info!(?e, "Got error");
+            e.into_response()
+        }
+    }
+}
+
+pub fn evaluate_vector_index_inner(input: HandlerInput) -> Result<protocol::Response, GraphError> {
+    let db = Arc::clone(&input.graph.storage);
+    let txn = db.graph_env.read_txn().map_err(GraphError::from)?;
+
+    let params = sonic_rs::from_slice::<EvaluateVectorIndexParams>(&input.request.body)
+        .map_err(|e| GraphError::New(format!("invalid evaluation parameters: {e}")))?;
+
+    let evaluation = db.vectors.evaluate(
+        &txn,
+        &params.label,
+        params.k,
+        params.queries,
+        params.sample,
+        SearchParams::new(params.ef, None),
+    )?;
+
+    Ok(protocol::Response {
+        body: sonic_rs::to_vec(&evaluation).map_err(|e| GraphError::New(e.to_string()))?,
+        fmt: Default::default(),
+    })
+}
+
+inventory::submit! {
+    HandlerSubmission(
+        Handler::new("evaluate_vector_index", evaluate_vector_index_inner)
+    )
+}
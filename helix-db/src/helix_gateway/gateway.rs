@@ -22,7 +22,9 @@ use crate::helix_gateway::builtin::node_by_id::node_details_handler;
 use crate::helix_gateway::builtin::node_connections::node_connections_handler;
 #[cfg(feature = "dev-instance")]
 use crate::helix_gateway::builtin::nodes_by_label::nodes_by_label_handler;
+use crate::helix_gateway::evaluate_vector_index::evaluate_vector_index_handler;
 use crate::helix_gateway::introspect_schema::introspect_schema_handler;
+use crate::helix_gateway::rebuild_vector_index::rebuild_vector_index_handler;
 use crate::helix_gateway::worker_pool::WorkerPool;
 use crate::protocol;
 use crate::{
@@ -125,7 +127,9 @@ impl HelixGateway {
 
         axum_app = axum_app
             .route("/{*path}", post(post_handler))
-            .route("/introspect", get(introspect_schema_handler));
+            .route("/introspect", get(introspect_schema_handler))
+            .route("/rebuild-vector-index", post(rebuild_vector_index_handler))
+            .route("/evaluate-vector-index", post(evaluate_vector_index_handler));
 
         #[cfg(feature = "dev-instance")]
         {
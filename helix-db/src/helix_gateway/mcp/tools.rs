@@ -20,7 +20,7 @@ use crate::{
             traversal_value::{Traversable, TraversalValue},
         },
         types::GraphError,
-        vector_core::vector::HVector,
+        vector_core::{vector::HVector, vector_core::SearchParams},
     },
     helix_gateway::{
         embedding_providers::embedding_providers::{EmbeddingModel, get_embedding_model},
@@ -367,10 +367,7 @@ impl<'a> McpTools<'a> for McpBackend {
     ) -> Result<Vec<TraversalValue>, GraphError> {
         let db = Arc::clone(&self.db);
 
-        let iter = NFromType {
-            iter: db.nodes_db.lazily_decode_data().iter(txn).unwrap(),
-            label: &node_type,
-        };
+        let iter = NFromType::new(db, txn, &node_type)?;
 
         let result = iter.collect::<Result<Vec<_>, _>>();
         debug_println!("result: {:?}", result);
@@ -385,10 +382,7 @@ impl<'a> McpTools<'a> for McpBackend {
     ) -> Result<Vec<TraversalValue>, GraphError> {
         let db = Arc::clone(&self.db);
 
-        let iter = EFromType {
-            iter: db.edges_db.lazily_decode_data().iter(txn).unwrap(),
-            label: &edge_type,
-        };
+        let iter = EFromType::new(db, txn, &edge_type)?;
 
         let result = iter.collect::<Result<Vec<_>, _>>();
         debug_println!("result: {:?}", result);
@@ -471,7 +465,13 @@ impl<'a> McpTools<'a> for McpBackend {
         let embedding = result?;
 
         let res = G::new(db, txn)
-            .search_v::<fn(&HVector, &RoTxn) -> bool, _>(&embedding, k.unwrap_or(5), &label, None)
+            .search_v::<fn(&HVector, &RoTxn) -> bool, _>(
+                &embedding,
+                k.unwrap_or(5),
+                &label,
+                None,
+                SearchParams::default(),
+            )
             .collect_to::<Vec<_>>();
 
         debug_println!("result: {res:?}");
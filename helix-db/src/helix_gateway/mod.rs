@@ -1,8 +1,10 @@
 #[cfg(feature = "dev-instance")]
 pub mod builtin;
 pub mod embedding_providers;
+pub mod evaluate_vector_index;
 pub mod gateway;
 pub mod introspect_schema;
 pub mod mcp;
+pub mod rebuild_vector_index;
 pub mod router;
 pub mod worker_pool;
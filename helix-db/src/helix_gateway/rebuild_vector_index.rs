@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::response::IntoResponse;
+use serde::{Deserialize, Serialize};
+use sonic_rs::json;
+use tracing::info;
+
+use crate::helix_engine::types::GraphError;
+use crate::helix_gateway::gateway::AppState;
+use crate::helix_gateway::router::router::{Handler, HandlerInput, HandlerSubmission};
+use crate::protocol::{self, request::RequestType};
+
+// rebuild the hnsw graph of a vector type with its current config
+// curl -X POST "http://localhost:PORT/rebuild-vector-index?label=YOUR_VECTOR_TYPE"
+// "&m=32&ef_construction=256&ef_search=128" rebuilds it with new params instead
+
+#[derive(Serialize, Deserialize)]
+pub struct RebuildVectorIndexQuery {
+    label: String,
+    m: Option<usize>,
+    ef_construction: Option<usize>,
+    ef_search: Option<usize>,
+}
+
+pub async fn rebuild_vector_index_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<RebuildVectorIndexQuery>,
+) -> axum::http::Response<Body> {
+    let mut req = protocol::request::Request {
+        name: "rebuild_vector_index".to_string(),
+        req_type: RequestType::Query,
+        body: axum::body::Bytes::new(),
+        in_fmt: protocol::Format::default(),
+        out_fmt: protocol::Format::default(),
+    };
+
+    if let Ok(params_json) = sonic_rs::to_vec(&params) {
+        req.body = axum::body::Bytes::from(params_json);
+    }
+
+    let res = state.worker_pool.process(req).await;
+
+    match res {
+        Ok(r) => r.into_response(),
+        Err(e) => {
+            info!(?e, "Got error");
+            e.into_response()
+        }
+    }
+}
+
+pub fn rebuild_vector_index_inner(input: HandlerInput) -> Result<protocol::Response, GraphError> {
+    let db = Arc::clone(&input.graph.storage);
+
+    let params = sonic_rs::from_slice::<RebuildVectorIndexQuery>(&input.request.body)
+        .map_err(|e| GraphError::New(format!("invalid rebuild parameters: {e}")))?;
+
+    // searches keep going through the current graph while the new one is built
+    let vectors = db.vectors.rebuild(
+        &db.graph_env,
+        &params.label,
+        params.m,
+        params.ef_construction,
+        params.ef_search,
+    )?;
+
+    let result = json!({
+        "label": params.label,
+        "vectors": vectors
+    });
+
+    Ok(protocol::Response {
+        body: sonic_rs::to_vec(&result).map_err(|e| GraphError::New(e.to_string()))?,
+        fmt: Default::default(),
+    })
+}
+
+inventory::submit! {
+    HandlerSubmission(
+        Handler::new("rebuild_vector_index", rebuild_vector_index_inner)
+    )
+}
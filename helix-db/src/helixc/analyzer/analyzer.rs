@@ -1,16 +1,19 @@
 //! Semantic analyzer for Helix‑QL.
-use crate::helixc::{
-    analyzer::{
-        diagnostic::Diagnostic,
-        methods::{
-            migration_validation::validate_migration,
-            query_validation::validate_query,
-            schema_methods::{SchemaVersionMap, build_field_lookups, check_schema},
+use crate::{
+    helix_engine::traversal_core::config::{SecondaryIndex, TextField, VectorIndex},
+    helixc::{
+        analyzer::{
+            diagnostic::Diagnostic,
+            methods::{
+                migration_validation::validate_migration,
+                query_validation::validate_query,
+                schema_methods::{SchemaVersionMap, build_field_lookups, check_schema},
+            },
+            types::Type,
         },
-        types::Type,
+        generator::Source as GeneratedSource,
+        parser::helix_parser::{EdgeSchema, ExpressionType, Field, Query, Source},
     },
-    generator::Source as GeneratedSource,
-    parser::helix_parser::{EdgeSchema, ExpressionType, Field, Query, Source},
 };
 use itertools::Itertools;
 use serde::Serialize;
@@ -38,13 +41,22 @@ pub(crate) struct Ctx<'a> {
     pub(super) node_fields: HashMap<&'a str, HashMap<&'a str, Cow<'a, Field>>>,
     pub(super) edge_fields: HashMap<&'a str, HashMap<&'a str, Cow<'a, Field>>>,
     pub(super) vector_fields: HashMap<&'a str, HashMap<&'a str, Cow<'a, Field>>>,
+    /// Declared dimensions of the vector types that have one
+    pub(super) vector_dimensions: HashMap<&'a str, usize>,
+    /// Vector types whose items are documents of several vectors
+    pub(super) multi_vectors: HashSet<&'a str>,
+    /// Vector types of sparse vectors, searched through an inverted index rather than HNSW
+    pub(super) sparse_vectors: HashSet<&'a str>,
     pub(super) all_schemas: SchemaVersionMap<'a>,
     pub(super) diagnostics: Vec<Diagnostic>,
     pub(super) output: GeneratedSource,
 }
 
 pub static INTROSPECTION_DATA: OnceLock<IntrospectionData> = OnceLock::new();
-pub static SECONDARY_INDICES: OnceLock<Vec<String>> = OnceLock::new();
+pub static SECONDARY_INDICES: OnceLock<Vec<SecondaryIndex>> = OnceLock::new();
+pub static EDGE_SECONDARY_INDICES: OnceLock<Vec<SecondaryIndex>> = OnceLock::new();
+pub static VECTOR_INDICES: OnceLock<Vec<VectorIndex>> = OnceLock::new();
+pub static TEXT_FIELDS: OnceLock<Vec<TextField>> = OnceLock::new();
 
 impl<'a> Ctx<'a> {
     pub(super) fn new(src: &'a Source) -> Self {
@@ -76,6 +88,26 @@ impl<'a> Ctx<'a> {
                 .iter()
                 .map(|e| (e.name.1.as_str(), e))
                 .collect(),
+            vector_dimensions: src
+                .get_latest_schema()
+                .vector_schemas
+                .iter()
+                .filter_map(|v| Some((v.name.as_str(), v.dimension.as_ref()?.1)))
+                .collect(),
+            multi_vectors: src
+                .get_latest_schema()
+                .vector_schemas
+                .iter()
+                .filter(|v| v.multi)
+                .map(|v| v.name.as_str())
+                .collect(),
+            sparse_vectors: src
+                .get_latest_schema()
+                .vector_schemas
+                .iter()
+                .filter(|v| v.sparse)
+                .map(|v| v.name.as_str())
+                .collect(),
             node_fields,
             edge_fields,
             vector_fields,
@@ -99,16 +131,107 @@ impl<'a> Ctx<'a> {
                             .fields
                             .iter()
                             .filter(|f| f.is_indexed())
-                            .map(|f| f.name.clone())
+                            .map(|f| match f.prefix.is_unique() {
+                                true => SecondaryIndex::unique(&schema.name.1, &f.name),
+                                false => SecondaryIndex::new(&schema.name.1, &f.name),
+                            })
+                            .chain(schema.composite_indices.iter().map(|index| {
+                                match index.unique {
+                                    true => SecondaryIndex::unique(&schema.name.1, &index.name()),
+                                    false => SecondaryIndex::new(&schema.name.1, &index.name()),
+                                }
+                            }))
                     })
                     .dedup()
                     .collect(),
             )
             .ok();
+
+        EDGE_SECONDARY_INDICES
+            .set(
+                src.get_latest_schema()
+                    .edge_schemas
+                    .iter()
+                    .flat_map(|schema| {
+                        schema
+                            .properties
+                            .iter()
+                            .flatten()
+                            .filter(|f| f.is_indexed())
+                            .map(|f| SecondaryIndex::new(&schema.name.1, &f.name))
+                    })
+                    .dedup()
+                    .collect(),
+            )
+            .ok();
+
+        // unknown metrics and encodings are reported by `check_schema`,
+        // and sparse vector types have no HNSW index
+        VECTOR_INDICES
+            .set(
+                src.get_latest_schema()
+                    .vector_schemas
+                    .iter()
+                    .filter(|schema| !schema.sparse)
+                    .map(|schema| {
+                        let metric = schema
+                            .metric
+                            .as_ref()
+                            .and_then(|(_, metric)| metric.parse().ok())
+                            .unwrap_or_default();
+                        let encoding = schema
+                            .encoding
+                            .as_ref()
+                            .and_then(|(_, encoding)| encoding.parse().ok())
+                            .unwrap_or_default();
+                        let mut index = VectorIndex::new(&schema.name, metric)
+                            .with_hnsw(schema.m, schema.ef_construction, schema.ef_search)
+                            .with_encoding(encoding);
+                        if schema.multi {
+                            index = index.with_multi();
+                        }
+                        match schema.dimension {
+                            Some((_, dimension)) => index.with_dimension(dimension),
+                            None => index,
+                        }
+                    })
+                    .collect(),
+            )
+            .ok();
+
+        // fields declared `TEXT` on anything but a `String`, or with an unknown analyzer,
+        // are reported by `check_schema`. Schemas without any keep indexing every field.
+        let latest = src.get_latest_schema();
+        let text_fields = latest
+            .node_schemas
+            .iter()
+            .map(|schema| (schema.name.1.as_str(), &schema.fields))
+            .chain(
+                latest
+                    .vector_schemas
+                    .iter()
+                    .map(|schema| (schema.name.as_str(), &schema.fields)),
+            )
+            .flat_map(|(label, fields)| {
+                fields
+                    .iter()
+                    .filter(|f| f.text)
+                    .map(move |f| {
+                        TextField::new(label, &f.name).with_analyzer(
+                            f.text_analyzer
+                                .as_ref()
+                                .and_then(|(_, analyzer)| analyzer.parse().ok())
+                                .unwrap_or_default(),
+                        )
+                    })
+            })
+            .collect::<Vec<_>>();
+        if !text_fields.is_empty() {
+            TEXT_FIELDS.set(text_fields).ok();
+        }
         ctx
     }
 
-    #[allow(unused)]
     pub(super) fn get_item_fields(
         &self,
         item_type: &Type,
@@ -16,6 +16,30 @@ pub enum ErrorCode {
     E105,
     /// `E106` – `use of undeclared node type in schema`
     E106,
+    /// `E107` – `unique field is not indexed`
+    E107,
+    /// `E108` – `unique modifier on a field that is not a node field`
+    E108,
+    /// `E109` – `unique field has a default value`
+    E109,
+    /// `E110` – `composite index field is not a field of the node type`
+    E110,
+    /// `E111` – `field appears more than once in a composite index`
+    E111,
+    /// `E112` – `unknown distance metric`
+    E112,
+    /// `E113` – `vector type declared with zero dimensions`
+    E113,
+    /// `E114` – `unknown vector encoding`
+    E114,
+    /// `E115` – `sparse vector type declared with arguments of dense vectors`
+    E115,
+    /// `E116` – `text modifier on a field that is not a node or vector field`
+    E116,
+    /// `E117` – `text modifier on a field that is not a string`
+    E117,
+    /// `E118` – `unknown text analyzer`
+    E118,
 
     // TYPE ERRORS
     /// `E201` – `item type not in schema`
@@ -36,6 +60,36 @@ pub enum ErrorCode {
     E208,
     /// `E209` – `unknown type for parameter`
     E209,
+    /// `E210` – `prefix lookup on a field that is not a string`
+    E210,
+    /// `E211` – `no composite index starts with the looked up fields`
+    E211,
+    /// `E212` – `range or prefix lookup on an edge index`
+    E212,
+    /// `E213` – `vector length does not match the dimension of the vector type`
+    E213,
+    /// `E214` – `threads given to a search of a single query vector`
+    E214,
+    /// `E215` – `search over an array of query vectors used where only a single search is supported`
+    E215,
+    /// `E216` – `multi-vector type given a single vector`
+    E216,
+    /// `E217` – `array of vectors added to a vector type that is not multi-vector`
+    E217,
+    /// `E218` – `search of a multi-vector type used where it is not supported`
+    E218,
+    /// `E219` – `sparse vector given to a dense vector type, or a dense vector to a sparse one`
+    E219,
+    /// `E220` – `indices or weights of a sparse vector are not parameters of the right type`
+    E220,
+    /// `E221` – `search of a sparse vector type used where it is not supported`
+    E221,
+    /// `E222` – `hybrid search of a type without both vectors and text`
+    E222,
+    /// `E223` – `BM25 search of a node type without text fields`
+    E223,
+    /// `E224` – `fuzzy BM25 search over more edits than allowed`
+    E224,
 
     // QUERY ERRORS
     /// `E301` – `variable not in scope`
@@ -127,6 +181,18 @@ impl std::fmt::Display for ErrorCode {
             ErrorCode::E104 => write!(f, "E104"),
             ErrorCode::E105 => write!(f, "E105"),
             ErrorCode::E106 => write!(f, "E106"),
+            ErrorCode::E107 => write!(f, "E107"),
+            ErrorCode::E108 => write!(f, "E108"),
+            ErrorCode::E109 => write!(f, "E109"),
+            ErrorCode::E110 => write!(f, "E110"),
+            ErrorCode::E111 => write!(f, "E111"),
+            ErrorCode::E112 => write!(f, "E112"),
+            ErrorCode::E113 => write!(f, "E113"),
+            ErrorCode::E114 => write!(f, "E114"),
+            ErrorCode::E115 => write!(f, "E115"),
+            ErrorCode::E116 => write!(f, "E116"),
+            ErrorCode::E117 => write!(f, "E117"),
+            ErrorCode::E118 => write!(f, "E118"),
             ErrorCode::E201 => write!(f, "E201"),
             ErrorCode::E202 => write!(f, "E202"),
             ErrorCode::E203 => write!(f, "E203"),
@@ -136,6 +202,21 @@ impl std::fmt::Display for ErrorCode {
             ErrorCode::E207 => write!(f, "E207"),
             ErrorCode::E208 => write!(f, "E208"),
             ErrorCode::E209 => write!(f, "E209"),
+            ErrorCode::E210 => write!(f, "E210"),
+            ErrorCode::E211 => write!(f, "E211"),
+            ErrorCode::E212 => write!(f, "E212"),
+            ErrorCode::E213 => write!(f, "E213"),
+            ErrorCode::E214 => write!(f, "E214"),
+            ErrorCode::E215 => write!(f, "E215"),
+            ErrorCode::E216 => write!(f, "E216"),
+            ErrorCode::E217 => write!(f, "E217"),
+            ErrorCode::E218 => write!(f, "E218"),
+            ErrorCode::E219 => write!(f, "E219"),
+            ErrorCode::E220 => write!(f, "E220"),
+            ErrorCode::E221 => write!(f, "E221"),
+            ErrorCode::E222 => write!(f, "E222"),
+            ErrorCode::E223 => write!(f, "E223"),
+            ErrorCode::E224 => write!(f, "E224"),
             ErrorCode::E301 => write!(f, "E301"),
             ErrorCode::E302 => write!(f, "E302"),
             ErrorCode::E303 => write!(f, "E303"),
@@ -200,6 +281,18 @@ implement_error_code!(E102, "unknown edge type `{} `" => { edge_type }, "check t
 implement_error_code!(E103, "unknown vector type `{}`" => { vector_type }, "check the schema field names or declare the vector type" => {});
 implement_error_code!(E105, "invalid identifier `{}`" => { identifier }, "check the identifier" => {});
 implement_error_code!(E106, "use of undeclared node or vector type `{}` in schema" => { item_type_name }, "declare `{}` in the schema before using it in an edge" => { item_type_name });
+implement_error_code!(E107, "`UNIQUE` field `{}` of node type `{}` is not indexed" => { field_name, node_type }, "declare the field as `UNIQUE INDEX {}`" => { field_name });
+implement_error_code!(E108, "`UNIQUE` is only valid on node fields, but `{}` is a field of {} type `{}`" => { field_name, item_type, item_type_name }, "remove `UNIQUE` from the field" => {});
+implement_error_code!(E109, "`UNIQUE` field `{}` cannot have a default value" => { field_name }, "remove the default value, every node would otherwise share it" => {});
+implement_error_code!(E110, "field `{}` in composite index is not a field of node type `{}`" => { field_name, node_type }, "declare the field or remove it from the index" => {});
+implement_error_code!(E111, "field `{}` appears more than once in composite index of node type `{}`" => { field_name, node_type }, "remove the repeated field from the index" => {});
+implement_error_code!(E112, "unknown distance metric `{}` for vector type `{}`" => { metric, vector_type }, "use one of `cosine`, `euclidean`, `inner_product` or `hamming`" => {});
+implement_error_code!(E113, "vector type `{}` is declared with zero dimensions" => { vector_type }, "declare the length of the type's vectors, e.g. `V::{}(1536)`" => { vector_type });
+implement_error_code!(E114, "unknown vector encoding `{}` for vector type `{}`" => { encoding, vector_type }, "use one of `f64`, `f32`, `int8` or `binary`" => {});
+implement_error_code!(E115, "sparse vector type `{}` cannot be declared with {}" => { vector_type, argument }, "sparse vectors are searched by dot product through an inverted index, so take no dimension, metric, encoding, `multi` or HNSW parameters" => {});
+implement_error_code!(E116, "`TEXT` is only valid on node and vector fields, but `{}` is a field of edge type `{}`" => { field_name, edge_type }, "remove `TEXT` from the field" => {});
+implement_error_code!(E117, "`TEXT` field `{}` of {} type `{}` is a `{}`, not a `String`" => { field_name, item_type, item_type_name, field_type }, "declare the field as a `String` or remove `TEXT`" => {});
+implement_error_code!(E118, "unknown text analyzer `{}` for field `{}` of {} type `{}`" => { analyzer, field_name, item_type, item_type_name }, "use one of `standard`, `english`, `whitespace`, `ngram` or `cjk`" => {});
 
 // Type errors
 implement_error_code!(E201, "item type not in schema `{}`" => { item_type }, "check the schema field names" => {});
@@ -213,8 +306,23 @@ implement_error_code!(E205,
     "change the value type to match the field type defined in the schema" => {});
 implement_error_code!(E206, "invalid value type `{}`" => { value_type }, "use a literal or an identifier" => {});
 implement_error_code!(E207, "edge type `{}` exists but it is not a valid edge type for the given {} type `{}`" => { edge_type, item_type, item_type_name }, "check the schema field names" => {});
-implement_error_code!(E208, "field `{}` has not been indexed for node type `{}`" => { field_name, node_type }, "use a field that has been indexed with `INDEX` in the schema for node type `{}`" => { node_type });
+implement_error_code!(E208, "field `{}` has not been indexed for {} type `{}`" => { field_name, item_type, item_type_name }, "use a field that has been indexed with `INDEX` in the schema for {} type `{}`" => { item_type, item_type_name });
 implement_error_code!(E209, "unknown type `{}` for parameter `{}`" => { parameter_type, parameter_name }, "declare or use a matching schema object or use a primitive type" => {});
+implement_error_code!(E210, "`PREFIX` lookup requires a `String` field, but `{}` of node type `{}` is `{}`" => { field_name, node_type, field_type }, "use `GT`, `GTE`, `LT`, `LTE` or `BETWEEN` for fields that are not strings" => {});
+implement_error_code!(E211, "no index on node type `{}` starts with the fields `{}`" => { node_type, fields }, "declare `INDEX({})` in the schema for node type `{}`" => { fields, node_type });
+implement_error_code!(E212, "range and prefix lookups are not supported on edge type `{}`" => { edge_type }, "look up an exact value of an indexed edge property instead" => {});
+implement_error_code!(E213, "{} has {} dimensions, but vector type `{}` is declared with {}" => { vector, found, vector_type, expected }, "use a vector of {} dimensions" => { expected });
+implement_error_code!(E214, "`threads` only applies to `SearchV` over an array of query vectors" => {}, "pass the query vectors as a `[[F64]]` parameter, or remove `threads`" => {});
+implement_error_code!(E215, "`SearchV` over an array of query vectors {}" => { reason }, "search the query vectors one at a time" => {});
+implement_error_code!(E216, "vector type `{}` holds multi-vector documents, but `{}` is given a single vector" => { vector_type, step }, "pass the vectors as a `[[F64]]` parameter" => {});
+implement_error_code!(E217, "`AddV` of vector type `{}` takes a single vector, but is given an array of vectors" => { vector_type }, "declare the vector type with `multi`, e.g. `V::{}(multi)`, to store several vectors per item" => { vector_type });
+implement_error_code!(E218, "`SearchV` over multi-vector type `{}` {}" => { vector_type, reason }, "search the documents at the start of a traversal, with a `k`" => {});
+implement_error_code!(E219, "vector type `{}` holds {} vectors, but `{}` is given a {} vector" => { vector_type, kind, step, given }, "pass `Sparse(indices, weights)` to a sparse vector type, and a vector or `Embed` to a dense one" => {});
+implement_error_code!(E220, "`{}` of `Sparse` must be a `{}` parameter" => { argument, expected }, "declare `{}` as a `{}` parameter of the query" => { argument, expected });
+implement_error_code!(E221, "`SearchV` over sparse vector type `{}` {}" => { vector_type, reason }, "search the sparse vectors at the start of a traversal, with a `k`" => {});
+implement_error_code!(E222, "`SearchHybrid` needs a vector type with text, but `{}` {}" => { vector_type, reason }, "declare the fields to search as `TEXT`, e.g. `TEXT title: String`" => {});
+implement_error_code!(E223, "`SearchBM25` over node type `{}`, which has no `TEXT` fields" => { node_type }, "declare the fields to search as `TEXT`, e.g. `TEXT title: String`" => {});
+implement_error_code!(E224, "`SearchBM25` with a `fuzzy` distance of `{}`, above the most allowed" => { distance }, "use a `fuzzy` distance of at most `{}`" => { max_distance });
 
 // Query errors
 implement_error_code!(E301, "variable `{}` not in scope" => { variable }, "check the variable" => {});
@@ -10,7 +10,11 @@ use crate::{
             analyzer::Ctx,
             errors::push_query_err,
             types::Type,
-            utils::{gen_identifier_or_param, is_valid_identifier},
+            utils::{
+                check_vector_dimension, check_vector_kind, gen_identifier_or_param, gen_search_radius,
+                gen_sparse_vector, is_multi_vector, is_sparse_vector, is_valid_identifier,
+                is_vector_batch,
+            },
         },
         generator::{
             queries::Query as GeneratedQuery,
@@ -365,12 +369,61 @@ pub(crate) fn apply_graph_step<'a>(
             {
                 generate_error!(ctx, original_query, sv.loc.clone(), E103, ty.as_str());
             }
+            // the step compares the vectors of the traversal to a single query vector
+            if let Some(ty) = sv.vector_type.as_deref().filter(|_| is_multi_vector(ctx, &sv.vector_type)) {
+                generate_error!(
+                    ctx,
+                    original_query,
+                    sv.loc.clone(),
+                    E218,
+                    ty,
+                    "must start a traversal"
+                );
+            } else if let Some(ty) = sv
+                .vector_type
+                .as_deref()
+                .filter(|_| is_sparse_vector(ctx, &sv.vector_type))
+            {
+                generate_error!(
+                    ctx,
+                    original_query,
+                    sv.loc.clone(),
+                    E221,
+                    ty,
+                    "must start a traversal"
+                );
+            } else if sv
+                .data
+                .as_ref()
+                .is_some_and(|data| is_vector_batch(original_query, data))
+            {
+                generate_error!(
+                    ctx,
+                    original_query,
+                    sv.loc.clone(),
+                    E215,
+                    "must start a traversal"
+                );
+            } else if let Some(threads) = &sv.threads {
+                generate_error!(ctx, original_query, threads.loc.clone(), E214);
+            }
+            if let (Some(ty), Some(data)) = (&sv.vector_type, &sv.data) {
+                check_vector_kind(ctx, original_query, sv.loc.clone(), ty, data, "SearchV");
+                check_vector_dimension(
+                    ctx,
+                    original_query,
+                    sv.loc.clone(),
+                    ty,
+                    data,
+                    gen_query.embedding_model_to_use.as_deref(),
+                );
+            }
             let vec = match &sv.data {
                 Some(VectorData::Vector(v)) => {
                     VecData::Standard(GeneratedValue::Literal(GenRef::Ref(format!(
                         "[{}]",
                         v.iter()
-                            .map(|f| f.to_string())
+                            .map(|f| format!("{f:?}"))
                             .collect::<Vec<String>>()
                             .join(",")
                     ))))
@@ -397,6 +450,7 @@ pub(crate) fn apply_graph_step<'a>(
 
                     VecData::Hoisted(name)
                 }
+                Some(VectorData::Sparse(sparse)) => gen_sparse_vector(ctx, original_query, sparse),
                 _ => {
                     generate_error!(
                         ctx,
@@ -454,6 +508,8 @@ pub(crate) fn apply_graph_step<'a>(
                         GeneratedValue::Unknown
                     }
                 },
+                // a search within a radius has no k
+                None if sv.radius.is_some() => GeneratedValue::Unknown,
                 None => {
                     generate_error!(
                         ctx,
@@ -466,6 +522,10 @@ pub(crate) fn apply_graph_step<'a>(
                     GeneratedValue::Unknown
                 }
             };
+            let radius = sv
+                .radius
+                .as_ref()
+                .map(|radius| gen_search_radius(ctx, original_query, radius));
 
             // Search returns nodes that contain the vectors
 
@@ -480,7 +540,7 @@ pub(crate) fn apply_graph_step<'a>(
             traversal
                 .steps
                 .push(Separator::Period(GeneratedStep::SearchVector(
-                    SearchVectorStep { vec, k },
+                    SearchVectorStep { vec, k, radius },
                 )));
             // traversal.traversal_type = TraversalType::Ref;
             traversal.should_collect = ShouldCollect::ToVec;
@@ -3,6 +3,7 @@ use crate::helixc::analyzer::error_codes::ErrorCode;
 use crate::helixc::generator::utils::EmbedData;
 use crate::{
     generate_error,
+    helix_engine::bm25::query::MAX_EDIT_DISTANCE,
     helixc::{
         analyzer::{
             analyzer::Ctx,
@@ -10,20 +11,21 @@ use crate::{
             methods::traversal_validation::validate_traversal,
             types::Type,
             utils::{
-                gen_id_access_or_param, gen_identifier_or_param, is_valid_identifier, type_in_scope,
+                check_hybrid_search_type, check_vector_dimension, check_vector_kind, gen_fusion,
+                gen_id_access_or_param, gen_identifier_or_param, gen_pre_filter, gen_search_param,
+                gen_search_radius, gen_search_threads, gen_sparse_vector, is_multi_vector,
+                is_valid_identifier, is_vector_batch, type_in_scope,
             },
         },
         generator::{
             bool_op::BoExp,
             queries::Query as GeneratedQuery,
             source_steps::{
-                AddE, AddN, AddV, SearchBM25, SearchVector as GeneratedSearchVector, SourceStep,
+                AddE, AddN, AddV, ExpansionMethod, SearchBM25, SearchHybrid,
+                SearchVector as GeneratedSearchVector, SourceStep,
             },
             statements::Statement as GeneratedStatement,
-            traversal_steps::{
-                ShouldCollect, Step as GeneratedStep, Traversal as GeneratedTraversal,
-                TraversalType, Where, WhereRef,
-            },
+            traversal_steps::{ShouldCollect, Traversal as GeneratedTraversal, TraversalType},
             utils::{GenRef, GeneratedValue, Separator, VecData},
         },
         parser::helix_parser::*,
@@ -281,9 +283,18 @@ pub(crate) fn infer_expr_type<'a>(
                                 .properties
                                 .iter()
                                 .filter_map(|p| {
-                                    matches!(p.is_index, FieldPrefix::Index)
+                                    p.is_index.is_indexed()
                                         .then_some(p.name.clone())
                                 })
+                                .chain(
+                                    ctx.src
+                                        .get_latest_schema()
+                                        .node_schemas
+                                        .iter()
+                                        .filter(|schema| schema.name.1 == *ty)
+                                        .flat_map(|schema| &schema.composite_indices)
+                                        .map(|index| index.name()),
+                                )
                                 .collect::<Vec<_>>();
                             match secondary_indices.is_empty() {
                                 true => None,
@@ -315,7 +326,7 @@ pub(crate) fn infer_expr_type<'a>(
                     source_step: Separator::Period(SourceStep::AddN(add_n)),
                     steps: vec![],
                     traversal_type: TraversalType::Mut,
-                    should_collect: ShouldCollect::ToVal,
+                    should_collect: ShouldCollect::TryToVal,
                 });
                 gen_query.is_mut = true;
                 return (Type::Node(Some(ty.to_string())), Some(stmt));
@@ -533,6 +544,28 @@ pub(crate) fn infer_expr_type<'a>(
                 if !ctx.vector_set.contains(ty.as_str()) {
                     generate_error!(ctx, original_query, add.loc.clone(), E103, ty.as_str());
                 }
+                let multi = ctx.multi_vectors.contains(ty.as_str());
+                if let Some(data) = &add.data
+                    && check_vector_kind(ctx, original_query, add.loc.clone(), ty, data, "AddV")
+                {
+                    match (multi, is_vector_batch(original_query, data)) {
+                        (true, false) => {
+                            generate_error!(ctx, original_query, add.loc.clone(), E216, ty, "AddV");
+                        }
+                        (false, true) => {
+                            generate_error!(ctx, original_query, add.loc.clone(), E217, [ty], [ty]);
+                        }
+                        _ => {}
+                    }
+                    check_vector_dimension(
+                        ctx,
+                        original_query,
+                        add.loc.clone(),
+                        ty,
+                        data,
+                        gen_query.embedding_model_to_use.as_deref(),
+                    );
+                }
                 // Validate vector fields
                 let (label, properties) = match &add.fields {
                     Some(fields) => {
@@ -670,7 +703,7 @@ pub(crate) fn infer_expr_type<'a>(
                             VecData::Standard(GeneratedValue::Literal(GenRef::Ref(format!(
                                 "[{}]",
                                 v.iter()
-                                    .map(|f| f.to_string())
+                                    .map(|f| format!("{f:?}"))
                                     .collect::<Vec<String>>()
                                     .join(",")
                             ))))
@@ -700,11 +733,15 @@ pub(crate) fn infer_expr_type<'a>(
 
                             VecData::Hoisted(gen_query.add_hoisted_embed(embed_data))
                         }
+                        VectorData::Sparse(sparse) => {
+                            gen_sparse_vector(ctx, original_query, sparse)
+                        }
                     };
                     let add_v = AddV {
                         vec,
                         label,
                         properties,
+                        multi,
                     };
                     let stmt = GeneratedStatement::Traversal(GeneratedTraversal {
                         source_step: Separator::Period(SourceStep::AddV(add_v)),
@@ -745,12 +782,23 @@ pub(crate) fn infer_expr_type<'a>(
             {
                 generate_error!(ctx, original_query, sv.loc.clone(), E103, ty.as_str());
             }
+            if let (Some(ty), Some(data)) = (&sv.vector_type, &sv.data) {
+                check_vector_kind(ctx, original_query, sv.loc.clone(), ty, data, "SearchV");
+                check_vector_dimension(
+                    ctx,
+                    original_query,
+                    sv.loc.clone(),
+                    ty,
+                    data,
+                    gen_query.embedding_model_to_use.as_deref(),
+                );
+            }
             let vec: VecData = match &sv.data {
                 Some(VectorData::Vector(v)) => {
                     VecData::Standard(GeneratedValue::Literal(GenRef::Ref(format!(
                         "[{}]",
                         v.iter()
-                            .map(|f| f.to_string())
+                            .map(|f| format!("{f:?}"))
                             .collect::<Vec<String>>()
                             .join(",")
                     ))))
@@ -780,6 +828,7 @@ pub(crate) fn infer_expr_type<'a>(
 
                     VecData::Hoisted(gen_query.add_hoisted_embed(embed_data))
                 }
+                Some(VectorData::Sparse(sparse)) => gen_sparse_vector(ctx, original_query, sparse),
                 _ => {
                     generate_error!(
                         ctx,
@@ -838,77 +887,59 @@ pub(crate) fn infer_expr_type<'a>(
                         GeneratedValue::Unknown
                     }
                 },
+                // a search within a radius has no k
+                None if sv.radius.is_some() => GeneratedValue::Unknown,
                 None => {
                     generate_error!(ctx, original_query, sv.loc.clone(), E601, &sv.loc.span);
                     GeneratedValue::Unknown
                 }
             };
 
-            let pre_filter: Option<Vec<BoExp>> = match &sv.pre_filter {
-                Some(expr) => {
-                    let (_, stmt) = infer_expr_type(
-                        ctx,
-                        expr,
-                        scope,
-                        original_query,
-                        Some(Type::Vector(sv.vector_type.clone())),
-                        gen_query,
-                    );
-                    // Where/boolean ops don't change the element type,
-                    // so `cur_ty` stays the same.
-                    assert!(stmt.is_some());
-                    let stmt = stmt.unwrap();
-                    let mut gen_traversal = GeneratedTraversal {
-                        traversal_type: TraversalType::NestedFrom(GenRef::Std("v".to_string())),
-                        steps: vec![],
-                        should_collect: ShouldCollect::ToVec,
-                        source_step: Separator::Empty(SourceStep::Anonymous),
-                    };
-                    match stmt {
-                        GeneratedStatement::Traversal(tr) => {
-                            gen_traversal
-                                .steps
-                                .push(Separator::Period(GeneratedStep::Where(Where::Ref(
-                                    WhereRef {
-                                        expr: BoExp::Expr(tr),
-                                    },
-                                ))));
-                        }
-                        GeneratedStatement::BoExp(expr) => {
-                            gen_traversal
-                                .steps
-                                .push(Separator::Period(GeneratedStep::Where(match expr {
-                                    BoExp::Exists(mut traversal) => {
-                                        traversal.should_collect = ShouldCollect::No;
-                                        Where::Ref(WhereRef {
-                                            expr: BoExp::Exists(traversal),
-                                        })
-                                    }
-                                    _ => Where::Ref(WhereRef { expr }),
-                                })));
-                        }
-                        _ => unreachable!(),
-                    }
-                    Some(vec![BoExp::Expr(gen_traversal)])
-                }
-                None => None,
-            };
+            let pre_filter = sv.pre_filter.as_ref().map(|expr| {
+                vec![gen_pre_filter(
+                    ctx,
+                    expr,
+                    scope,
+                    original_query,
+                    &sv.vector_type,
+                    gen_query,
+                )]
+            });
+
+            let threads = gen_search_threads(ctx, original_query, sv);
 
             // Search returns nodes that contain the vectors
             (
-                Type::Vectors(sv.vector_type.clone()),
+                match threads {
+                    Some(_) => Type::VectorBatches(sv.vector_type.clone()),
+                    None => Type::Vectors(sv.vector_type.clone()),
+                },
                 Some(GeneratedStatement::Traversal(GeneratedTraversal {
                     traversal_type: TraversalType::Ref,
                     steps: vec![],
-                    should_collect: ShouldCollect::ToVec,
-                    source_step: Separator::Period(SourceStep::SearchVector(
+                    should_collect: match threads {
+                        Some(_) => ShouldCollect::Try,
+                        None => ShouldCollect::ToVec,
+                    },
+                    source_step: Separator::Period(SourceStep::SearchVector(Box::new(
                         GeneratedSearchVector {
                             label: GenRef::Literal(sv.vector_type.clone().unwrap()),
                             vec,
                             k,
+                            radius: sv
+                                .radius
+                                .as_ref()
+                                .map(|radius| gen_search_radius(ctx, original_query, radius)),
+                            ef: sv.ef.as_ref().map(|ef| gen_search_param(ctx, original_query, ef)),
+                            candidates: sv
+                                .candidates
+                                .as_ref()
+                                .map(|candidates| gen_search_param(ctx, original_query, candidates)),
                             pre_filter,
+                            threads,
+                            multi: is_multi_vector(ctx, &sv.vector_type),
                         },
-                    )),
+                    ))),
                 })),
             )
         }
@@ -1059,6 +1090,25 @@ pub(crate) fn infer_expr_type<'a>(
                     E101,
                     ty.as_str()
                 );
+            } else if let Some(ref ty) = bm25_search.type_arg
+                // schemas without any `TEXT` fields index every field, so any type can be searched
+                && ctx
+                    .node_fields
+                    .values()
+                    .chain(ctx.vector_fields.values())
+                    .any(|fields| fields.values().any(|field| field.text))
+                && !ctx
+                    .node_fields
+                    .get(ty.as_str())
+                    .is_some_and(|fields| fields.values().any(|field| field.text))
+            {
+                generate_error!(
+                    ctx,
+                    original_query,
+                    bm25_search.loc.clone(),
+                    E223,
+                    ty.as_str()
+                );
             }
             let vec = match &bm25_search.data {
                 Some(ValueType::Literal { value, loc: _ }) => {
@@ -1165,10 +1215,28 @@ pub(crate) fn infer_expr_type<'a>(
                 }
             };
 
+            let expansion = bm25_search.expansion.as_ref().map(|expansion| match expansion {
+                Expansion::Prefix => ExpansionMethod::Prefix,
+                Expansion::Fuzzy { loc, distance } => {
+                    let distance = distance.unwrap_or(MAX_EDIT_DISTANCE);
+                    if distance > MAX_EDIT_DISTANCE {
+                        generate_error!(
+                            ctx,
+                            original_query,
+                            loc.clone(),
+                            E224,
+                            [&distance.to_string()],
+                            [&MAX_EDIT_DISTANCE.to_string()]
+                        );
+                    }
+                    ExpansionMethod::Fuzzy(distance.min(MAX_EDIT_DISTANCE))
+                }
+            });
             let search_bm25 = SearchBM25 {
                 type_arg: GenRef::Literal(bm25_search.type_arg.clone().unwrap()),
                 query: vec,
                 k,
+                expansion,
             };
             (
                 Type::Nodes(bm25_search.type_arg.clone()),
@@ -1180,6 +1248,120 @@ pub(crate) fn infer_expr_type<'a>(
                 })),
             )
         }
+        HybridSearch(hs) => {
+            let vector_type = hs.vector_type.clone().unwrap();
+            if check_hybrid_search_type(ctx, original_query, hs.loc.clone(), &vector_type)
+                && let Some(data) = &hs.data
+                && check_vector_kind(
+                    ctx,
+                    original_query,
+                    hs.loc.clone(),
+                    &vector_type,
+                    data,
+                    "SearchHybrid",
+                )
+            {
+                check_vector_dimension(
+                    ctx,
+                    original_query,
+                    hs.loc.clone(),
+                    &vector_type,
+                    data,
+                    gen_query.embedding_model_to_use.as_deref(),
+                );
+            }
+            let query = match &hs.text {
+                Some(ValueType::Literal { value, loc: _ }) => {
+                    GeneratedValue::Literal(GenRef::Std(value.to_string()))
+                }
+                Some(ValueType::Identifier { value: i, loc }) => {
+                    is_valid_identifier(ctx, original_query, loc.clone(), i.as_str());
+                    let _ = type_in_scope(ctx, original_query, loc.clone(), scope, i.as_str());
+                    gen_identifier_or_param(original_query, i, true, false)
+                }
+                _ => {
+                    generate_error!(
+                        ctx,
+                        original_query,
+                        hs.loc.clone(),
+                        E305,
+                        ["text", "SearchHybrid"],
+                        ["text"]
+                    );
+                    GeneratedValue::Unknown
+                }
+            };
+            let vec = match &hs.data {
+                Some(VectorData::Vector(v)) => {
+                    VecData::Standard(GeneratedValue::Literal(GenRef::Ref(format!(
+                        "[{}]",
+                        v.iter()
+                            .map(|f| format!("{f:?}"))
+                            .collect::<Vec<String>>()
+                            .join(",")
+                    ))))
+                }
+                Some(VectorData::Identifier(i)) => {
+                    is_valid_identifier(ctx, original_query, hs.loc.clone(), i.as_str());
+                    let _ = type_in_scope(ctx, original_query, hs.loc.clone(), scope, i.as_str());
+                    VecData::Standard(gen_identifier_or_param(original_query, i, true, false))
+                }
+                Some(VectorData::Embed(e)) => {
+                    let data = match &e.value {
+                        EvaluatesToString::Identifier(i) => {
+                            gen_identifier_or_param(original_query, i.as_str(), true, false)
+                        }
+                        EvaluatesToString::StringLiteral(s) => {
+                            GeneratedValue::Literal(GenRef::Ref(s.clone()))
+                        }
+                    };
+                    VecData::Hoisted(gen_query.add_hoisted_embed(EmbedData {
+                        data,
+                        model_name: gen_query.embedding_model_to_use.clone(),
+                    }))
+                }
+                Some(VectorData::Sparse(sparse)) => gen_sparse_vector(ctx, original_query, sparse),
+                None => {
+                    generate_error!(
+                        ctx,
+                        original_query,
+                        hs.loc.clone(),
+                        E305,
+                        ["vector_data", "SearchHybrid"],
+                        ["vector_data"]
+                    );
+                    VecData::Unknown
+                }
+            };
+            let k = match &hs.k {
+                Some(k) => gen_search_param(ctx, original_query, k),
+                None => {
+                    generate_error!(ctx, original_query, hs.loc.clone(), E601, &hs.loc.span);
+                    GeneratedValue::Unknown
+                }
+            };
+
+            (
+                Type::Vectors(hs.vector_type.clone()),
+                Some(GeneratedStatement::Traversal(GeneratedTraversal {
+                    traversal_type: TraversalType::Ref,
+                    steps: vec![],
+                    should_collect: ShouldCollect::ToVec,
+                    source_step: Separator::Period(SourceStep::SearchHybrid(Box::new(
+                        SearchHybrid {
+                            label: GenRef::Literal(vector_type),
+                            query,
+                            vec,
+                            k,
+                            fusion: hs
+                                .fusion
+                                .as_ref()
+                                .map(|fusion| gen_fusion(ctx, original_query, fusion)),
+                        },
+                    ))),
+                })),
+            )
+        }
         _ => {
             println!("Unknown expression: {expr:?}");
             todo!()
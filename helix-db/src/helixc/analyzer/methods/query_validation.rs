@@ -173,6 +173,12 @@ pub(crate) fn validate_query<'a>(ctx: &mut Ctx<'a>, original_query: &'a Query) {
                             ReturnValueExpr::Identifier(value),
                         ));
                     }
+                    Type::VectorBatches(_) => {
+                        query.return_values.push(ReturnValue::new_named_batches(
+                            GeneratedValue::Literal(GenRef::Literal(id.inner().clone())),
+                            ReturnValueExpr::Identifier(value),
+                        ));
+                    }
                     _ => {
                         query.return_values.push(ReturnValue::new_named(
                             GeneratedValue::Literal(GenRef::Literal(id.inner().clone())),
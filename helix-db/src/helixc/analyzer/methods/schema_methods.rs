@@ -1,10 +1,19 @@
-use std::{borrow::Cow, collections::HashMap};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
 
-use crate::helixc::{
-    analyzer::{analyzer::Ctx, error_codes::ErrorCode, errors::push_schema_err},
-    parser::{
-        helix_parser::{Field, FieldPrefix, FieldType, Source},
-        location::Loc,
+use crate::{
+    helix_engine::{
+        bm25::analyzer::TextAnalyzer,
+        vector_core::{vector_distance::DistanceMetric, vector_encoding::VectorEncoding},
+    },
+    helixc::{
+        analyzer::{analyzer::Ctx, error_codes::ErrorCode, errors::push_schema_err},
+        parser::{
+            helix_parser::{Field, FieldPrefix, FieldType, Source},
+            location::Loc,
+        },
     },
 };
 
@@ -45,6 +54,8 @@ pub(crate) fn build_field_lookups<'a>(src: &'a Source) -> SchemaVersionMap<'a> {
                             "id",
                             Cow::Owned(Field {
                                 prefix: FieldPrefix::Empty,
+                                text: false,
+                                text_analyzer: None,
                                 defaults: None,
                                 name: "id".to_string(),
                                 field_type: FieldType::Uuid,
@@ -72,6 +83,8 @@ pub(crate) fn build_field_lookups<'a>(src: &'a Source) -> SchemaVersionMap<'a> {
                             "id",
                             Cow::Owned(Field {
                                 prefix: FieldPrefix::Empty,
+                                text: false,
+                                text_analyzer: None,
                                 defaults: None,
                                 name: "id".to_string(),
                                 field_type: FieldType::Uuid,
@@ -95,6 +108,8 @@ pub(crate) fn build_field_lookups<'a>(src: &'a Source) -> SchemaVersionMap<'a> {
                             "id",
                             Cow::Owned(Field {
                                 prefix: FieldPrefix::Empty,
+                                text: false,
+                                text_analyzer: None,
                                 defaults: None,
                                 name: "id".to_string(),
                                 field_type: FieldType::Uuid,
@@ -158,6 +173,24 @@ pub(crate) fn check_schema(ctx: &mut Ctx) {
                         Some("rename the field".to_string()),
                     );
                 }
+                if f.prefix.is_unique() {
+                    push_schema_err(
+                        ctx,
+                        f.loc.clone(),
+                        ErrorCode::E108,
+                        ErrorCode::E108_message(&f.name, "edge", &edge.name.1),
+                        Some(ErrorCode::E108_hint()),
+                    );
+                }
+                if f.text {
+                    push_schema_err(
+                        ctx,
+                        f.loc.clone(),
+                        ErrorCode::E116,
+                        ErrorCode::E116_message(&f.name, &edge.name.1),
+                        Some(ErrorCode::E116_hint()),
+                    );
+                }
             })
         }
         ctx.output.edges.push(edge.clone().into());
@@ -173,7 +206,49 @@ pub(crate) fn check_schema(ctx: &mut Ctx) {
                     Some("rename the field".to_string()),
                 );
             }
+            if f.prefix.is_unique() && !f.is_indexed() {
+                push_schema_err(
+                    ctx,
+                    f.loc.clone(),
+                    ErrorCode::E107,
+                    ErrorCode::E107_message(&f.name, &node.name.1),
+                    Some(ErrorCode::E107_hint(&f.name)),
+                );
+            }
+            if f.prefix.is_unique() && f.defaults.is_some() {
+                push_schema_err(
+                    ctx,
+                    f.loc.clone(),
+                    ErrorCode::E109,
+                    ErrorCode::E109_message(&f.name),
+                    Some(ErrorCode::E109_hint()),
+                );
+            }
+            check_text_field(ctx, f, "node", &node.name.1);
         });
+        for index in &node.composite_indices {
+            let mut seen = HashSet::new();
+            for (loc, field) in &index.fields {
+                if !node.fields.iter().any(|f| f.name == *field) {
+                    push_schema_err(
+                        ctx,
+                        loc.clone(),
+                        ErrorCode::E110,
+                        ErrorCode::E110_message(field, &node.name.1),
+                        Some(ErrorCode::E110_hint()),
+                    );
+                }
+                if !seen.insert(field.as_str()) {
+                    push_schema_err(
+                        ctx,
+                        loc.clone(),
+                        ErrorCode::E111,
+                        ErrorCode::E111_message(field, &node.name.1),
+                        Some(ErrorCode::E111_hint()),
+                    );
+                }
+            }
+        }
         ctx.output.nodes.push(node.clone().into());
     }
     for vector in &ctx.src.get_latest_schema().vector_schemas {
@@ -187,7 +262,99 @@ pub(crate) fn check_schema(ctx: &mut Ctx) {
                     Some("rename the field".to_string()),
                 );
             }
+            if f.prefix.is_unique() {
+                push_schema_err(
+                    ctx,
+                    f.loc.clone(),
+                    ErrorCode::E108,
+                    ErrorCode::E108_message(&f.name, "vector", &vector.name),
+                    Some(ErrorCode::E108_hint()),
+                );
+            }
+            check_text_field(ctx, f, "vector", &vector.name);
         });
+        if let Some((loc, metric)) = &vector.metric
+            && metric.parse::<DistanceMetric>().is_err()
+        {
+            push_schema_err(
+                ctx,
+                loc.clone(),
+                ErrorCode::E112,
+                ErrorCode::E112_message(metric, &vector.name),
+                Some(ErrorCode::E112_hint()),
+            );
+        }
+        if let Some((loc, encoding)) = &vector.encoding
+            && encoding.parse::<VectorEncoding>().is_err()
+        {
+            push_schema_err(
+                ctx,
+                loc.clone(),
+                ErrorCode::E114,
+                ErrorCode::E114_message(encoding, &vector.name),
+                Some(ErrorCode::E114_hint()),
+            );
+        }
+        if let Some((loc, 0)) = &vector.dimension {
+            push_schema_err(
+                ctx,
+                loc.clone(),
+                ErrorCode::E113,
+                ErrorCode::E113_message(&vector.name),
+                Some(ErrorCode::E113_hint(&vector.name)),
+            );
+        }
+        if vector.sparse {
+            let hnsw = vector.m.is_some()
+                || vector.ef_construction.is_some()
+                || vector.ef_search.is_some();
+            [
+                (vector.dimension.is_some(), "a dimension"),
+                (vector.metric.is_some(), "a distance metric"),
+                (vector.encoding.is_some(), "an encoding"),
+                (vector.multi, "`multi`"),
+                (hnsw, "HNSW parameters"),
+            ]
+            .into_iter()
+            .filter(|(given, _)| *given)
+            .for_each(|(_, argument)| {
+                push_schema_err(
+                    ctx,
+                    vector.loc.clone(),
+                    ErrorCode::E115,
+                    ErrorCode::E115_message(&vector.name, argument),
+                    Some(ErrorCode::E115_hint()),
+                );
+            });
+        }
         ctx.output.vectors.push(vector.clone().into());
     }
 }
+
+fn check_text_field(ctx: &mut Ctx, field: &Field, item_type: &str, item_type_name: &str) {
+    if field.text && field.field_type != FieldType::String {
+        push_schema_err(
+            ctx,
+            field.loc.clone(),
+            ErrorCode::E117,
+            ErrorCode::E117_message(
+                &field.name,
+                item_type,
+                item_type_name,
+                &field.field_type.to_string(),
+            ),
+            Some(ErrorCode::E117_hint()),
+        );
+    }
+    if let Some((loc, analyzer)) = &field.text_analyzer
+        && analyzer.parse::<TextAnalyzer>().is_err()
+    {
+        push_schema_err(
+            ctx,
+            loc.clone(),
+            ErrorCode::E118,
+            ErrorCode::E118_message(analyzer, &field.name, item_type, item_type_name),
+            Some(ErrorCode::E118_hint()),
+        );
+    }
+}
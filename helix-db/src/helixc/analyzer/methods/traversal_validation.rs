@@ -13,7 +13,9 @@ use crate::{
             },
             types::Type,
             utils::{
-                Variable, field_exists_on_item_type, gen_identifier_or_param, is_valid_identifier,
+                Variable, check_vector_dimension, check_vector_kind, field_exists_on_item_type,
+                gen_identifier_or_param, gen_pre_filter, gen_search_param, gen_search_radius,
+                gen_search_threads, gen_sparse_vector, is_multi_vector, is_valid_identifier,
                 type_in_scope,
             },
         },
@@ -21,7 +23,10 @@ use crate::{
             bool_op::{BoExp, BoolOp, Eq, Gt, Gte, Lt, Lte, Neq},
             object_remapping_generation::{ExcludeField, Remapping, RemappingType},
             queries::Query as GeneratedQuery,
-            source_steps::{EFromID, EFromType, NFromID, NFromIndex, NFromType, SourceStep},
+            source_steps::{
+                EFromID, EFromIndex, EFromType, NFromCompositeIndex, NFromID, NFromIndex, NFromIndexPrefix,
+                NFromIndexRange, NFromType, SourceStep,
+            },
             statements::Statement as GeneratedStatement,
             traversal_steps::{
                 OrderBy, Range, ShouldCollect, Step as GeneratedStep,
@@ -34,7 +39,7 @@ use crate::{
     protocol::value::Value,
 };
 use paste::paste;
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::Bound};
 
 /// Validates the traversal and returns the end type of the traversal
 ///
@@ -61,118 +66,169 @@ pub(crate) fn validate_traversal<'a>(
             }
             if let Some(ids) = ids {
                 assert!(ids.len() == 1, "multiple ids not supported yet");
+                let node_ty = Type::Node(Some(node_type.to_string()));
                 // check id exists in scope
                 match ids[0].clone() {
-                    IdType::ByIndex { index, value, loc } => {
-                        is_valid_identifier(
+                    // a field that is only indexed as the first field of a composite index
+                    IdType::ByIndex {
+                        index,
+                        value,
+                        loc: _,
+                    } if !is_indexed_field(ctx, node_type, &index.to_string())
+                            && let Some(composite) =
+                                find_composite_index(ctx, node_type, &[index.to_string()]) =>
+                    {
+                        gen_traversal.source_step = Separator::Period(gen_composite_index_lookup(
                             ctx,
                             original_query,
-                            loc.clone(),
-                            index.to_string().as_str(),
-                        );
-                        let corresponding_field = ctx.node_fields.get(node_type.as_str()).cloned();
-                        match corresponding_field {
-                            Some(node_fields) => {
-                                match node_fields
-                                    .iter()
-                                    .find(|(name, _)| name.to_string() == *index.to_string())
-                                {
-                                    Some((_, field)) => {
-                                        if !field.is_indexed() {
-                                            generate_error!(
-                                                ctx,
-                                                original_query,
-                                                loc.clone(),
-                                                E208,
-                                                [&index.to_string(), node_type],
-                                                [node_type]
-                                            );
-                                        } else if let ValueType::Literal { ref value, ref loc } =
-                                            *value
-                                            && !field.field_type.eq(value)
-                                        {
-                                            generate_error!(
-                                                ctx,
-                                                original_query,
-                                                loc.clone(),
-                                                E205,
-                                                &value.to_string(),
-                                                &field.field_type.to_string(),
-                                                "node",
-                                                node_type
-                                            );
-                                        }
-                                    }
-                                    None => {
-                                        generate_error!(
+                            scope,
+                            node_type,
+                            composite,
+                            vec![(*index, *value)],
+                        ));
+                        gen_traversal.should_collect = ShouldCollect::ToVec;
+                        gen_traversal.traversal_type = TraversalType::Ref;
+                        Type::Nodes(Some(node_type.to_string()))
+                    }
+                    IdType::ByIndex { index, value, loc } => {
+                        let field =
+                            validate_index_field(ctx, original_query, &node_ty, &index, &loc);
+                        if let Some(field) = &field {
+                            validate_index_value(
+                                ctx,
+                                original_query,
+                                scope,
+                                &node_ty,
+                                field,
+                                &value,
+                            );
+                        }
+                        gen_traversal.source_step =
+                            Separator::Period(SourceStep::NFromIndex(NFromIndex {
+                                label: GenRef::Literal(node_type.clone()),
+                                index: GenRef::Literal(String::from(*index)),
+                                key: gen_index_key(
+                                    ctx,
+                                    original_query,
+                                    scope,
+                                    *value,
+                                    field.as_ref(),
+                                ),
+                            }));
+                        gen_traversal.should_collect = ShouldCollect::ToVal;
+                        gen_traversal.traversal_type = TraversalType::Ref;
+                        Type::Node(Some(node_type.to_string()))
+                    }
+                    IdType::ByIndexRange { index, range, loc } => {
+                        let field =
+                            validate_index_field(ctx, original_query, &node_ty, &index, &loc);
+                        let label = GenRef::Literal(node_type.clone());
+                        let index = GenRef::Literal(String::from(*index));
+                        gen_traversal.source_step = Separator::Period(match *range {
+                            IndexRange::Bounds { start, end } => {
+                                let mut gen_bound = |bound: Bound<ValueType>| {
+                                    if let (Some(field), Bound::Included(value) | Bound::Excluded(value)) =
+                                        (&field, &bound)
+                                    {
+                                        validate_index_value(
                                             ctx,
                                             original_query,
-                                            loc.clone(),
-                                            E208,
-                                            [&index.to_string(), node_type],
-                                            [node_type]
+                                            scope,
+                                            &node_ty,
+                                            field,
+                                            value,
                                         );
                                     }
-                                }
+                                    bound.map(|value| {
+                                        gen_index_key(
+                                            ctx,
+                                            original_query,
+                                            scope,
+                                            value,
+                                            field.as_ref(),
+                                        )
+                                    })
+                                };
+                                let start = gen_bound(start);
+                                let end = gen_bound(end);
+                                SourceStep::NFromIndexRange(Box::new(NFromIndexRange {
+                                    label,
+                                    index,
+                                    start,
+                                    end,
+                                }))
                             }
-                            None => unreachable!(),
-                        };
-                        gen_traversal.source_step =
-                            Separator::Period(SourceStep::NFromIndex(NFromIndex {
-                                label: GenRef::Literal(node_type.clone()),
-                                index: GenRef::Literal(match *index {
-                                    IdType::Identifier { value, loc: _ } => value,
-                                    // would be caught by the parser
-                                    _ => unreachable!(),
-                                }),
-                                key: match *value {
-                                    ValueType::Identifier { value, loc } => {
-                                        if is_valid_identifier(
+                            IndexRange::Prefix(prefix) => {
+                                if let Some(field) = &field {
+                                    if !matches!(field.field_type, FieldType::String) {
+                                        generate_error!(
                                             ctx,
                                             original_query,
                                             loc.clone(),
-                                            value.as_str(),
-                                        ) && !scope.contains_key(value.as_str())
-                                        {
-                                            generate_error!(
-                                                ctx,
-                                                original_query,
-                                                loc.clone(),
-                                                E301,
-                                                value.as_str()
-                                            );
-                                        }
-                                        gen_identifier_or_param(
-                                            original_query,
-                                            value.as_str(),
-                                            true,
-                                            false,
-                                        )
-                                    }
-                                    ValueType::Literal { value, loc: _ } => {
-                                        GeneratedValue::Primitive(GenRef::Std(match value {
-                                            Value::String(s) => format!("\"{s}\""),
-                                            Value::I8(i) => i.to_string(),
-                                            Value::I16(i) => i.to_string(),
-                                            Value::I32(i) => i.to_string(),
-                                            Value::I64(i) => i.to_string(),
-                                            Value::U8(i) => i.to_string(),
-                                            Value::U16(i) => i.to_string(),
-                                            Value::U32(i) => i.to_string(),
-                                            Value::U64(i) => i.to_string(),
-                                            Value::U128(i) => i.to_string(),
-                                            Value::F32(i) => i.to_string(),
-                                            Value::F64(i) => i.to_string(),
-                                            Value::Boolean(b) => b.to_string(),
-                                            _ => unreachable!(),
-                                        }))
+                                            E210,
+                                            &field.name,
+                                            node_type,
+                                            &field.field_type.to_string()
+                                        );
                                     }
-                                    _ => unreachable!(),
-                                },
-                            }));
-                        gen_traversal.should_collect = ShouldCollect::ToVal;
+                                    validate_index_value(
+                                        ctx,
+                                        original_query,
+                                        scope,
+                                        &node_ty,
+                                        field,
+                                        &prefix,
+                                    );
+                                }
+                                SourceStep::NFromIndexPrefix(NFromIndexPrefix {
+                                    label,
+                                    index,
+                                    prefix: gen_index_key(
+                                        ctx,
+                                        original_query,
+                                        scope,
+                                        prefix,
+                                        field.as_ref(),
+                                    ),
+                                })
+                            }
+                        });
+                        gen_traversal.should_collect = ShouldCollect::ToVec;
                         gen_traversal.traversal_type = TraversalType::Ref;
-                        Type::Node(Some(node_type.to_string()))
+                        Type::Nodes(Some(node_type.to_string()))
+                    }
+                    IdType::ByCompositeIndex { fields, loc } => {
+                        let names = fields
+                            .iter()
+                            .map(|(field, _)| field.to_string())
+                            .collect::<Vec<_>>();
+                        match find_composite_index(ctx, node_type, &names) {
+                            Some(composite) => {
+                                gen_traversal.source_step =
+                                    Separator::Period(gen_composite_index_lookup(
+                                        ctx,
+                                        original_query,
+                                        scope,
+                                        node_type,
+                                        composite,
+                                        fields,
+                                    ));
+                            }
+                            None => {
+                                let names = names.join(", ");
+                                generate_error!(
+                                    ctx,
+                                    original_query,
+                                    loc.clone(),
+                                    E211,
+                                    [node_type, &names],
+                                    [&names, node_type]
+                                );
+                            }
+                        }
+                        gen_traversal.should_collect = ShouldCollect::ToVec;
+                        gen_traversal.traversal_type = TraversalType::Ref;
+                        Type::Nodes(Some(node_type.to_string()))
                     }
                     IdType::Identifier { value: i, loc } => {
                         gen_traversal.source_step =
@@ -230,24 +286,77 @@ pub(crate) fn validate_traversal<'a>(
             }
             if let Some(ids) = ids {
                 assert!(ids.len() == 1, "multiple ids not supported yet");
-                gen_traversal.source_step = Separator::Period(SourceStep::EFromID(EFromID {
-                    id: match ids[0].clone() {
-                        IdType::Identifier { value: i, loc } => {
-                            is_valid_identifier(ctx, original_query, loc.clone(), i.as_str());
-                            let _ =
-                                type_in_scope(ctx, original_query, loc.clone(), scope, i.as_str());
-                            let value =
-                                gen_identifier_or_param(original_query, i.as_str(), true, false);
-                            value.inner().clone()
+                match ids[0].clone() {
+                    IdType::ByIndex { index, value, loc } => {
+                        let edge_ty = Type::Edge(Some(edge_type.to_string()));
+                        let field =
+                            validate_index_field(ctx, original_query, &edge_ty, &index, &loc);
+                        if let Some(field) = &field {
+                            validate_index_value(
+                                ctx,
+                                original_query,
+                                scope,
+                                &edge_ty,
+                                field,
+                                &value,
+                            );
                         }
-                        IdType::Literal { value: s, loc: _ } => GenRef::Std(s),
-                        _ => unreachable!(),
-                    },
-                    label: GenRef::Literal(edge_type.clone()),
-                }));
-                gen_traversal.traversal_type = TraversalType::Ref;
-                gen_traversal.should_collect = ShouldCollect::ToVal;
-                Type::Edge(Some(edge_type.to_string()))
+                        gen_traversal.source_step =
+                            Separator::Period(SourceStep::EFromIndex(EFromIndex {
+                                label: GenRef::Literal(edge_type.clone()),
+                                index: GenRef::Literal(String::from(*index)),
+                                key: gen_index_key(
+                                    ctx,
+                                    original_query,
+                                    scope,
+                                    *value,
+                                    field.as_ref(),
+                                ),
+                            }));
+                        gen_traversal.should_collect = ShouldCollect::ToVec;
+                        gen_traversal.traversal_type = TraversalType::Ref;
+                        Type::Edges(Some(edge_type.to_string()))
+                    }
+                    IdType::ByIndexRange { loc, .. } => {
+                        generate_error!(ctx, original_query, loc.clone(), E212, edge_type);
+                        Type::Edges(Some(edge_type.to_string()))
+                    }
+                    id => {
+                        gen_traversal.source_step =
+                            Separator::Period(SourceStep::EFromID(EFromID {
+                                id: match id {
+                                    IdType::Identifier { value: i, loc } => {
+                                        is_valid_identifier(
+                                            ctx,
+                                            original_query,
+                                            loc.clone(),
+                                            i.as_str(),
+                                        );
+                                        let _ = type_in_scope(
+                                            ctx,
+                                            original_query,
+                                            loc.clone(),
+                                            scope,
+                                            i.as_str(),
+                                        );
+                                        let value = gen_identifier_or_param(
+                                            original_query,
+                                            i.as_str(),
+                                            true,
+                                            false,
+                                        );
+                                        value.inner().clone()
+                                    }
+                                    IdType::Literal { value: s, loc: _ } => GenRef::Std(s),
+                                    _ => unreachable!(),
+                                },
+                                label: GenRef::Literal(edge_type.clone()),
+                            }));
+                        gen_traversal.traversal_type = TraversalType::Ref;
+                        gen_traversal.should_collect = ShouldCollect::ToVal;
+                        Type::Edge(Some(edge_type.to_string()))
+                    }
+                }
             } else {
                 gen_traversal.source_step = Separator::Period(SourceStep::EFromType(EFromType {
                     label: GenRef::Literal(edge_type.clone()),
@@ -295,12 +404,23 @@ pub(crate) fn validate_traversal<'a>(
             {
                 generate_error!(ctx, original_query, sv.loc.clone(), E103, ty.as_str());
             }
+            if let (Some(ty), Some(data)) = (&sv.vector_type, &sv.data) {
+                check_vector_kind(ctx, original_query, sv.loc.clone(), ty, data, "SearchV");
+                check_vector_dimension(
+                    ctx,
+                    original_query,
+                    sv.loc.clone(),
+                    ty,
+                    data,
+                    gen_query.embedding_model_to_use.as_deref(),
+                );
+            }
             let vec: VecData = match &sv.data {
                 Some(VectorData::Vector(v)) => {
                     VecData::Standard(GeneratedValue::Literal(GenRef::Ref(format!(
                         "[{}]",
                         v.iter()
-                            .map(|f| f.to_string())
+                            .map(|f| format!("{f:?}"))
                             .collect::<Vec<String>>()
                             .join(",")
                     ))))
@@ -330,6 +450,7 @@ pub(crate) fn validate_traversal<'a>(
 
                     VecData::Hoisted(gen_query.add_hoisted_embed(embed_data))
                 }
+                Some(VectorData::Sparse(sparse)) => gen_sparse_vector(ctx, original_query, sparse),
                 _ => {
                     generate_error!(
                         ctx,
@@ -388,73 +509,63 @@ pub(crate) fn validate_traversal<'a>(
                         GeneratedValue::Unknown
                     }
                 },
+                // a search within a radius has no k
+                None if sv.radius.is_some() => GeneratedValue::Unknown,
                 None => {
                     generate_error!(ctx, original_query, sv.loc.clone(), E601, &sv.loc.span);
                     GeneratedValue::Unknown
                 }
             };
 
-            // let pre_filter: Option<Vec<BoExp>> = match &sv.pre_filter {
-            //     Some(expr) => {
-            //         let (_, stmt) = infer_expr_type(
-            //             ctx,
-            //             expr,
-            //             scope,
-            //             original_query,
-            //             Some(Type::Vector(sv.vector_type.clone())),
-            //             gen_query,
-            //         );
-            //         // Where/boolean ops don't change the element type,
-            //         // so `cur_ty` stays the same.
-            //         assert!(stmt.is_some());
-            //         let stmt = stmt.unwrap();
-            //         let mut gen_traversal = GeneratedTraversal {
-            //             traversal_type: TraversalType::NestedFrom(GenRef::Std("v".to_string())),
-            //             steps: vec![],
-            //             should_collect: ShouldCollect::ToVec,
-            //             source_step: Separator::Empty(SourceStep::Anonymous),
-            //         };
-            //         match stmt {
-            //             GeneratedStatement::Traversal(tr) => {
-            //                 gen_traversal
-            //                     .steps
-            //                     .push(Separator::Period(GeneratedStep::Where(Where::Ref(
-            //                         WhereRef {
-            //                             expr: BoExp::Expr(tr),
-            //                         },
-            //                     ))));
-            //             }
-            //             GeneratedStatement::BoExp(expr) => {
-            //                 gen_traversal
-            //                     .steps
-            //                     .push(Separator::Period(GeneratedStep::Where(match expr {
-            //                         BoExp::Exists(mut traversal) => {
-            //                             traversal.should_collect = ShouldCollect::No;
-            //                             Where::Ref(WhereRef {
-            //                                 expr: BoExp::Exists(traversal),
-            //                             })
-            //                         }
-            //                         _ => Where::Ref(WhereRef { expr }),
-            //                     })));
-            //             }
-            //             _ => unreachable!(),
-            //         }
-            //         Some(vec![BoExp::Expr(gen_traversal)])
-            //     }
-            //     None => None,
-            // };
-            let pre_filter = None;
+            let pre_filter = sv.pre_filter.as_ref().map(|expr| {
+                vec![gen_pre_filter(
+                    ctx,
+                    expr,
+                    scope,
+                    original_query,
+                    &sv.vector_type,
+                    gen_query,
+                )]
+            });
+
+            let threads = gen_search_threads(ctx, original_query, sv);
+            if threads.is_some() && !tr.steps.is_empty() {
+                generate_error!(
+                    ctx,
+                    original_query,
+                    tr.loc.clone(),
+                    E215,
+                    "cannot be followed by other steps"
+                );
+            }
 
             gen_traversal.traversal_type = TraversalType::Ref;
-            gen_traversal.should_collect = ShouldCollect::ToVec;
-            gen_traversal.source_step = Separator::Period(SourceStep::SearchVector(SearchVector {
+            gen_traversal.should_collect = match threads {
+                Some(_) => ShouldCollect::Try,
+                None => ShouldCollect::ToVec,
+            };
+            gen_traversal.source_step = Separator::Period(SourceStep::SearchVector(Box::new(SearchVector {
                 label: GenRef::Literal(sv.vector_type.clone().unwrap()),
                 vec,
                 k,
+                radius: sv
+                    .radius
+                    .as_ref()
+                    .map(|radius| gen_search_radius(ctx, original_query, radius)),
+                ef: sv.ef.as_ref().map(|ef| gen_search_param(ctx, original_query, ef)),
+                candidates: sv
+                    .candidates
+                    .as_ref()
+                    .map(|candidates| gen_search_param(ctx, original_query, candidates)),
                 pre_filter,
-            }));
+                threads: threads.clone(),
+                multi: is_multi_vector(ctx, &sv.vector_type),
+            })));
             // Search returns nodes that contain the vectors
-            Type::Vectors(sv.vector_type.clone())
+            match threads {
+                Some(_) => Type::VectorBatches(sv.vector_type.clone()),
+                None => Type::Vectors(sv.vector_type.clone()),
+            }
         }
     };
 
@@ -1075,7 +1186,10 @@ pub(crate) fn validate_traversal<'a>(
                         })
                         .collect(),
                 ));
-                gen_traversal.should_collect = ShouldCollect::No;
+                gen_traversal.should_collect = match cur_ty {
+                    Type::Nodes(_) | Type::Edges(_) => ShouldCollect::TryToVec,
+                    _ => ShouldCollect::TryToVal,
+                };
                 excluded.clear();
             }
 
@@ -1299,3 +1413,197 @@ pub(crate) fn validate_traversal<'a>(
     }
     cur_ty
 }
+
+/// Checks that `index` is an indexed field of the node or edge type `item_type` and returns the field.
+fn validate_index_field<'a>(
+    ctx: &mut Ctx<'a>,
+    original_query: &'a Query,
+    item_type: &Type,
+    index: &IdType,
+    loc: &Loc,
+) -> Option<Field> {
+    let index = index.to_string();
+    is_valid_identifier(ctx, original_query, loc.clone(), index.as_str());
+    // unknown item types are reported by the caller
+    match ctx.get_item_fields(item_type)?.get(index.as_str()) {
+        Some(field) if field.is_indexed() => Some(field.clone().into_owned()),
+        _ => {
+            let type_name = item_type.get_type_name();
+            generate_error!(
+                ctx,
+                original_query,
+                loc.clone(),
+                E208,
+                [&index, item_type.kind_str(), &type_name],
+                [item_type.kind_str(), &type_name]
+            );
+            None
+        }
+    }
+}
+
+/// Whether `field` of `node_type` is declared `INDEX` on its own.
+fn is_indexed_field(ctx: &Ctx, node_type: &str, field: &str) -> bool {
+    ctx.node_fields
+        .get(node_type)
+        .and_then(|fields| fields.get(field))
+        .is_some_and(|field| field.is_indexed())
+}
+
+/// Finds a composite index of `node_type` whose leading fields are `fields`, in any order.
+fn find_composite_index<'a>(
+    ctx: &Ctx<'a>,
+    node_type: &str,
+    fields: &[String],
+) -> Option<&'a CompositeIndex> {
+    ctx.src
+        .get_latest_schema()
+        .node_schemas
+        .iter()
+        .find(|schema| schema.name.1 == node_type)?
+        .composite_indices
+        .iter()
+        .find(|index| {
+            index.fields.len() >= fields.len()
+                && index.fields[..fields.len()]
+                    .iter()
+                    .all(|(_, field)| fields.contains(field))
+        })
+}
+
+/// Generates the lookup of `fields` in the composite `index`, keyed in the order of the index's fields.
+fn gen_composite_index_lookup<'a>(
+    ctx: &mut Ctx<'a>,
+    original_query: &'a Query,
+    scope: &HashMap<&'a str, Type>,
+    node_type: &str,
+    index: &CompositeIndex,
+    fields: Vec<(IdType, ValueType)>,
+) -> SourceStep {
+    let node_ty = Type::Node(Some(node_type.to_string()));
+    let mut keys = Vec::with_capacity(fields.len());
+    for (_, name) in &index.fields[..fields.len()] {
+        let Some((_, value)) = fields.iter().find(|(field, _)| field.to_string() == *name) else {
+            continue;
+        };
+        let field = ctx
+            .get_item_fields(&node_ty)
+            .and_then(|fields| fields.get(name.as_str()))
+            .map(|field| field.clone().into_owned());
+        if let Some(field) = &field {
+            validate_index_value(ctx, original_query, scope, &node_ty, field, value);
+        }
+        keys.push(gen_index_key(ctx, original_query, scope, value.clone(), field.as_ref()));
+    }
+    SourceStep::NFromCompositeIndex(NFromCompositeIndex {
+        label: GenRef::Literal(node_type.to_string()),
+        index: GenRef::Literal(index.name()),
+        keys,
+    })
+}
+
+/// Checks that a value looked up in a secondary index is of the type of the indexed field.
+///
+/// Integers and floats are keyed apart, so a lookup of one never finds the other.
+/// Integer literals are still accepted for float fields, as `gen_index_key` writes them as floats.
+fn validate_index_value<'a>(
+    ctx: &mut Ctx<'a>,
+    original_query: &'a Query,
+    scope: &HashMap<&'a str, Type>,
+    item_type: &Type,
+    field: &Field,
+    value: &ValueType,
+) {
+    let mismatch = match value {
+        ValueType::Literal { value, loc }
+            if !(field.field_type.eq(value)
+                || is_float(&field.field_type) && integer_as_float(value).is_some()) =>
+        {
+            Some((value.to_string(), loc))
+        }
+        ValueType::Identifier { value, loc } => match scope.get(value.as_str()) {
+            Some(Type::Scalar(ty)) if *ty != field.field_type => Some((value.clone(), loc)),
+            _ => None,
+        },
+        _ => None,
+    };
+    if let Some((value, loc)) = mismatch {
+        generate_error!(
+            ctx,
+            original_query,
+            loc.clone(),
+            E205,
+            &value,
+            &field.field_type.to_string(),
+            item_type.kind_str(),
+            &item_type.get_type_name()
+        );
+    }
+}
+
+/// Generates a reference to the key looked up in a secondary index,
+/// with integer literals written as floats when the indexed field is a float.
+fn gen_index_key<'a>(
+    ctx: &mut Ctx<'a>,
+    original_query: &'a Query,
+    scope: &HashMap<&'a str, Type>,
+    value: ValueType,
+    field: Option<&Field>,
+) -> GeneratedValue {
+    match value {
+        ValueType::Identifier { value, loc } => {
+            if is_valid_identifier(ctx, original_query, loc.clone(), value.as_str())
+                && !scope.contains_key(value.as_str())
+            {
+                generate_error!(ctx, original_query, loc.clone(), E301, value.as_str());
+            }
+            gen_identifier_or_param(original_query, value.as_str(), true, false)
+        }
+        ValueType::Literal { value, loc: _ } => {
+            if let Some(field) = field
+                && is_float(&field.field_type)
+                && let Some(f) = integer_as_float(&value)
+            {
+                return GeneratedValue::Primitive(GenRef::Std(format!("&{f:?}")));
+            }
+            GeneratedValue::Primitive(GenRef::Std(match value {
+                Value::String(s) => format!("&{s:?}"),
+                Value::I8(i) => format!("&{i}"),
+                Value::I16(i) => format!("&{i}"),
+                Value::I32(i) => format!("&{i}"),
+                Value::I64(i) => format!("&{i}"),
+                Value::U8(i) => format!("&{i}"),
+                Value::U16(i) => format!("&{i}"),
+                Value::U32(i) => format!("&{i}"),
+                Value::U64(i) => format!("&{i}"),
+                Value::U128(i) => format!("&{i}"),
+                // debug formatting keeps the decimal point so whole numbers stay floats
+                Value::F32(f) => format!("&{f:?}"),
+                Value::F64(f) => format!("&{f:?}"),
+                Value::Boolean(b) => format!("&{b}"),
+                _ => unreachable!(),
+            }))
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn is_float(field_type: &FieldType) -> bool {
+    matches!(field_type, FieldType::F32 | FieldType::F64)
+}
+
+/// The value of an integer literal as a float, `None` for other literals
+fn integer_as_float(value: &Value) -> Option<f64> {
+    match value {
+        Value::I8(i) => Some(*i as f64),
+        Value::I16(i) => Some(*i as f64),
+        Value::I32(i) => Some(*i as f64),
+        Value::I64(i) => Some(*i as f64),
+        Value::U8(i) => Some(*i as f64),
+        Value::U16(i) => Some(*i as f64),
+        Value::U32(i) => Some(*i as f64),
+        Value::U64(i) => Some(*i as f64),
+        Value::U128(i) => Some(*i as f64),
+        _ => None,
+    }
+}
@@ -238,6 +238,8 @@ pub(crate) enum Type {
     Edges(Option<String>),
     Vector(Option<String>),
     Vectors(Option<String>),
+    /// The results of a `SearchV` over an array of query vectors, one list of vectors per query
+    VectorBatches(Option<String>),
     Scalar(FieldType),
     Object(HashMap<String, Type>),
     Anonymous(Box<Type>),
@@ -254,6 +256,7 @@ impl Type {
             Type::Edges(_) => "edges",
             Type::Vector(_) => "vector",
             Type::Vectors(_) => "vectors",
+            Type::VectorBatches(_) => "vector batches",
             Type::Scalar(_) => "scalar",
             Type::Object(_) => "object",
             Type::Boolean => "boolean",
@@ -270,6 +273,7 @@ impl Type {
             Type::Edges(Some(name)) => name.clone(),
             Type::Vector(Some(name)) => name.clone(),
             Type::Vectors(Some(name)) => name.clone(),
+            Type::VectorBatches(Some(name)) => name.clone(),
             Type::Scalar(ft) => ft.to_string(),
             Type::Anonymous(ty) => ty.get_type_name(),
             Type::Boolean => "boolean".to_string(),
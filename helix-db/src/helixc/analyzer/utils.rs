@@ -3,10 +3,17 @@ use crate::helixc::analyzer::error_codes::ErrorCode;
 use crate::{
     generate_error,
     helixc::{
-        analyzer::{analyzer::Ctx, errors::push_query_err, types::Type},
+        analyzer::{
+            analyzer::Ctx, errors::push_query_err, methods::infer_expr_type::infer_expr_type,
+            types::Type,
+        },
         generator::{
-            traversal_steps::Step,
-            utils::{GenRef, GeneratedValue},
+            bool_op::BoExp,
+            queries::Query as GeneratedQuery,
+            statements::Statement as GeneratedStatement,
+            traversal_steps::{ShouldCollect, Step},
+            source_steps::FusionMethod,
+            utils::{GenRef, GeneratedValue, VecData},
         },
         parser::{helix_parser::*, location::Loc},
     },
@@ -261,3 +268,336 @@ impl FieldLookup for Type {
         (is_valid_field, item_type.to_string())
     }
 }
+
+/// Generates the value of a `SearchV` tuning argument, an integer or a parameter
+pub(super) fn gen_search_param(
+    ctx: &mut Ctx,
+    original_query: &Query,
+    param: &EvaluatesToNumber,
+) -> GeneratedValue {
+    match &param.value {
+        EvaluatesToNumberType::Identifier(i) => {
+            is_valid_identifier(ctx, original_query, param.loc.clone(), i.as_str());
+            gen_identifier_or_param(original_query, i, false, true)
+        }
+        EvaluatesToNumberType::I32(i) => GeneratedValue::Primitive(GenRef::Std(i.to_string())),
+        // the parser reads tuning arguments as integers or identifiers
+        _ => unreachable!(),
+    }
+}
+
+/// Generates the radius of a `SearchV` within a distance, a number or a parameter
+pub(super) fn gen_search_radius(
+    ctx: &mut Ctx,
+    original_query: &Query,
+    radius: &EvaluatesToNumber,
+) -> GeneratedValue {
+    match &radius.value {
+        EvaluatesToNumberType::Identifier(i) => {
+            is_valid_identifier(ctx, original_query, radius.loc.clone(), i.as_str());
+            gen_identifier_or_param(original_query, i, false, true)
+        }
+        EvaluatesToNumberType::F64(f) => GeneratedValue::Primitive(GenRef::Std(format!("{f:?}"))),
+        // the parser reads radiuses as floats or identifiers
+        _ => unreachable!(),
+    }
+}
+
+/// Whether a `SearchV` searches an array of query vectors, passed as a `[[F64]]` parameter
+pub(super) fn is_vector_batch(original_query: &Query, data: &VectorData) -> bool {
+    match data {
+        VectorData::Identifier(i) => is_param(original_query, i).is_some_and(|param| {
+            matches!(&param.param_type.1, FieldType::Array(inner) if matches!(**inner, FieldType::Array(_)))
+        }),
+        _ => false,
+    }
+}
+
+/// Whether the vector type holds documents of several vectors
+pub(super) fn is_multi_vector(ctx: &Ctx, vector_type: &Option<String>) -> bool {
+    vector_type
+        .as_deref()
+        .is_some_and(|ty| ctx.multi_vectors.contains(ty))
+}
+
+/// Whether the vector type holds sparse vectors
+pub(super) fn is_sparse_vector(ctx: &Ctx, vector_type: &Option<String>) -> bool {
+    vector_type
+        .as_deref()
+        .is_some_and(|ty| ctx.sparse_vectors.contains(ty))
+}
+
+/// Checks that a sparse vector is given to a sparse vector type, and any other vector data
+/// to a dense one, returning whether it is
+pub(super) fn check_vector_kind(
+    ctx: &mut Ctx,
+    original_query: &Query,
+    loc: Loc,
+    vector_type: &str,
+    data: &VectorData,
+    step: &str,
+) -> bool {
+    let sparse = ctx.sparse_vectors.contains(vector_type);
+    if sparse == matches!(data, VectorData::Sparse(_)) {
+        return true;
+    }
+    let (kind, given) = match sparse {
+        true => ("sparse", "dense"),
+        false => ("dense", "sparse"),
+    };
+    generate_error!(ctx, original_query, loc, E219, vector_type, kind, step, given);
+    false
+}
+
+/// Generates the indices and weights of a sparse vector, which must be passed in as
+/// `[U32]` and `[F64]` parameters
+pub(super) fn gen_sparse_vector(
+    ctx: &mut Ctx,
+    original_query: &Query,
+    sparse: &SparseVectorData,
+) -> VecData {
+    let is_array_of = |name: &str, element: fn(&FieldType) -> bool| {
+        is_param(original_query, name).is_some_and(|param| {
+            matches!(&param.param_type.1, FieldType::Array(inner) if element(inner))
+        })
+    };
+    let args = [
+        (
+            &sparse.indices,
+            "[U32]",
+            is_array_of(&sparse.indices, |ty| matches!(ty, FieldType::U32)),
+        ),
+        (
+            &sparse.weights,
+            "[F64]",
+            is_array_of(&sparse.weights, |ty| matches!(ty, FieldType::F64)),
+        ),
+    ];
+    for (argument, expected, valid) in args {
+        if is_valid_identifier(ctx, original_query, sparse.loc.clone(), argument) && !valid {
+            generate_error!(
+                ctx,
+                original_query,
+                sparse.loc.clone(),
+                E220,
+                [argument, expected],
+                [argument, expected]
+            );
+        }
+    }
+    VecData::Sparse {
+        indices: gen_identifier_or_param(original_query, &sparse.indices, true, false),
+        weights: gen_identifier_or_param(original_query, &sparse.weights, true, false),
+    }
+}
+
+/// Checks that the type of a `SearchHybrid` is a vector type with `TEXT` fields to search
+/// the text of, returning whether it is
+pub(super) fn check_hybrid_search_type(
+    ctx: &mut Ctx,
+    original_query: &Query,
+    loc: Loc,
+    vector_type: &str,
+) -> bool {
+    let reason = if ctx.node_set.contains(vector_type) {
+        "is a node type, which has no vectors"
+    } else if !ctx.vector_set.contains(vector_type) {
+        generate_error!(ctx, original_query, loc, E103, vector_type);
+        return false;
+    } else if ctx.multi_vectors.contains(vector_type) {
+        "holds multi-vector documents"
+    } else if !ctx
+        .vector_fields
+        .get(vector_type)
+        .is_some_and(|fields| fields.values().any(|field| field.text))
+    {
+        "has no `TEXT` fields"
+    } else {
+        return true;
+    };
+    generate_error!(ctx, original_query, loc, E222, vector_type, reason);
+    false
+}
+
+/// Generates how a `SearchHybrid` fuses its rankings, a weighted blend by default
+pub(super) fn gen_fusion(ctx: &mut Ctx, original_query: &Query, fusion: &Fusion) -> FusionMethod {
+    let gen_number = |ctx: &mut Ctx, number: &EvaluatesToNumber| match &number.value {
+        EvaluatesToNumberType::Identifier(i) => {
+            is_valid_identifier(ctx, original_query, number.loc.clone(), i.as_str());
+            gen_identifier_or_param(original_query, i, false, true)
+        }
+        EvaluatesToNumberType::F64(f) => GeneratedValue::Primitive(GenRef::Std(format!("{f:?}"))),
+        EvaluatesToNumberType::I32(i) => GeneratedValue::Primitive(GenRef::Std(format!("{i}.0"))),
+        // the parser reads alphas as floats and rrf's k as integers, or either as identifiers
+        _ => unreachable!(),
+    };
+    match fusion {
+        Fusion::Weighted(alpha) => FusionMethod::Weighted(gen_number(ctx, alpha)),
+        Fusion::ReciprocalRank(k) => {
+            FusionMethod::ReciprocalRank(k.as_ref().map(|k| gen_number(ctx, k)))
+        }
+    }
+}
+
+/// Generates the threads of a `SearchV` over an array of query vectors, one by default,
+/// or `None` for a search of a single query vector.
+///
+/// The array of query vectors of a search of a multi-vector type is searched as one,
+/// so it has no threads either, and neither has a search of a sparse vector type, which
+/// only takes a `k` and a `PREFILTER`.
+pub(super) fn gen_search_threads(
+    ctx: &mut Ctx,
+    original_query: &Query,
+    sv: &SearchVector,
+) -> Option<GeneratedValue> {
+    let batch = sv
+        .data
+        .as_ref()
+        .is_some_and(|data| is_vector_batch(original_query, data));
+    if let Some(ty) = sv.vector_type.as_deref().filter(|_| is_sparse_vector(ctx, &sv.vector_type)) {
+        let unsupported = [
+            (&sv.radius, "cannot search within a radius"),
+            (&sv.threads, "cannot split its query across `threads`"),
+            (&sv.ef, "takes no `ef`, having no HNSW index"),
+            (&sv.candidates, "takes no `candidates`, having no HNSW index"),
+        ];
+        for (param, reason) in unsupported {
+            if let Some(param) = param {
+                generate_error!(ctx, original_query, param.loc.clone(), E221, ty, reason);
+            }
+        }
+        return None;
+    }
+    if let Some(ty) = sv.vector_type.as_deref().filter(|_| is_multi_vector(ctx, &sv.vector_type)) {
+        // a sparse vector is reported by `check_vector_kind`
+        if !batch && !matches!(sv.data, Some(VectorData::Sparse(_))) {
+            generate_error!(ctx, original_query, sv.loc.clone(), E216, ty, "SearchV");
+        }
+        if sv.radius.is_some() {
+            generate_error!(
+                ctx,
+                original_query,
+                sv.loc.clone(),
+                E218,
+                ty,
+                "cannot search within a radius"
+            );
+        }
+        if let Some(threads) = &sv.threads {
+            generate_error!(
+                ctx,
+                original_query,
+                threads.loc.clone(),
+                E218,
+                ty,
+                "cannot split its query vectors across `threads`"
+            );
+        }
+        return None;
+    }
+    match (batch, &sv.threads) {
+        (false, Some(threads)) => {
+            generate_error!(ctx, original_query, threads.loc.clone(), E214);
+            None
+        }
+        (false, None) => None,
+        (true, threads) => {
+            if sv.radius.is_some() {
+                generate_error!(
+                    ctx,
+                    original_query,
+                    sv.loc.clone(),
+                    E215,
+                    "cannot search within a radius"
+                );
+            }
+            Some(match threads {
+                Some(threads) => gen_search_param(ctx, original_query, threads),
+                None => GeneratedValue::Primitive(GenRef::Std("1".to_string())),
+            })
+        }
+    }
+}
+
+/// Generates the filter `SearchV` applies to each vector it reaches, from the expression
+/// of its `PREFILTER`, which is checked like a `WHERE` on the vector type
+pub(super) fn gen_pre_filter<'a>(
+    ctx: &mut Ctx<'a>,
+    expr: &'a Expression,
+    scope: &mut HashMap<&'a str, Type>,
+    original_query: &'a Query,
+    vector_type: &Option<String>,
+    gen_query: &mut GeneratedQuery,
+) -> BoExp {
+    let (_, stmt) = infer_expr_type(
+        ctx,
+        expr,
+        scope,
+        original_query,
+        Some(Type::Vector(vector_type.clone())),
+        gen_query,
+    );
+    match stmt {
+        Some(GeneratedStatement::Traversal(traversal)) => BoExp::Expr(traversal),
+        Some(GeneratedStatement::BoExp(BoExp::Exists(mut traversal))) => {
+            traversal.should_collect = ShouldCollect::No;
+            BoExp::Exists(traversal)
+        }
+        Some(GeneratedStatement::BoExp(expr)) => expr,
+        _ => BoExp::Empty,
+    }
+}
+
+/// Checks a literal vector, or the output of an embedding model of known size,
+/// against the dimension declared for the vector type.
+///
+/// Vectors passed in as parameters are checked when they are written.
+pub(super) fn check_vector_dimension(
+    ctx: &mut Ctx,
+    original_query: &Query,
+    loc: Loc,
+    vector_type: &str,
+    data: &VectorData,
+    embedding_model: Option<&str>,
+) {
+    let Some(&expected) = ctx.vector_dimensions.get(vector_type) else {
+        return;
+    };
+    let (vector, found) = match (data, embedding_model) {
+        (VectorData::Vector(v), _) => ("vector literal".to_string(), v.len()),
+        (VectorData::Embed(_), Some(model)) => {
+            // the model name keeps its quotes when given as a string literal
+            let model = model.trim_matches('"');
+            match embedding_dimension(model) {
+                Some(found) => (format!("`Embed` output of model `{model}`"), found),
+                None => return,
+            }
+        }
+        _ => return,
+    };
+    if found != expected {
+        let (found, expected) = (found.to_string(), expected.to_string());
+        generate_error!(
+            ctx,
+            original_query,
+            loc,
+            E213,
+            [&vector, &found, vector_type, &expected],
+            [&expected]
+        );
+    }
+}
+
+/// Length of the embeddings of a model named as in `#[model(...)]`, if known ahead of time
+fn embedding_dimension(model: &str) -> Option<usize> {
+    let name = match model.strip_prefix("gemini:") {
+        Some(rest) => rest.split(':').next().unwrap_or_default(),
+        None => model.strip_prefix("openai:").unwrap_or(model),
+    };
+    match name {
+        "text-embedding-ada-002" | "text-embedding-3-small" => Some(1536),
+        "text-embedding-3-large" | "gemini-embedding-001" => Some(3072),
+        "text-embedding-004" => Some(768),
+        _ => None,
+    }
+}
@@ -32,6 +32,13 @@ impl Display for ReturnValue {
                     name, self.value
                 )
             }
+            ReturnType::NamedBatches(name) => {
+                writeln!(
+                    f,
+                    "    return_vals.insert({}.to_string(), ReturnValue::Array({}.into_iter().map(|results| ReturnValue::from_traversal_value_array_with_mixin(results, remapping_vals.borrow_mut())).collect()));",
+                    name, self.value
+                )
+            }
             ReturnType::SingleExpr(name) => {
                 writeln!(
                     f,
@@ -53,6 +60,7 @@ impl ReturnValue {
             ReturnType::Literal(name) => name.inner().inner().to_string(),
             ReturnType::NamedLiteral(name) => name.inner().inner().to_string(),
             ReturnType::NamedExpr(name) => name.inner().inner().to_string(),
+            ReturnType::NamedBatches(name) => name.inner().inner().to_string(),
             ReturnType::SingleExpr(name) => name.inner().inner().to_string(),
             ReturnType::UnnamedExpr => todo!(),
         }
@@ -76,6 +84,13 @@ impl ReturnValue {
             return_type: ReturnType::NamedExpr(name),
         }
     }
+    /// A list of results per query, as returned by a `SearchV` over an array of query vectors
+    pub fn new_named_batches(name: GeneratedValue, value: ReturnValueExpr) -> Self {
+        Self {
+            value,
+            return_type: ReturnType::NamedBatches(name),
+        }
+    }
     pub fn new_single_named(name: GeneratedValue, value: ReturnValueExpr) -> Self {
         Self {
             value,
@@ -95,6 +110,7 @@ pub enum ReturnType {
     Literal(GeneratedValue),
     NamedLiteral(GeneratedValue),
     NamedExpr(GeneratedValue),
+    NamedBatches(GeneratedValue),
     SingleExpr(GeneratedValue),
     UnnamedExpr,
 }
@@ -1,5 +1,5 @@
 use core::fmt;
-use std::fmt::Display;
+use std::{fmt::Display, ops::Bound};
 
 use crate::helixc::generator::utils::{write_properties, write_secondary_indices, VecData};
 
@@ -16,11 +16,16 @@ pub enum SourceStep {
     AddV(AddV),
     NFromID(NFromID),
     NFromIndex(NFromIndex),
+    NFromIndexRange(Box<NFromIndexRange>),
+    NFromIndexPrefix(NFromIndexPrefix),
+    NFromCompositeIndex(NFromCompositeIndex),
     NFromType(NFromType),
     EFromID(EFromID),
     EFromType(EFromType),
-    SearchVector(SearchVector),
+    EFromIndex(EFromIndex),
+    SearchVector(Box<SearchVector>),
     SearchBM25(SearchBM25),
+    SearchHybrid(Box<SearchHybrid>),
     Anonymous,
     Empty,
 }
@@ -68,12 +73,19 @@ pub struct AddV {
     pub vec: VecData,
     pub label: GenRef<String>,
     pub properties: Option<Vec<(String, GeneratedValue)>>,
+    /// Whether the vector type holds documents of several vectors, `vec` being their vectors
+    pub multi: bool,
 }
 impl Display for AddV {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "insert_v::<fn(&HVector, &RoTxn) -> bool>({}, {}, {})",
+            "{}::<fn(&HVector, &RoTxn) -> bool>({}, {}, {})",
+            match (&self.vec, self.multi) {
+                (VecData::Sparse { .. }, _) => "insert_sparse_v",
+                (_, true) => "insert_multi_v",
+                (_, false) => "insert_v",
+            },
             self.vec,
             self.label,
             write_properties(&self.properties)
@@ -125,16 +137,109 @@ impl Display for EFromType {
     }
 }
 
+#[derive(Clone)]
+pub struct EFromIndex {
+    pub index: GenRef<String>,
+    pub key: GeneratedValue,
+    pub label: GenRef<String>,
+}
+
+impl Display for EFromIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "e_from_index({}, {}, {})", self.label, self.index, self.key)
+    }
+}
+
 #[derive(Clone)]
 pub struct SearchBM25 {
     pub type_arg: GenRef<String>,
     pub query: GeneratedValue,
     pub k: GeneratedValue,
+    /// How the words of the query are expanded, exactly matched if `None`
+    pub expansion: Option<ExpansionMethod>,
+}
+
+#[derive(Clone)]
+pub enum ExpansionMethod {
+    Prefix,
+    Fuzzy(u32),
+}
+
+impl Display for ExpansionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExpansionMethod::Prefix => write!(f, "TermExpansion::Prefix"),
+            ExpansionMethod::Fuzzy(distance) => write!(f, "TermExpansion::Fuzzy({distance})"),
+        }
+    }
 }
 
 impl Display for SearchBM25 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "search_bm25({}, {}, {})?", self.type_arg, self.query, self.k)
+        match &self.expansion {
+            Some(expansion) => write!(
+                f,
+                "search_bm25_expanded({}, {}, {}, {expansion})?",
+                self.type_arg, self.query, self.k
+            ),
+            None => write!(f, "search_bm25({}, {}, {})?", self.type_arg, self.query, self.k),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SearchHybrid {
+    pub label: GenRef<String>,
+    pub query: GeneratedValue,
+    pub vec: VecData,
+    pub k: GeneratedValue,
+    /// How the rankings are fused, the default weighted blend if `None`
+    pub fusion: Option<FusionMethod>,
+}
+
+#[derive(Clone)]
+pub enum FusionMethod {
+    Weighted(GeneratedValue),
+    ReciprocalRank(Option<GeneratedValue>),
+}
+
+impl Display for FusionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FusionMethod::Weighted(alpha @ GeneratedValue::Primitive(_)) => {
+                write!(f, "HybridFusion::Weighted {{ alpha: {alpha} }}")
+            }
+            FusionMethod::Weighted(alpha) => {
+                write!(f, "HybridFusion::Weighted {{ alpha: {alpha} as f32 }}")
+            }
+            FusionMethod::ReciprocalRank(Some(k @ GeneratedValue::Primitive(_))) => {
+                write!(f, "HybridFusion::ReciprocalRank {{ k: {k} }}")
+            }
+            FusionMethod::ReciprocalRank(Some(k)) => {
+                write!(f, "HybridFusion::ReciprocalRank {{ k: {k} as f32 }}")
+            }
+            FusionMethod::ReciprocalRank(None) => {
+                write!(f, "HybridFusion::ReciprocalRank {{ k: DEFAULT_RRF_K }}")
+            }
+        }
+    }
+}
+
+impl Display for SearchHybrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let search = match self.vec {
+            VecData::Sparse { .. } => "hybrid_search_bm25_sparse",
+            _ => "hybrid_search_bm25",
+        };
+        let fusion = match &self.fusion {
+            Some(fusion) => fusion.to_string(),
+            None => "HybridFusion::default()".to_string(),
+        };
+        write!(
+            f,
+            "{search}({}, {}, {}, {}, {fusion})",
+            self.label, self.query, self.vec, self.k
+        )
     }
 }
 
@@ -147,11 +252,20 @@ impl Display for SourceStep {
             SourceStep::AddV(add_v) => write!(f, "{add_v}"),
             SourceStep::NFromID(n_from_id) => write!(f, "{n_from_id}"),
             SourceStep::NFromIndex(n_from_index) => write!(f, "{n_from_index}"),
+            SourceStep::NFromIndexRange(n_from_index_range) => write!(f, "{n_from_index_range}"),
+            SourceStep::NFromIndexPrefix(n_from_index_prefix) => {
+                write!(f, "{n_from_index_prefix}")
+            }
+            SourceStep::NFromCompositeIndex(n_from_composite_index) => {
+                write!(f, "{n_from_composite_index}")
+            }
             SourceStep::NFromType(n_from_type) => write!(f, "{n_from_type}"),
             SourceStep::EFromID(e_from_id) => write!(f, "{e_from_id}"),
             SourceStep::EFromType(e_from_type) => write!(f, "{e_from_type}"),
+            SourceStep::EFromIndex(e_from_index) => write!(f, "{e_from_index}"),
             SourceStep::SearchVector(search_vector) => write!(f, "{search_vector}"),
             SourceStep::SearchBM25(search_bm25) => write!(f, "{search_bm25}"),
+            SourceStep::SearchHybrid(search_hybrid) => write!(f, "{search_hybrid}"),
             SourceStep::Anonymous => write!(f, ""),
             SourceStep::Empty => panic!("Should not be empty"),
         }
@@ -163,30 +277,92 @@ pub struct SearchVector {
     pub label: GenRef<String>,
     pub vec: VecData,
     pub k: GeneratedValue,
+    pub radius: Option<GeneratedValue>,
+    pub ef: Option<GeneratedValue>,
+    pub candidates: Option<GeneratedValue>,
     pub pre_filter: Option<Vec<BoExp>>,
+    /// Threads of a search over an array of query vectors, `None` for a single query vector
+    pub threads: Option<GeneratedValue>,
+    /// Whether the vector type holds documents of several vectors, `vec` being the query vectors
+    pub multi: bool,
+}
+
+impl SearchVector {
+    /// The search params after the filter, which a search of sparse vectors has none of
+    fn params(&self) -> String {
+        let param = |value: &Option<GeneratedValue>| match value {
+            Some(value @ GeneratedValue::Primitive(_)) => format!("Some({value})"),
+            Some(value) => format!("Some({value} as usize)"),
+            None => "None".to_string(),
+        };
+        match (&self.vec, &self.ef, &self.candidates) {
+            (VecData::Sparse { .. }, _, _) => String::new(),
+            (_, None, None) => ", SearchParams::default()".to_string(),
+            (_, ef, candidates) => {
+                format!(", SearchParams::new({}, {})", param(ef), param(candidates))
+            }
+        }
+    }
+
+    /// The threads a search over an array of query vectors is split across, after its params
+    fn threads(&self) -> String {
+        match &self.threads {
+            Some(threads @ GeneratedValue::Primitive(_)) => format!(", {threads}"),
+            Some(threads) => format!(", {threads} as usize"),
+            None => String::new(),
+        }
+    }
+
+    /// The search method, its generics after the filter's, and its k or radius
+    fn search(&self) -> (&'static str, &'static str, String) {
+        if let VecData::Sparse { .. } = self.vec {
+            return ("search_sparse_v", ", _", self.k.to_string());
+        }
+        if self.multi {
+            return ("search_v_multi", ", _", self.k.to_string());
+        }
+        if self.threads.is_some() {
+            return ("search_v_batch", ", _", self.k.to_string());
+        }
+        match &self.radius {
+            Some(radius @ GeneratedValue::Primitive(_)) => ("search_v_radius", "", radius.to_string()),
+            Some(radius) => ("search_v_radius", "", format!("{radius} as f64")),
+            None => ("search_v", ", _", self.k.to_string()),
+        }
+    }
 }
 
 impl Display for SearchVector {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (search, generics, limit) = self.search();
         match &self.pre_filter {
+            // the filters read the vector as a traversal value, like the expression of a `WHERE`,
+            // and a filter that errors leaves the vector out
             Some(pre_filter) => write!(
                 f,
-                "search_v::<fn(&HVector, &RoTxn) -> bool, _>({}, {}, {}, Some(&[{}]))",
+                "{search}::<_{generics}>({}, {limit}, {}, Some(&[{}]){}{})",
                 self.vec,
-                self.k,
                 self.label,
                 pre_filter
                     .iter()
-                    .map(|f| format!("|v: &HVector, txn: &RoTxn| {f}"))
+                    .map(|f| format!(
+                        "|v: &HVector, txn: &RoTxn| {{
+                let val = &TraversalValue::Vector(v.clone());
+                (|| -> Result<bool, GraphError> {{ Ok({f}) }})().unwrap_or(false)
+            }}"
+                    ))
                     .collect::<Vec<_>>()
-                    .join(", ")
+                    .join(", "),
+                self.params(),
+                self.threads(),
             ),
             None => write!(
                 f,
-                "search_v::<fn(&HVector, &RoTxn) -> bool, _>({}, {}, {}, None)",
+                "{search}::<fn(&HVector, &RoTxn) -> bool{generics}>({}, {limit}, {}, None{}{})",
                 self.vec,
-                self.k,
                 self.label,
+                self.params(),
+                self.threads(),
             ),
         }
     }
@@ -205,3 +381,70 @@ impl Display for NFromIndex {
     }
 }
 
+
+#[derive(Clone)]
+pub struct NFromIndexRange {
+    pub index: GenRef<String>,
+    pub start: Bound<GeneratedValue>,
+    pub end: Bound<GeneratedValue>,
+    pub label: GenRef<String>,
+}
+
+impl Display for NFromIndexRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let write_bound = |bound: &Bound<GeneratedValue>| match bound {
+            Bound::Included(value) => format!("Bound::Included({value})"),
+            Bound::Excluded(value) => format!("Bound::Excluded({value})"),
+            Bound::Unbounded => "Bound::Unbounded".to_string(),
+        };
+        write!(
+            f,
+            "n_from_index_range({}, {}, {}, {})",
+            self.label,
+            self.index,
+            write_bound(&self.start),
+            write_bound(&self.end)
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct NFromIndexPrefix {
+    pub index: GenRef<String>,
+    pub prefix: GeneratedValue,
+    pub label: GenRef<String>,
+}
+
+impl Display for NFromIndexPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "n_from_index_prefix({}, {}, {})",
+            self.label, self.index, self.prefix
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct NFromCompositeIndex {
+    pub index: GenRef<String>,
+    /// Values of the looked up fields, in the order of the index's fields
+    pub keys: Vec<GeneratedValue>,
+    pub label: GenRef<String>,
+}
+
+impl Display for NFromCompositeIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "n_from_composite_index({}, {}, &[{}])",
+            self.label,
+            self.index,
+            self.keys
+                .iter()
+                .map(|key| format!("Value::from({key})"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
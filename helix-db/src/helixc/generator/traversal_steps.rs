@@ -50,6 +50,10 @@ impl Debug for TraversalType {
 pub enum ShouldCollect {
     ToVec,
     ToVal,
+    /// Like `ToVal` but returns the first error, failing the write transaction
+    TryToVal,
+    /// Like `ToVec` but returns the first error, failing the write transaction
+    TryToVec,
     No,
     Try,
 }
@@ -58,6 +62,8 @@ impl Display for ShouldCollect {
         match self {
             ShouldCollect::ToVec => write!(f, ".collect_to::<Vec<_>>()"),
             ShouldCollect::ToVal => write!(f, ".collect_to_obj()"),
+            ShouldCollect::TryToVal => write!(f, ".try_collect_to_obj()?"),
+            ShouldCollect::TryToVec => write!(f, ".try_collect_to::<Vec<_>>()?"),
             ShouldCollect::Try => write!(f, "?"),
             ShouldCollect::No => write!(f, ""),
         }
@@ -130,8 +136,9 @@ impl Display for Traversal {
                                                                              // scrappy
                 )?;
                 write!(f, "\n    .update({})", write_properties(properties))?;
-                write!(f, "\n    .collect_to_obj()")?;
-                write!(f, "}}")?;
+                // collected inside the block, before the write traversal borrowing `txn` ends
+                write!(f, "\n    {}", self.should_collect)?;
+                return write!(f, "}}");
             }
         }
         write!(f, "{}", self.should_collect)
@@ -373,9 +380,18 @@ impl Display for ShortestPath {
 pub struct SearchVectorStep {
     pub vec: VecData,
     pub k: GeneratedValue,
+    pub radius: Option<GeneratedValue>,
 }
 impl Display for SearchVectorStep {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "brute_force_search_v({}, {})", self.vec, self.k)
+        match &self.radius {
+            Some(radius @ GeneratedValue::Primitive(_)) => {
+                write!(f, "brute_force_search_v_radius({}, {})", self.vec, radius)
+            }
+            Some(radius) => {
+                write!(f, "brute_force_search_v_radius({}, {} as f64)", self.vec, radius)
+            }
+            None => write!(f, "brute_force_search_v({}, {})", self.vec, self.k),
+        }
     }
 }
@@ -116,6 +116,11 @@ pub enum VecData {
     //     model_name: Option<String>,
     // },
     Hoisted(String),
+    /// The indices and weights of a sparse vector
+    Sparse {
+        indices: GeneratedValue,
+        weights: GeneratedValue,
+    },
     Unknown,
 }
 
@@ -128,6 +133,7 @@ impl Display for VecData {
             //     None => write!(f, "&embed!(db, {data})"),
             // },
             VecData::Hoisted(ident) => write!(f, "&{ident}"),
+            VecData::Sparse { indices, weights } => write!(f, "{indices}, {weights}"),
             VecData::Unknown => panic!("Cannot convert to string, VecData is unknown"),
         }
     }
@@ -367,10 +373,17 @@ use heed3::RoTxn;
 use helix_macros::{handler, tool_call, mcp_handler, migration};
 use helix_db::{
     helix_engine::{
+        bm25::{
+            analyzer::TextAnalyzer,
+            bm25::{DEFAULT_RRF_K, HybridFusion},
+            query::TermExpansion,
+        },
         traversal_core::{
-            config::{Config, GraphConfig, VectorConfig},
+            config::{Config, GraphConfig, SecondaryIndex, TextField, VectorConfig, VectorIndex},
             ops::{
-                bm25::search_bm25::SearchBM25Adapter,
+                bm25::{
+                    hybrid_search_bm25::HybridSearchBM25Adapter, search_bm25::SearchBM25Adapter,
+                },
                 g::G,
                 in_::{in_::InAdapter, in_e::InEdgesAdapter, to_n::ToNAdapter, to_v::ToVAdapter},
                 out::{
@@ -380,9 +393,11 @@ use helix_db::{
                     add_e::{AddEAdapter, EdgeType},
                     add_n::AddNAdapter,
                     e_from_id::EFromIdAdapter,
+                    e_from_index::EFromIndexAdapter,
                     e_from_type::EFromTypeAdapter,
                     n_from_id::NFromIdAdapter,
                     n_from_index::NFromIndexAdapter,
+                    n_from_index_range::NFromIndexRangeAdapter,
                     n_from_type::NFromTypeAdapter,
                 },
                 util::{
@@ -398,7 +413,10 @@ use helix_db::{
                 traversal_value::{Traversable, TraversalValue},
             },
         types::GraphError,
-        vector_core::vector::HVector,
+        vector_core::{
+            vector::HVector, vector_core::SearchParams, vector_distance::DistanceMetric,
+            vector_encoding::VectorEncoding,
+        },
     },
     helix_gateway::{
         embedding_providers::embedding_providers::{EmbeddingModel, get_embedding_model},
@@ -425,6 +443,7 @@ use helix_db::{
 };
 use sonic_rs::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::ops::Bound;
 use std::sync::Arc;
 use std::time::Instant;
 use chrono::{DateTime, Utc};
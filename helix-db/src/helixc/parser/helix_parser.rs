@@ -2,7 +2,9 @@ use super::{
     location::{HasLoc, Loc},
     parser_methods::ParserError,
 };
-use crate::protocol::value::Value;
+use crate::{
+    helix_engine::storage_core::index_key::composite_index_name, protocol::value::Value,
+};
 use chrono::{DateTime, NaiveDate, Utc};
 use itertools::Itertools;
 use pest::{
@@ -15,6 +17,7 @@ use std::{
     collections::{HashMap, HashSet},
     fmt::{Debug, Display},
     io::Write,
+    ops::Bound,
 };
 
 #[derive(Parser)]
@@ -94,13 +97,48 @@ pub struct Schema {
 pub struct NodeSchema {
     pub name: (Loc, String),
     pub fields: Vec<Field>,
+    pub composite_indices: Vec<CompositeIndex>,
     pub loc: Loc,
 }
 
+/// An index over several fields of a node, e.g. `INDEX(tenant_id, external_id)`.
+#[derive(Debug, Clone)]
+pub struct CompositeIndex {
+    pub fields: Vec<(Loc, String)>,
+    pub unique: bool,
+    pub loc: Loc,
+}
+impl CompositeIndex {
+    /// Name of the secondary index backing this index.
+    pub fn name(&self) -> String {
+        composite_index_name(
+            &self
+                .fields
+                .iter()
+                .map(|(_, field)| field.as_str())
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct VectorSchema {
     pub name: String,
     pub fields: Vec<Field>,
+    /// The declared dimension, e.g. `1536` in `V::Doc(1536)`
+    pub dimension: Option<(Loc, usize)>,
+    /// The declared distance metric, e.g. `euclidean` in `V::Doc(metric: euclidean)`
+    pub metric: Option<(Loc, String)>,
+    /// The declared storage encoding, e.g. `int8` in `V::Doc(encoding: int8)`
+    pub encoding: Option<(Loc, String)>,
+    /// HNSW parameters of the type's index, e.g. `m: 32` in `V::Doc(m: 32, ef_search: 200)`
+    pub m: Option<usize>,
+    pub ef_construction: Option<usize>,
+    pub ef_search: Option<usize>,
+    /// Whether each item holds several vectors, e.g. `V::Passage(128, multi)`
+    pub multi: bool,
+    /// Whether the vectors are sparse, e.g. `V::Splade(sparse)`
+    pub sparse: bool,
     pub loc: Loc,
 }
 
@@ -175,6 +213,10 @@ pub struct ValueCast {
 #[derive(Debug, Clone)]
 pub struct Field {
     pub prefix: FieldPrefix,
+    /// Declared `TEXT`, the field's text is indexed for BM25 search
+    pub text: bool,
+    /// The analyzer of a `TEXT` field, e.g. `english` in `TEXT(english)`
+    pub text_analyzer: Option<(Loc, String)>,
     pub defaults: Option<DefaultValue>,
     pub name: String,
     pub field_type: FieldType,
@@ -208,12 +250,20 @@ pub enum DefaultValue {
 #[derive(Debug, Clone)]
 pub enum FieldPrefix {
     Index,
+    /// `UNIQUE INDEX`, an index that rejects a second node with the same value.
+    UniqueIndex,
+    /// `UNIQUE` without `INDEX`, rejected by the analyzer.
+    Unique,
     Optional,
     Empty,
 }
 impl FieldPrefix {
     pub fn is_indexed(&self) -> bool {
-        matches!(self, FieldPrefix::Index)
+        matches!(self, FieldPrefix::Index | FieldPrefix::UniqueIndex)
+    }
+
+    pub fn is_unique(&self) -> bool {
+        matches!(self, FieldPrefix::Unique | FieldPrefix::UniqueIndex)
     }
 }
 
@@ -497,8 +547,9 @@ pub enum ExpressionType {
     Not(Box<Expression>),
     And(Vec<Expression>),
     Or(Vec<Expression>),
-    SearchVector(SearchVector),
+    SearchVector(Box<SearchVector>),
     BM25Search(BM25Search),
+    HybridSearch(Box<HybridSearch>),
     Empty,
 }
 impl Debug for ExpressionType {
@@ -520,6 +571,7 @@ impl Debug for ExpressionType {
             ExpressionType::Or(exprs) => write!(f, "Or({exprs:?})"),
             ExpressionType::SearchVector(sv) => write!(f, "SearchVector({sv:?})"),
             ExpressionType::BM25Search(bm25) => write!(f, "BM25Search({bm25:?})"),
+            ExpressionType::HybridSearch(hs) => write!(f, "HybridSearch({hs:?})"),
             ExpressionType::Empty => write!(f, "Empty"),
         }
     }
@@ -543,6 +595,7 @@ impl Display for ExpressionType {
             ExpressionType::Or(exprs) => write!(f, "Or({exprs:?})"),
             ExpressionType::SearchVector(sv) => write!(f, "SearchVector({sv:?})"),
             ExpressionType::BM25Search(bm25) => write!(f, "BM25Search({bm25:?})"),
+            ExpressionType::HybridSearch(hs) => write!(f, "HybridSearch({hs:?})"),
             ExpressionType::Empty => write!(f, "Empty"),
         }
     }
@@ -573,7 +626,7 @@ pub enum StartNode {
         edge_type: String,
         ids: Option<Vec<IdType>>,
     },
-    SearchVector(SearchVector),
+    SearchVector(Box<SearchVector>),
     Identifier(String),
     Anonymous,
 }
@@ -677,7 +730,7 @@ pub enum GraphStepType {
     InE(String),
 
     ShortestPath(ShortestPath),
-    SearchVector(SearchVector),
+    SearchVector(Box<SearchVector>),
 }
 impl GraphStep {
     pub fn get_item_type(&self) -> Option<String> {
@@ -723,6 +776,7 @@ pub enum VectorData {
     Vector(Vec<f64>),
     Identifier(String),
     Embed(Embed),
+    Sparse(SparseVectorData),
 }
 
 #[derive(Debug, Clone)]
@@ -731,6 +785,15 @@ pub struct Embed {
     pub value: EvaluatesToString,
 }
 
+/// A sparse vector given as parameters of its indices and their weights,
+/// e.g. `Sparse(indices, weights)`
+#[derive(Debug, Clone)]
+pub struct SparseVectorData {
+    pub loc: Loc,
+    pub indices: String,
+    pub weights: String,
+}
+
 #[derive(Debug, Clone)]
 pub enum EvaluatesToString {
     Identifier(String),
@@ -743,6 +806,13 @@ pub struct SearchVector {
     pub vector_type: Option<String>,
     pub data: Option<VectorData>,
     pub k: Option<EvaluatesToNumber>,
+    /// Searches within a distance instead of for the k nearest, e.g. `SearchV<Doc>(vec, radius: 0.2)`
+    pub radius: Option<EvaluatesToNumber>,
+    /// Per query search tuning, e.g. `ef: 200` in `SearchV<Doc>(vec, 10, ef: 200)`
+    pub ef: Option<EvaluatesToNumber>,
+    pub candidates: Option<EvaluatesToNumber>,
+    /// Threads a search over an array of query vectors is split across, e.g. `threads: 4`
+    pub threads: Option<EvaluatesToNumber>,
     pub pre_filter: Option<Box<Expression>>,
 }
 
@@ -752,6 +822,37 @@ pub struct BM25Search {
     pub type_arg: Option<String>,
     pub data: Option<ValueType>,
     pub k: Option<EvaluatesToNumber>,
+    /// How the words of the query are expanded, e.g. `fuzzy: 1` in
+    /// `SearchBM25<Doc>(text, 10, fuzzy: 1)`
+    pub expansion: Option<Expansion>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expansion {
+    /// Matches the terms each word prefixes
+    Prefix,
+    /// Matches the terms within `distance` edits of each word, or the most allowed if not set
+    Fuzzy { loc: Loc, distance: Option<u32> },
+}
+
+/// `SearchHybrid<Doc>(text, vector, k)`, searching both the BM25 docs and the vectors of a type
+#[derive(Debug, Clone)]
+pub struct HybridSearch {
+    pub loc: Loc,
+    pub vector_type: Option<String>,
+    pub text: Option<ValueType>,
+    pub data: Option<VectorData>,
+    pub k: Option<EvaluatesToNumber>,
+    /// How the two rankings are combined, e.g. `alpha: 0.7` or `rrf: 60`
+    pub fusion: Option<Fusion>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Fusion {
+    /// Weights the BM25 scores by alpha and the vector similarities by `1 - alpha`
+    Weighted(EvaluatesToNumber),
+    /// Reciprocal rank fusion, with its k if given
+    ReciprocalRank(Option<EvaluatesToNumber>),
 }
 
 #[derive(Debug, Clone)]
@@ -822,6 +923,16 @@ pub enum IdType {
         value: Box<ValueType>,
         loc: Loc,
     },
+    ByIndexRange {
+        index: Box<IdType>,
+        range: Box<IndexRange>,
+        loc: Loc,
+    },
+    /// A lookup on several fields of a composite index, e.g. `N<Account>({tenant_id: t, external_id: e})`
+    ByCompositeIndex {
+        fields: Vec<(IdType, ValueType)>,
+        loc: Loc,
+    },
 }
 impl Display for IdType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -833,10 +944,34 @@ impl Display for IdType {
                 value: _,
                 loc: _,
             } => write!(f, "{index}"),
+            IdType::ByIndexRange {
+                index,
+                range: _,
+                loc: _,
+            } => write!(f, "{index}"),
+            IdType::ByCompositeIndex { fields, loc: _ } => write!(
+                f,
+                "{}",
+                fields
+                    .iter()
+                    .map(|(field, _)| field.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
         }
     }
 }
 
+/// A range or prefix lookup on a secondary index, e.g. `N<Event>({start: GTE(x)})`
+#[derive(Debug, Clone)]
+pub enum IndexRange {
+    Bounds {
+        start: Bound<ValueType>,
+        end: Bound<ValueType>,
+    },
+    Prefix(ValueType),
+}
+
 #[derive(Debug, Clone)]
 pub enum ValueType {
     Literal {
@@ -912,6 +1047,12 @@ impl From<IdType> for String {
                 value: _,
                 loc: _,
             } => String::from(*index),
+            IdType::ByIndexRange {
+                index,
+                range: _,
+                loc: _,
+            } => String::from(*index),
+            IdType::ByCompositeIndex { .. } => id_type.to_string(),
         }
     }
 }
@@ -1111,10 +1252,17 @@ impl HelixParser {
     ) -> Result<NodeSchema, ParserError> {
         let mut pairs = pair.clone().into_inner();
         let name = pairs.next().unwrap().as_str().to_string();
-        let fields = self.parse_node_body(pairs.next().unwrap())?;
+        let body = pairs.next().unwrap();
+        let fields = self.parse_node_body(body.clone())?;
+        let composite_indices = body
+            .into_inner()
+            .filter(|p| p.as_rule() == Rule::composite_index)
+            .map(|p| self.parse_composite_index(p))
+            .collect();
         Ok(NodeSchema {
             name: (pair.loc(), name),
             fields,
+            composite_indices,
             loc: pair.loc_with_filepath(filepath),
         })
     }
@@ -1126,10 +1274,67 @@ impl HelixParser {
     ) -> Result<VectorSchema, ParserError> {
         let mut pairs = pair.clone().into_inner();
         let name = pairs.next().unwrap().as_str().to_string();
-        let fields = self.parse_node_body(pairs.next().unwrap())?;
+        let mut fields = Vec::new();
+        let mut dimension = None;
+        let mut metric = None;
+        let mut encoding = None;
+        let mut multi = false;
+        let mut sparse = false;
+        let (mut m, mut ef_construction, mut ef_search) = (None, None, None);
+        for p in pairs {
+            match p.as_rule() {
+                Rule::vector_args => {
+                    for arg in p.into_inner().flat_map(|arg| arg.into_inner()) {
+                        match arg.as_rule() {
+                            Rule::dimension_arg => {
+                                let value = arg.into_inner().next().unwrap();
+                                let size = value.as_str().parse().map_err(|_| {
+                                    ParserError::from("Invalid dimension for vector type")
+                                })?;
+                                dimension = Some((value.loc(), size));
+                            }
+                            Rule::metric_arg => {
+                                let value = arg.into_inner().next().unwrap();
+                                metric = Some((value.loc(), value.as_str().to_string()));
+                            }
+                            Rule::encoding_arg => {
+                                let value = arg.into_inner().next().unwrap();
+                                encoding = Some((value.loc(), value.as_str().to_string()));
+                            }
+                            Rule::multi_arg => multi = true,
+                            Rule::sparse_arg => sparse = true,
+                            Rule::hnsw_arg => {
+                                let mut inner = arg.into_inner();
+                                let param = inner.next().unwrap().as_str();
+                                let value = inner.next().unwrap().as_str().parse().map_err(|_| {
+                                    ParserError::from(format!("Invalid value for {param}"))
+                                })?;
+                                match param {
+                                    "m" => m = Some(value),
+                                    "ef_construction" => ef_construction = Some(value),
+                                    "ef_search" => ef_search = Some(value),
+                                    _ => unreachable!(),
+                                }
+                            }
+                            _ => unreachable!(),
+                        }
+                    }
+                }
+                Rule::vector_body => fields = self.parse_node_body(p)?,
+                _ => unreachable!(),
+            }
+        }
         Ok(VectorSchema {
             name,
             fields,
+            dimension,
+            metric,
+            encoding,
+            m,
+            ef_construction,
+            ef_search,
+            multi,
+            sparse,
             loc: pair.loc_with_filepath(filepath),
         })
     }
@@ -1147,6 +1352,24 @@ impl HelixParser {
             .collect::<Result<Vec<_>, _>>()
     }
 
+    fn parse_composite_index(&self, pair: Pair<Rule>) -> CompositeIndex {
+        let loc = pair.loc();
+        let mut unique = false;
+        let mut fields = Vec::new();
+        for p in pair.into_inner() {
+            match p.as_rule() {
+                Rule::unique => unique = true,
+                Rule::identifier => fields.push((p.loc(), p.as_str().to_string())),
+                _ => {}
+            }
+        }
+        CompositeIndex {
+            fields,
+            unique,
+            loc,
+        }
+    }
+
     fn parse_migration_def(
         &self,
         pair: Pair<Rule>,
@@ -1498,8 +1721,18 @@ impl HelixParser {
 
     fn parse_field_def(&self, pair: Pair<Rule>) -> Result<Field, ParserError> {
         let mut pairs = pair.clone().into_inner();
-        // structure is index? ~ identifier ~ ":" ~ param_type
+        // structure is unique? ~ index? ~ text? ~ identifier ~ ":" ~ param_type
         let prefix: FieldPrefix = match pairs.clone().next().unwrap().as_rule() {
+            Rule::unique => {
+                pairs.next().unwrap();
+                match pairs.clone().next().unwrap().as_rule() {
+                    Rule::index => {
+                        pairs.next().unwrap();
+                        FieldPrefix::UniqueIndex
+                    }
+                    _ => FieldPrefix::Unique,
+                }
+            }
             Rule::index => {
                 pairs.next().unwrap();
                 FieldPrefix::Index
@@ -1510,6 +1743,17 @@ impl HelixParser {
             // }
             _ => FieldPrefix::Empty,
         };
+        let (text, text_analyzer) = match pairs.peek() {
+            Some(p) if p.as_rule() == Rule::text => {
+                pairs.next();
+                let analyzer = p
+                    .into_inner()
+                    .find(|p| p.as_rule() == Rule::identifier)
+                    .map(|p| (p.loc(), p.as_str().to_string()));
+                (true, analyzer)
+            }
+            _ => (false, None),
+        };
         let name = pairs.next().unwrap().as_str().to_string();
 
         let field_type = self.parse_field_type(
@@ -1521,6 +1765,8 @@ impl HelixParser {
 
         Ok(Field {
             prefix,
+            text,
+            text_analyzer,
             defaults,
             name,
             field_type,
@@ -1726,6 +1972,25 @@ impl HelixParser {
             }
         };
         let k = pairs.next().unwrap().as_str().to_string();
+        let expansion = match pairs.next().and_then(|p| p.into_inner().next()) {
+            Some(method) => Some(match method.as_rule() {
+                Rule::expansion_prefix => Expansion::Prefix,
+                _ => Expansion::Fuzzy {
+                    loc: method.loc(),
+                    distance: method
+                        .into_inner()
+                        .next()
+                        .map(|distance| {
+                            distance
+                                .as_str()
+                                .parse::<u32>()
+                                .map_err(|_| ParserError::from("Invalid value for fuzzy"))
+                        })
+                        .transpose()?,
+                },
+            }),
+            None => None,
+        };
         Ok(BM25Search {
             loc: pair.loc(),
             type_arg: Some(vector_type),
@@ -1734,6 +1999,111 @@ impl HelixParser {
                 loc: pair.loc(),
                 value: EvaluatesToNumberType::U32(k.parse::<u32>().unwrap()),
             }),
+            expansion,
+        })
+    }
+
+    fn parse_hybrid_search(&self, pair: Pair<Rule>) -> Result<HybridSearch, ParserError> {
+        let mut vector_type = None;
+        let mut text = None;
+        let mut data = None;
+        let mut k = None;
+        let mut fusion = None;
+        for p in pair.clone().into_inner() {
+            match p.as_rule() {
+                Rule::identifier_upper => {
+                    vector_type = Some(p.as_str().to_string());
+                }
+                Rule::string_literal => {
+                    text = Some(ValueType::Literal {
+                        value: Value::String(p.as_str().to_string()),
+                        loc: p.loc(),
+                    });
+                }
+                // the text comes before the vector data, and k after it
+                Rule::identifier if data.is_none() => {
+                    text = Some(ValueType::Identifier {
+                        value: p.as_str().to_string(),
+                        loc: p.loc(),
+                    });
+                }
+                Rule::vector_data => {
+                    data = Some(self.parse_vector_data(p, "SearchHybrid")?);
+                }
+                Rule::integer => {
+                    k = Some(EvaluatesToNumber {
+                        loc: p.loc(),
+                        value: EvaluatesToNumberType::I32(
+                            p.as_str()
+                                .parse::<i32>()
+                                .map_err(|_| ParserError::from("Invalid integer value"))?,
+                        ),
+                    });
+                }
+                Rule::identifier => {
+                    k = Some(EvaluatesToNumber {
+                        loc: p.loc(),
+                        value: EvaluatesToNumberType::Identifier(p.as_str().to_string()),
+                    });
+                }
+                Rule::hybrid_fusion => {
+                    let method = p.into_inner().next().unwrap();
+                    let value = method.clone().into_inner().next();
+                    fusion = Some(match method.as_rule() {
+                        Rule::fusion_alpha => {
+                            let value = value.unwrap();
+                            Fusion::Weighted(EvaluatesToNumber {
+                                loc: value.loc(),
+                                value: match value.as_rule() {
+                                    Rule::identifier => EvaluatesToNumberType::Identifier(
+                                        value.as_str().to_string(),
+                                    ),
+                                    _ => EvaluatesToNumberType::F64(
+                                        value.as_str().parse::<f64>().map_err(|_| {
+                                            ParserError::from("Invalid value for alpha")
+                                        })?,
+                                    ),
+                                },
+                            })
+                        }
+                        _ => Fusion::ReciprocalRank(
+                            value
+                                .map(|value| -> Result<EvaluatesToNumber, ParserError> {
+                                    Ok(EvaluatesToNumber {
+                                        loc: value.loc(),
+                                        value: match value.as_rule() {
+                                            Rule::identifier => EvaluatesToNumberType::Identifier(
+                                                value.as_str().to_string(),
+                                            ),
+                                            _ => EvaluatesToNumberType::I32(
+                                                value.as_str().parse::<i32>().map_err(|_| {
+                                                    ParserError::from("Invalid value for rrf")
+                                                })?,
+                                            ),
+                                        },
+                                    })
+                                })
+                                .transpose()?,
+                        ),
+                    });
+                }
+                _ => {
+                    return Err(ParserError::from(format!(
+                        "Unexpected rule in SearchHybrid: {:?} => {:?}",
+                        p.as_rule(),
+                        p,
+                    )));
+                }
+            }
+        }
+
+        Ok(HybridSearch {
+            loc: pair.loc(),
+            vector_type,
+            text,
+            data,
+            k,
+            fusion,
         })
     }
 
@@ -1847,7 +2217,10 @@ impl HelixParser {
                             data = Some(VectorData::Identifier(p.as_str().to_string()));
                         }
                         Rule::vec_literal => {
-                            data = Some(VectorData::Vector(self.parse_vec_literal(p)?));
+                            data = Some(VectorData::Vector(self.parse_vec_literal(vector_data)?));
+                        }
+                        Rule::sparse_vector => {
+                            data = Some(VectorData::Sparse(self.parse_sparse_vector(vector_data)));
                         }
                         Rule::embed_method => {
                             data = Some(VectorData::Embed(Embed {
@@ -1919,6 +2292,8 @@ impl HelixParser {
         let mut vector_type = None;
         let mut data = None;
         let mut k = None;
+        let mut radius = None;
+        let (mut ef, mut candidates, mut threads) = (None, None, None);
         let mut pre_filter = None;
         for p in pair.clone().into_inner() {
             match p.as_rule() {
@@ -1931,7 +2306,10 @@ impl HelixParser {
                             data = Some(VectorData::Identifier(p.as_str().to_string()));
                         }
                         Rule::vec_literal => {
-                            data = Some(VectorData::Vector(self.parse_vec_literal(p)?));
+                            data = Some(VectorData::Vector(self.parse_vec_literal(vector_data)?));
+                        }
+                        Rule::sparse_vector => {
+                            data = Some(VectorData::Sparse(self.parse_sparse_vector(vector_data)));
                         }
                         Rule::embed_method => {
                             data = Some(VectorData::Embed(Embed {
@@ -1995,6 +2373,45 @@ impl HelixParser {
                         value: EvaluatesToNumberType::Identifier(p.as_str().to_string()),
                     });
                 }
+                Rule::search_radius => {
+                    let value = p.into_inner().next().unwrap();
+                    radius = Some(EvaluatesToNumber {
+                        loc: value.loc(),
+                        value: match value.as_rule() {
+                            Rule::identifier => {
+                                EvaluatesToNumberType::Identifier(value.as_str().to_string())
+                            }
+                            _ => EvaluatesToNumberType::F64(
+                                value
+                                    .as_str()
+                                    .parse::<f64>()
+                                    .map_err(|_| ParserError::from("Invalid value for radius"))?,
+                            ),
+                        },
+                    });
+                }
+                Rule::search_param => {
+                    let mut inner = p.into_inner();
+                    let name = inner.next().unwrap().as_str();
+                    let value = inner.next().unwrap();
+                    let value = EvaluatesToNumber {
+                        loc: value.loc(),
+                        value: match value.as_rule() {
+                            Rule::integer => EvaluatesToNumberType::I32(
+                                value.as_str().parse::<i32>().map_err(|_| {
+                                    ParserError::from(format!("Invalid value for {name}"))
+                                })?,
+                            ),
+                            _ => EvaluatesToNumberType::Identifier(value.as_str().to_string()),
+                        },
+                    };
+                    match name {
+                        "ef" => ef = Some(value),
+                        "candidates" => candidates = Some(value),
+                        "threads" => threads = Some(value),
+                        _ => unreachable!(),
+                    }
+                }
                 Rule::pre_filter => {
                     pre_filter = Some(Box::new(self.parse_expression(p)?));
                 }
@@ -2013,6 +2430,10 @@ impl HelixParser {
             vector_type,
             data,
             k,
+            radius,
+            ef,
+            candidates,
+            threads,
             pre_filter,
         })
     }
@@ -2030,6 +2451,48 @@ impl HelixParser {
         Ok(vec)
     }
 
+    fn parse_vector_data(&self, pair: Pair<Rule>, step: &str) -> Result<VectorData, ParserError> {
+        let vector_data = pair.clone().into_inner().next().ok_or_else(|| {
+            ParserError::from(format!(
+                "Unexpected rule in {step}: {:?} => {:?}",
+                pair.as_rule(),
+                pair,
+            ))
+        })?;
+        match vector_data.as_rule() {
+            Rule::identifier => Ok(VectorData::Identifier(vector_data.as_str().to_string())),
+            Rule::vec_literal => Ok(VectorData::Vector(self.parse_vec_literal(vector_data)?)),
+            Rule::sparse_vector => Ok(VectorData::Sparse(self.parse_sparse_vector(vector_data))),
+            Rule::embed_method => {
+                let inner = vector_data.clone().into_inner().next().unwrap();
+                Ok(VectorData::Embed(Embed {
+                    loc: vector_data.loc(),
+                    value: match inner.as_rule() {
+                        Rule::identifier => {
+                            EvaluatesToString::Identifier(inner.as_str().to_string())
+                        }
+                        _ => EvaluatesToString::StringLiteral(inner.as_str().to_string()),
+                    },
+                }))
+            }
+            _ => Err(ParserError::from(format!(
+                "Unexpected rule in {step}: {:?} => {:?}",
+                vector_data.as_rule(),
+                vector_data,
+            ))),
+        }
+    }
+
+    fn parse_sparse_vector(&self, pair: Pair<Rule>) -> SparseVectorData {
+        let loc = pair.loc();
+        let mut inner = pair.into_inner();
+        SparseVectorData {
+            loc,
+            indices: inner.next().unwrap().as_str().to_string(),
+            weights: inner.next().unwrap().as_str().to_string(),
+        }
+    }
+
     fn parse_add_node(&self, pair: Pair<Rule>) -> Result<AddNode, ParserError> {
         let mut node_type = None;
         let mut fields = None;
@@ -2441,7 +2904,7 @@ impl HelixParser {
             }),
             Rule::search_vector => Ok(Expression {
                 loc: pair.loc(),
-                expr: ExpressionType::SearchVector(self.parse_search_vector(pair)?),
+                expr: ExpressionType::SearchVector(Box::new(self.parse_search_vector(pair)?)),
             }),
             Rule::none => Ok(Expression {
                 loc: pair.loc(),
@@ -2451,6 +2914,10 @@ impl HelixParser {
                 loc: pair.loc(),
                 expr: ExpressionType::BM25Search(self.parse_bm25_search(pair)?),
             }),
+            Rule::search_hybrid => Ok(Expression {
+                loc: pair.loc(),
+                expr: ExpressionType::HybridSearch(Box::new(self.parse_hybrid_search(pair)?)),
+            }),
             _ => Err(ParserError::from(format!(
                 "Unexpected expression type: {:?}",
                 pair.as_rule()
@@ -2458,6 +2925,141 @@ impl HelixParser {
         }
     }
 
+    /// Parses a `{field: value}` secondary index lookup of a node or edge source step.
+    fn parse_by_index(&self, p: Pair<Rule>) -> Result<IdType, ParserError> {
+        let mut pairs: Pairs<'_, Rule> = p.clone().into_inner();
+        let index = match pairs.next().unwrap().clone().into_inner().next() {
+            Some(id) => match id.as_rule() {
+                Rule::identifier => IdType::Identifier {
+                    value: id.as_str().to_string(),
+                    loc: id.loc(),
+                },
+                Rule::string_literal => IdType::Literal {
+                    value: id.as_str().to_string(),
+                    loc: id.loc(),
+                },
+                other => {
+                    panic!("Should be identifier or string literal: {other:?}")
+                }
+            },
+            None => return Err(ParserError::from("Missing index")),
+        };
+        let value = pairs.next().unwrap();
+        Ok(match value.as_rule() {
+            Rule::index_range => IdType::ByIndexRange {
+                index: Box::new(index),
+                range: Box::new(self.parse_index_range(value)?),
+                loc: p.loc(),
+            },
+            _ => IdType::ByIndex {
+                index: Box::new(index),
+                value: Box::new(self.parse_index_value(value.into_inner().next().unwrap())?),
+                loc: p.loc(),
+            },
+        })
+    }
+
+    /// Parses one `field: value` pair of a `by_composite_index` lookup.
+    fn parse_composite_index_arg(
+        &self,
+        pair: Pair<Rule>,
+    ) -> Result<(IdType, ValueType), ParserError> {
+        let mut pairs = pair.into_inner();
+        let field = match pairs.next().and_then(|p| p.into_inner().next()) {
+            Some(id) => match id.as_rule() {
+                Rule::identifier => IdType::Identifier {
+                    value: id.as_str().to_string(),
+                    loc: id.loc(),
+                },
+                Rule::string_literal => IdType::Literal {
+                    value: id.as_str().to_string(),
+                    loc: id.loc(),
+                },
+                other => {
+                    return Err(ParserError::from(format!(
+                        "Index field should be an identifier or string literal, got {other:?}"
+                    )));
+                }
+            },
+            None => return Err(ParserError::from("Missing index")),
+        };
+        let value = match pairs.next().and_then(|p| p.into_inner().next()) {
+            Some(value) => self.parse_index_value(value)?,
+            None => return Err(ParserError::from("Missing index value")),
+        };
+        Ok((field, value))
+    }
+
+    /// Parses the bounds of a range or prefix lookup in `by_index`.
+    fn parse_index_range(&self, pair: Pair<Rule>) -> Result<IndexRange, ParserError> {
+        let op = pair.into_inner().next().unwrap();
+        let rule = op.as_rule();
+        let mut values = op.into_inner();
+        let mut next_value = || match values.next() {
+            Some(value) => self.parse_index_value(value.into_inner().next().unwrap()),
+            None => Err(ParserError::from("Missing index range value")),
+        };
+        Ok(match rule {
+            Rule::index_gte => IndexRange::Bounds {
+                start: Bound::Included(next_value()?),
+                end: Bound::Unbounded,
+            },
+            Rule::index_gt => IndexRange::Bounds {
+                start: Bound::Excluded(next_value()?),
+                end: Bound::Unbounded,
+            },
+            Rule::index_lte => IndexRange::Bounds {
+                start: Bound::Unbounded,
+                end: Bound::Included(next_value()?),
+            },
+            Rule::index_lt => IndexRange::Bounds {
+                start: Bound::Unbounded,
+                end: Bound::Excluded(next_value()?),
+            },
+            Rule::index_between => IndexRange::Bounds {
+                start: Bound::Included(next_value()?),
+                end: Bound::Included(next_value()?),
+            },
+            Rule::index_prefix => IndexRange::Prefix(next_value()?),
+            other => {
+                return Err(ParserError::from(format!(
+                    "Unexpected rule in index range: {other:?}"
+                )));
+            }
+        })
+    }
+
+    /// Parses the value looked up in a secondary index.
+    fn parse_index_value(&self, val: Pair<Rule>) -> Result<ValueType, ParserError> {
+        Ok(match val.as_rule() {
+            Rule::identifier => ValueType::Identifier {
+                value: val.as_str().to_string(),
+                loc: val.loc(),
+            },
+            Rule::string_literal => ValueType::Literal {
+                loc: val.loc(),
+                value: Value::from(self.parse_string_literal(val)?),
+            },
+            Rule::integer => ValueType::Literal {
+                value: Value::from(val.as_str().parse::<i64>().unwrap()),
+                loc: val.loc(),
+            },
+            Rule::float => ValueType::Literal {
+                value: Value::from(val.as_str().parse::<f64>().unwrap()),
+                loc: val.loc(),
+            },
+            Rule::boolean => ValueType::Literal {
+                value: Value::from(val.as_str().parse::<bool>().unwrap()),
+                loc: val.loc(),
+            },
+            other => {
+                return Err(ParserError::from(format!(
+                    "Index value should be an identifier or literal, got {other:?}"
+                )));
+            }
+        })
+    }
+
     fn parse_string_literal(&self, pair: Pair<Rule>) -> Result<String, ParserError> {
         let inner = pair
             .into_inner()
@@ -2531,68 +3133,16 @@ impl HelixParser {
                                     .collect::<Vec<_>>(),
                             );
                         }
-                        Rule::by_index => {
-                            ids = Some({
-                                let mut pairs: Pairs<'_, Rule> = p.clone().into_inner();
-                                let index = match pairs.next().unwrap().clone().into_inner().next()
-                                {
-                                    Some(id) => match id.as_rule() {
-                                        Rule::identifier => IdType::Identifier {
-                                            value: id.as_str().to_string(),
-                                            loc: id.loc(),
-                                        },
-                                        Rule::string_literal => IdType::Literal {
-                                            value: id.as_str().to_string(),
-                                            loc: id.loc(),
-                                        },
-                                        other => {
-                                            panic!(
-                                                "Should be identifier or string literal: {other:?}"
-                                            )
-                                        }
-                                    },
-                                    None => return Err(ParserError::from("Missing index")),
-                                };
-                                let value = match pairs.next().unwrap().into_inner().next() {
-                                    Some(val) => match val.as_rule() {
-                                        Rule::identifier => ValueType::Identifier {
-                                            value: val.as_str().to_string(),
-                                            loc: val.loc(),
-                                        },
-                                        Rule::string_literal => ValueType::Literal {
-                                            value: Value::from(val.as_str()),
-                                            loc: val.loc(),
-                                        },
-                                        Rule::integer => ValueType::Literal {
-                                            value: Value::from(
-                                                val.as_str().parse::<i64>().unwrap(),
-                                            ),
-                                            loc: val.loc(),
-                                        },
-                                        Rule::float => ValueType::Literal {
-                                            value: Value::from(
-                                                val.as_str().parse::<f64>().unwrap(),
-                                            ),
-                                            loc: val.loc(),
-                                        },
-                                        Rule::boolean => ValueType::Literal {
-                                            value: Value::from(
-                                                val.as_str().parse::<bool>().unwrap(),
-                                            ),
-                                            loc: val.loc(),
-                                        },
-                                        _ => {
-                                            panic!("Should be identifier or string literal")
-                                        }
-                                    },
-                                    _ => unreachable!(),
-                                };
-                                vec![IdType::ByIndex {
-                                    index: Box::new(index),
-                                    value: Box::new(value),
-                                    loc: p.loc(),
-                                }]
-                            })
+                        Rule::by_index => ids = Some(vec![self.parse_by_index(p)?]),
+                        Rule::by_composite_index => {
+                            ids = Some(vec![IdType::ByCompositeIndex {
+                                fields: p
+                                    .clone()
+                                    .into_inner()
+                                    .map(|arg| self.parse_composite_index_arg(arg))
+                                    .collect::<Result<Vec<_>, _>>()?,
+                                loc: p.loc(),
+                            }])
                         }
                         _ => unreachable!(),
                     }
@@ -2631,13 +3181,14 @@ impl HelixParser {
                                     .collect::<Vec<_>>(),
                             );
                         }
+                        Rule::by_index => ids = Some(vec![self.parse_by_index(p)?]),
                         _ => unreachable!(),
                     }
                 }
                 Ok(StartNode::Edge { edge_type, ids })
             }
             Rule::identifier => Ok(StartNode::Identifier(pair.as_str().to_string())),
-            Rule::search_vector => Ok(StartNode::SearchVector(self.parse_search_vector(pair)?)),
+            Rule::search_vector => Ok(StartNode::SearchVector(Box::new(self.parse_search_vector(pair)?))),
             _ => Ok(StartNode::Anonymous),
         }
     }
@@ -2869,7 +3420,7 @@ impl HelixParser {
             }
             Rule::search_vector => GraphStep {
                 loc: pair.loc(),
-                step: GraphStepType::SearchVector(self.parse_search_vector(pair).unwrap()),
+                step: GraphStepType::SearchVector(Box::new(self.parse_search_vector(pair).unwrap())),
             },
             _ => {
                 unreachable!()
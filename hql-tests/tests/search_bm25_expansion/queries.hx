@@ -0,0 +1,16 @@
+N::Article {
+    TEXT title: String,
+    TEXT(english) body: String,
+}
+
+QUERY searchArticlesByPrefix(text: String) =>
+    res <- SearchBM25<Article>(text, 10, prefix)
+    RETURN res
+
+QUERY searchArticlesFuzzy(text: String) =>
+    res <- SearchBM25<Article>(text, 10, fuzzy)
+    RETURN res
+
+QUERY searchArticlesFuzzyOneEdit(text: String) =>
+    res <- SearchBM25<Article>(text, 10, fuzzy: 1)
+    RETURN res
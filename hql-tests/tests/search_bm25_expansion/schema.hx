@@ -0,0 +1,32 @@
+// N::Doc {
+//     content: String
+// }
+//     
+// V::Embedding {
+//     chunk: String
+// }
+// 
+// N::Chunk {
+//     content: String
+// }
+// 
+// E::EmbeddingOf {
+//     From: Doc,
+//     To: Embedding, 
+//     Properties: {
+//     }
+// }
+
+// N::User {
+//     name: String,
+//     age: I32
+// }
+// 
+// E::Knows {
+//     From: User,
+//     To: User,
+//     Properties: {
+//         since: I32,
+//     }
+// }
+
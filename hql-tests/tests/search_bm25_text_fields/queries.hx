@@ -0,0 +1,17 @@
+N::Article {
+    TEXT title: String,
+    TEXT(english) body: String,
+    views: I32,
+}
+
+N::Author {
+    name: String,
+}
+
+QUERY searchArticles(text: String) =>
+    res <- SearchBM25<Article>(text, 10)
+    RETURN res
+
+QUERY addArticle(title: String, body: String) =>
+    article <- AddN<Article>({title: title, body: body, views: 0})
+    RETURN article